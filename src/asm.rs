@@ -0,0 +1,5 @@
+//! Turning bytecode into mnemonics and back, and the control flow of it.
+
+pub mod assembler;
+pub mod cfg;
+pub mod disassembler;
@@ -0,0 +1,398 @@
+//! Assembling mnemonic text into legacy bytecode.
+//!
+//! The text is a whitespace separated list of
+//! - mnemonics, e.g. `ADD`, in any case,
+//! - `PUSH1` to `PUSH32` with a number or a label, left padded to the size,
+//! - `PUSH` with a number or a label, sized to fit it, `PUSH 0` is `PUSH0`,
+//! - `name:` labels, standing for the offset they are at,
+//! - `0x` prefixed hex, copied into the code as is.
+//!
+//! Numbers are decimal or `0x` prefixed hex, `;` starts a comment to the end of the line.
+
+use std::{collections::HashMap, fmt};
+
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use crate::interpreter::opcodes::{self, Opcode};
+
+pub type Result<T> = std::result::Result<T, AsmError>;
+
+/// A error and the line, from 1, it is on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AsmError {
+    UnknownMnemonic {
+        line: usize,
+        token: String,
+    },
+    MissingOperand {
+        line: usize,
+        mnemonic: String,
+    },
+    InvalidNumber {
+        line: usize,
+        token: String,
+    },
+    /// The value does not fit the size of the PUSH.
+    ValueTooLarge {
+        line: usize,
+        token: String,
+    },
+    UnknownLabel {
+        line: usize,
+        label: String,
+    },
+    DuplicateLabel {
+        line: usize,
+        label: String,
+    },
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownMnemonic { line, token } => {
+                write!(f, "line {line}: unknown mnemonic {token}")
+            }
+            Self::MissingOperand { line, mnemonic } => {
+                write!(f, "line {line}: {mnemonic} needs a value")
+            }
+            Self::InvalidNumber { line, token } => write!(f, "line {line}: invalid number {token}"),
+            Self::ValueTooLarge { line, token } => {
+                write!(f, "line {line}: {token} does not fit the push")
+            }
+            Self::UnknownLabel { line, label } => write!(f, "line {line}: unknown label {label}"),
+            Self::DuplicateLabel { line, label } => {
+                write!(f, "line {line}: label {label} defined twice")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Operand {
+    Value(U256),
+    Label(String),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Item {
+    Op(u8),
+    /// `size` is `None` for `PUSH`, which picks the smallest that fits.
+    Push {
+        size: Option<usize>,
+        operand: Operand,
+        line: usize,
+        token: String,
+    },
+    Label {
+        name: String,
+        line: usize,
+    },
+    Raw(Vec<u8>),
+}
+
+fn mnemonic(name: &str) -> Option<u8> {
+    let name = name.to_ascii_uppercase();
+    (0..=u8::MAX).find(|&byte| Opcode::new(byte).and_then(|op| op.to_str()) == Some(&name))
+}
+
+fn parse_operand(token: &str, line: usize) -> Result<Operand> {
+    if token.starts_with(|c: char| c.is_ascii_digit()) {
+        token
+            .parse()
+            .map(Operand::Value)
+            .map_err(|_| AsmError::InvalidNumber {
+                line,
+                token: token.to_string(),
+            })
+    } else {
+        Ok(Operand::Label(token.to_string()))
+    }
+}
+
+/// Splits the text into tokens with their line, a `:` on its own joins the label before it.
+fn tokenize(text: &str) -> Vec<(usize, String)> {
+    let mut tokens: Vec<(usize, String)> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let code = line.split(';').next().unwrap_or_default();
+        for token in code.split_whitespace() {
+            match tokens.last_mut() {
+                Some((_, last)) if token == ":" => last.push(':'),
+                _ => tokens.push((index + 1, token.to_string())),
+            }
+        }
+    }
+    tokens
+}
+
+fn parse(text: &str) -> Result<Vec<Item>> {
+    let mut items = Vec::new();
+    let mut tokens = tokenize(text).into_iter();
+    while let Some((line, token)) = tokens.next() {
+        if let Some(label) = token.strip_suffix(':') {
+            items.push(Item::Label {
+                name: label.to_string(),
+                line,
+            });
+        } else if let Some(hex) = token.strip_prefix("0x") {
+            let bytes = hex::decode(hex).map_err(|_| AsmError::InvalidNumber {
+                line,
+                token: token.clone(),
+            })?;
+            items.push(Item::Raw(bytes));
+        } else if token.eq_ignore_ascii_case("PUSH") || mnemonic(&token).is_some_and(is_push) {
+            let size = mnemonic(&token).map(|push| (push - opcodes::PUSH0) as usize);
+            let Some((_, operand)) = tokens.next() else {
+                return Err(AsmError::MissingOperand {
+                    line,
+                    mnemonic: token,
+                });
+            };
+            items.push(Item::Push {
+                size,
+                operand: parse_operand(&operand, line)?,
+                line,
+                token: operand,
+            });
+        } else {
+            let op = mnemonic(&token).ok_or(AsmError::UnknownMnemonic { line, token })?;
+            items.push(Item::Op(op));
+        }
+    }
+    Ok(items)
+}
+
+fn is_push(op: u8) -> bool {
+    (opcodes::PUSH1..=opcodes::PUSH32).contains(&op)
+}
+
+/// Assembles `text` into bytecode.
+pub fn assemble(text: &str) -> Result<Bytes> {
+    let items = parse(text)?;
+
+    // Auto sized pushes of labels start at one byte and grow until every label fits, growing
+    // can only move labels further out.
+    let mut sizes: Vec<usize> = items
+        .iter()
+        .map(|item| match item {
+            Item::Push {
+                size: Some(size), ..
+            } => *size,
+            Item::Push {
+                operand: Operand::Value(value),
+                ..
+            } => value.byte_len(),
+            Item::Push { .. } => 1,
+            _ => 0,
+        })
+        .collect();
+    let labels = loop {
+        let mut labels = HashMap::new();
+        let mut pc = 0;
+        for (item, size) in items.iter().zip(&sizes) {
+            match item {
+                Item::Op(_) => pc += 1,
+                Item::Push { .. } => pc += 1 + size,
+                Item::Raw(bytes) => pc += bytes.len(),
+                Item::Label { name, line } => {
+                    if labels.insert(name.clone(), pc).is_some() {
+                        return Err(AsmError::DuplicateLabel {
+                            line: *line,
+                            label: name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut grown = false;
+        for (item, size) in items.iter().zip(&mut sizes) {
+            if let Item::Push {
+                size: None,
+                operand: Operand::Label(label),
+                line,
+                ..
+            } = item
+            {
+                let offset = *labels.get(label).ok_or(AsmError::UnknownLabel {
+                    line: *line,
+                    label: label.clone(),
+                })?;
+                let needed = U256::from(offset).byte_len().max(1);
+                if needed > *size {
+                    *size = needed;
+                    grown = true;
+                }
+            }
+        }
+        if !grown {
+            break labels;
+        }
+    };
+
+    let mut code = Vec::new();
+    for (item, size) in items.iter().zip(sizes) {
+        match item {
+            Item::Op(op) => code.push(*op),
+            Item::Raw(bytes) => code.extend(bytes),
+            Item::Label { .. } => {}
+            Item::Push {
+                operand,
+                line,
+                token,
+                ..
+            } => {
+                let value = match operand {
+                    Operand::Value(value) => *value,
+                    Operand::Label(label) => {
+                        U256::from(*labels.get(label).ok_or(AsmError::UnknownLabel {
+                            line: *line,
+                            label: label.clone(),
+                        })?)
+                    }
+                };
+                if value.byte_len() > size {
+                    return Err(AsmError::ValueTooLarge {
+                        line: *line,
+                        token: token.clone(),
+                    });
+                }
+                code.push(opcodes::PUSH0 + size as u8);
+                code.extend(&value.to_be_bytes::<32>()[32 - size..]);
+            }
+        }
+    }
+    Ok(Bytes::from(code))
+}
+
+/// Assembles the mnemonics it is given into [`Bytes`], panicking on errors. Meant for tests:
+///
+/// ```
+/// use evm_rs::bytecode;
+///
+/// let code = bytecode!(PUSH 4 JUMP INVALID target: JUMPDEST);
+/// assert_eq!(code[..], [0x60, 0x04, 0x56, 0xfe, 0x5b]);
+/// ```
+#[macro_export]
+macro_rules! bytecode {
+    ($($token:tt)*) => {
+        $crate::asm::assembler::assemble(stringify!($($token)*)).unwrap()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::asm::disassembler::Disassembler;
+
+    use super::*;
+
+    #[test]
+    fn mnemonics() {
+        let code = assemble("push1 0x02 PUSH1 3 ; two and three\nADD PUSH2 0x01 STOP").unwrap();
+        assert_eq!(
+            code[..],
+            [0x60, 0x02, 0x60, 0x03, 0x01, 0x61, 0x00, 0x01, 0x00]
+        );
+        assert_eq!(
+            bytecode!(PUSH 0 PUSH 0xff PUSH 256)[..],
+            [
+                opcodes::PUSH0,
+                opcodes::PUSH1,
+                0xff,
+                opcodes::PUSH2,
+                0x01,
+                0x00
+            ]
+        );
+        assert_eq!(bytecode!(PUSH32 1)[..], {
+            let mut code = [0; 33];
+            code[0] = opcodes::PUSH32;
+            code[32] = 1;
+            code
+        });
+    }
+
+    #[test]
+    fn labels() {
+        let code = bytecode!(
+            start: JUMPDEST
+            PUSH end JUMP
+            PUSH1 start
+            end: JUMPDEST
+        );
+        assert_eq!(code[..], [0x5b, 0x60, 0x06, 0x56, 0x60, 0x00, 0x5b]);
+
+        // 300 bytes of padding push the label past one byte, which grows the push to it.
+        let text = format!("PUSH end JUMP 0x{} end: JUMPDEST", "00".repeat(300));
+        let code = assemble(&text).unwrap();
+        assert_eq!(code[..4], [opcodes::PUSH2, 0x01, 0x30, opcodes::JUMP]);
+        assert_eq!(code[0x130], opcodes::JUMPDEST);
+    }
+
+    #[test]
+    fn round_trip() {
+        // Unknown bytes and a truncated PUSH2 at the end come back as raw hex.
+        let code = hex::decode("60806040525f5b0c61ff0056fe6101").unwrap();
+        let text: Vec<_> = Disassembler::new(&code)
+            .map(|instruction| instruction.to_string())
+            .collect();
+        assert_eq!(assemble(&text.join("\n")).unwrap()[..], code[..]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            assemble("ADD\nFOO"),
+            Err(AsmError::UnknownMnemonic {
+                line: 2,
+                token: "FOO".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("PUSH1"),
+            Err(AsmError::MissingOperand {
+                line: 1,
+                mnemonic: "PUSH1".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("PUSH 0xzz"),
+            Err(AsmError::InvalidNumber {
+                line: 1,
+                token: "0xzz".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("PUSH1 0x100"),
+            Err(AsmError::ValueTooLarge {
+                line: 1,
+                token: "0x100".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("PUSH nowhere JUMP"),
+            Err(AsmError::UnknownLabel {
+                line: 1,
+                label: "nowhere".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("a: JUMPDEST\na: JUMPDEST"),
+            Err(AsmError::DuplicateLabel {
+                line: 2,
+                label: "a".to_string()
+            })
+        );
+        assert_eq!(
+            AsmError::UnknownLabel {
+                line: 3,
+                label: "end".to_string()
+            }
+            .to_string(),
+            "line 3: unknown label end"
+        );
+    }
+}
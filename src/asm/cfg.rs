@@ -0,0 +1,365 @@
+//! The control flow graph of legacy bytecode, with the jumps whose target is pushed right before
+//! them resolved.
+
+use std::fmt::Write;
+
+use ruint::aliases::U256;
+
+use crate::{
+    asm::disassembler::{Disassembler, Instruction},
+    domain::bytecode::Bytecode,
+    interpreter::opcodes,
+};
+
+/// Where a jump goes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JumpTarget {
+    /// The JUMPDEST at this offset.
+    Static(usize),
+    /// A pushed value that is not a JUMPDEST, the jump always fails.
+    Invalid,
+    /// A value computed at runtime.
+    Dynamic,
+}
+
+/// How a block ends.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Exit {
+    /// Runs into the next block, or stops at the end of the code.
+    Fallthrough,
+    Jump(JumpTarget),
+    /// A JUMPI, falling through to the next block when the condition is zero.
+    JumpI(JumpTarget),
+    /// STOP, RETURN, REVERT, INVALID, SELFDESTRUCT or a byte that is not a opcode.
+    Halt,
+}
+
+impl Exit {
+    /// A jump to a value computed at runtime.
+    pub fn is_dynamic(&self) -> bool {
+        matches!(
+            self,
+            Exit::Jump(JumpTarget::Dynamic) | Exit::JumpI(JumpTarget::Dynamic)
+        )
+    }
+}
+
+/// A run of instructions that is only entered at the first and left at the last.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BasicBlock {
+    /// The offset of the first instruction.
+    pub start: usize,
+    /// The offset right after the last instruction.
+    pub end: usize,
+    pub exit: Exit,
+    /// The indices of the blocks control can go to next.
+    pub successors: Vec<usize>,
+    /// The indices of the blocks control can come from.
+    pub predecessors: Vec<usize>,
+    /// Whether the block can run at all, see [`Cfg::new`].
+    pub reachable: bool,
+}
+
+impl BasicBlock {
+    fn new(start: usize, end: usize, exit: Exit) -> Self {
+        Self {
+            start,
+            end,
+            exit,
+            successors: Vec::new(),
+            predecessors: Vec::new(),
+            reachable: false,
+        }
+    }
+
+    /// Whether the block starts with a JUMPDEST, so dynamic jumps can get to it.
+    pub fn is_jump_target(&self, code: &[u8]) -> bool {
+        code[self.start] == opcodes::JUMPDEST
+    }
+}
+
+fn is_halt(instruction: &Instruction) -> bool {
+    instruction.is_unknown()
+        || matches!(
+            instruction.byte,
+            opcodes::STOP
+                | opcodes::RETURN
+                | opcodes::REVERT
+                | opcodes::INVALID
+                | opcodes::SELFDESTRUCT
+        )
+}
+
+/// The target of a jump with `previous` the instruction before it.
+fn jump_target(previous: Option<&Instruction>, jumpdests: &[bool]) -> JumpTarget {
+    let Some(push) = previous.filter(|previous| {
+        (opcodes::PUSH0..=opcodes::PUSH32).contains(&previous.byte) && !previous.is_truncated()
+    }) else {
+        return JumpTarget::Dynamic;
+    };
+    let value = U256::try_from_be_slice(push.immediate).unwrap_or_default();
+    match usize::try_from(value) {
+        Ok(dest) if jumpdests.get(dest) == Some(&true) => JumpTarget::Static(dest),
+        _ => JumpTarget::Invalid,
+    }
+}
+
+/// The basic blocks of a contract in the order of their offsets, the first is the entry.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Cfg<'a> {
+    pub code: &'a [u8],
+    pub blocks: Vec<BasicBlock>,
+}
+
+impl<'a> Cfg<'a> {
+    /// Splits `bytecode` into blocks and links them. A block is reachable if there is a path to
+    /// it from the entry, where a reachable dynamic jump may go to any JUMPDEST.
+    pub fn new(bytecode: &'a Bytecode) -> Self {
+        let code = bytecode.bytes_slice();
+        let jumpdests = bytecode.jumpdests();
+
+        let mut blocks = Vec::new();
+        let mut start = 0;
+        let mut previous: Option<Instruction> = None;
+        for instruction in Disassembler::new(code) {
+            if instruction.is_jumpdest() && instruction.pc != start {
+                blocks.push(BasicBlock::new(start, instruction.pc, Exit::Fallthrough));
+                start = instruction.pc;
+            }
+            let exit = match instruction.byte {
+                opcodes::JUMP => Some(Exit::Jump(jump_target(previous.as_ref(), &jumpdests))),
+                opcodes::JUMPI => Some(Exit::JumpI(jump_target(previous.as_ref(), &jumpdests))),
+                _ if is_halt(&instruction) => Some(Exit::Halt),
+                _ => None,
+            };
+            if let Some(exit) = exit {
+                let end = instruction.pc + instruction.size();
+                blocks.push(BasicBlock::new(start, end, exit));
+                start = end;
+            }
+            previous = Some(instruction);
+        }
+        if start < code.len() {
+            blocks.push(BasicBlock::new(start, code.len(), Exit::Fallthrough));
+        }
+
+        let mut cfg = Self { code, blocks };
+        cfg.link();
+        cfg.mark_reachable();
+        cfg
+    }
+
+    /// The index of the block starting at `offset`.
+    pub fn block_at(&self, offset: usize) -> Option<usize> {
+        self.blocks
+            .binary_search_by_key(&offset, |block| block.start)
+            .ok()
+    }
+
+    fn link(&mut self) {
+        for index in 0..self.blocks.len() {
+            let next = (index + 1 < self.blocks.len()).then_some(index + 1);
+            let target = |target| match target {
+                JumpTarget::Static(dest) => self.block_at(dest),
+                JumpTarget::Invalid | JumpTarget::Dynamic => None,
+            };
+            let successors: Vec<usize> = match self.blocks[index].exit {
+                Exit::Fallthrough => next.into_iter().collect(),
+                Exit::Jump(dest) => target(dest).into_iter().collect(),
+                Exit::JumpI(dest) => {
+                    let mut successors: Vec<usize> = target(dest).into_iter().collect();
+                    if next != successors.first().copied() {
+                        successors.extend(next);
+                    }
+                    successors
+                }
+                Exit::Halt => Vec::new(),
+            };
+            for &successor in &successors {
+                self.blocks[successor].predecessors.push(index);
+            }
+            self.blocks[index].successors = successors;
+        }
+    }
+
+    fn mark_reachable(&mut self) {
+        let mut pending = Vec::from_iter((!self.blocks.is_empty()).then_some(0));
+        let mut dynamic = false;
+        while let Some(index) = pending.pop() {
+            let block = &mut self.blocks[index];
+            if block.reachable {
+                continue;
+            }
+            block.reachable = true;
+            pending.extend(&block.successors);
+            if block.exit.is_dynamic() && !dynamic {
+                dynamic = true;
+                pending.extend(
+                    (0..self.blocks.len()).filter(|&i| self.blocks[i].is_jump_target(self.code)),
+                );
+            }
+        }
+    }
+
+    /// The blocks no execution can get to.
+    pub fn unreachable(&self) -> impl Iterator<Item = &BasicBlock> {
+        self.blocks.iter().filter(|block| !block.reachable)
+    }
+
+    /// The instructions of `block`.
+    pub fn instructions(&self, block: &BasicBlock) -> Disassembler<'a> {
+        Disassembler::with_offset(&self.code[..block.end], block.start)
+    }
+
+    /// The graph in the Graphviz DOT format, with the instructions of each block as its label.
+    /// Unreachable blocks are grey and dashed, and so is the edge of a JUMPI that does not jump.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph cfg {\n    node [shape=box fontname=monospace];\n");
+        for block in &self.blocks {
+            let mut label = String::new();
+            for instruction in self.instructions(block) {
+                let _ = write!(label, "{:06x}: {instruction}\\l", instruction.pc);
+            }
+            if block.exit.is_dynamic() {
+                label.push_str("; dynamic jump\\l");
+            }
+            let style = if block.reachable {
+                ""
+            } else {
+                " style=dashed color=grey"
+            };
+            let _ = writeln!(
+                dot,
+                "    block_{:x} [label=\"{label}\"{style}];",
+                block.start
+            );
+        }
+        for block in &self.blocks {
+            for &successor in &block.successors {
+                let successor = &self.blocks[successor];
+                let not_taken =
+                    matches!(block.exit, Exit::JumpI(_)) && successor.start == block.end;
+                let style = if not_taken { " [style=dashed]" } else { "" };
+                let _ = writeln!(
+                    dot,
+                    "    block_{:x} -> block_{:x}{style};",
+                    block.start, successor.start
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bytecode;
+
+    use super::*;
+
+    #[test]
+    fn blocks() {
+        let bytecode = Bytecode::new_legacy(bytecode!(
+            PUSH1 0x01 PUSH target JUMPI
+            PUSH0 PUSH0 REVERT
+            target: JUMPDEST
+            PUSH1 0x02
+            next: JUMPDEST
+            STOP
+        ));
+        let cfg = Cfg::new(&bytecode);
+        let ranges: Vec<_> = cfg
+            .blocks
+            .iter()
+            .map(|block| (block.start, block.end))
+            .collect();
+        assert_eq!(ranges, [(0, 5), (5, 8), (8, 11), (11, 13)]);
+        let exits: Vec<_> = cfg.blocks.iter().map(|block| block.exit).collect();
+        assert_eq!(
+            exits,
+            [
+                Exit::JumpI(JumpTarget::Static(8)),
+                Exit::Halt,
+                Exit::Fallthrough,
+                Exit::Halt
+            ]
+        );
+        assert_eq!(cfg.blocks[0].successors, [2, 1]);
+        assert_eq!(cfg.blocks[2].successors, [3]);
+        assert_eq!(cfg.blocks[2].predecessors, [0]);
+        assert_eq!(cfg.blocks[3].predecessors, [2]);
+        assert!(cfg.blocks[1].successors.is_empty());
+        assert_eq!(cfg.unreachable().count(), 0);
+        assert_eq!(cfg.block_at(8), Some(2));
+        assert_eq!(cfg.block_at(9), None);
+
+        let text: Vec<_> = cfg
+            .instructions(&cfg.blocks[2])
+            .map(|instruction| instruction.to_string())
+            .collect();
+        assert_eq!(text, ["JUMPDEST", "PUSH1 0x02"]);
+    }
+
+    #[test]
+    fn unreachable() {
+        let bytecode = Bytecode::new_legacy(bytecode!(
+            PUSH end JUMP
+            PUSH1 0x01 POP
+            dead: JUMPDEST
+            INVALID
+            end: JUMPDEST
+            PUSH1 0x03 JUMP
+        ));
+        let cfg = Cfg::new(&bytecode);
+        let unreachable: Vec<_> = cfg.unreachable().map(|block| block.start).collect();
+        assert_eq!(unreachable, [3, 6]);
+        // Pushing something that is not a JUMPDEST.
+        assert_eq!(cfg.blocks[3].exit, Exit::Jump(JumpTarget::Invalid));
+        assert!(cfg.blocks[3].successors.is_empty());
+    }
+
+    #[test]
+    fn dynamic_jumps() {
+        let bytecode = Bytecode::new_legacy(bytecode!(
+            CALLDATASIZE JUMP
+            PUSH1 0x01 POP
+            a: JUMPDEST STOP
+            b: JUMPDEST STOP
+        ));
+        let cfg = Cfg::new(&bytecode);
+        assert!(cfg.blocks[0].exit.is_dynamic());
+        assert!(cfg.blocks[0].successors.is_empty());
+        let unreachable: Vec<_> = cfg.unreachable().map(|block| block.start).collect();
+        assert_eq!(unreachable, [2]);
+
+        assert!(Cfg::new(&Bytecode::new_legacy(Default::default()))
+            .blocks
+            .is_empty());
+    }
+
+    #[test]
+    fn to_dot() {
+        let bytecode = Bytecode::new_legacy(bytecode!(
+            CALLVALUE PUSH skip JUMPI
+            STOP
+            skip: JUMPDEST
+            CALLDATASIZE JUMP
+            STOP
+        ));
+        assert_eq!(
+            Cfg::new(&bytecode).to_dot(),
+            concat!(
+                "digraph cfg {\n",
+                "    node [shape=box fontname=monospace];\n",
+                "    block_0 [label=\"000000: CALLVALUE\\l000001: PUSH1 0x05\\l000003: JUMPI\\l\"];\n",
+                "    block_4 [label=\"000004: STOP\\l\"];\n",
+                "    block_5 [label=\"000005: JUMPDEST\\l000006: CALLDATASIZE\\l000007: JUMP\\l; dynamic jump\\l\"];\n",
+                "    block_8 [label=\"000008: STOP\\l\" style=dashed color=grey];\n",
+                "    block_0 -> block_5;\n",
+                "    block_0 -> block_4 [style=dashed];\n",
+                "}\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Decoding legacy bytecode into instructions.
+
+use std::fmt::{self, Write};
+
+use crate::interpreter::opcodes::{self, Opcode};
+
+/// The keys solc starts its CBOR metadata with.
+const METADATA_KEYS: [&[u8]; 5] = [b"ipfs", b"bzzr0", b"bzzr1", b"solc", b"experimental"];
+
+/// A instruction, or a byte that is not one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Instruction<'a> {
+    pub pc: usize,
+    pub byte: u8,
+    /// `None` for bytes that are not a opcode.
+    pub opcode: Option<Opcode>,
+    /// The data of a PUSH, shorter than it should be at the end of the code.
+    pub immediate: &'a [u8],
+}
+
+impl Instruction<'_> {
+    pub fn is_jumpdest(&self) -> bool {
+        self.byte == opcodes::JUMPDEST
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        self.opcode.is_none()
+    }
+
+    /// A PUSH cut short by the end of the code.
+    pub fn is_truncated(&self) -> bool {
+        self.immediate.len() < immediate_size(self.byte)
+    }
+
+    /// The bytes the instruction takes up in the code.
+    pub fn size(&self) -> usize {
+        1 + self.immediate.len()
+    }
+}
+
+/// The mnemonic with the immediate as hex, e.g. `PUSH1 0x80`. Unknown bytes and truncated
+/// PUSHes are written as raw hex bytes, so the assembler turns the text back into the same code.
+impl fmt::Display for Instruction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.opcode.and_then(|opcode| opcode.to_str()) {
+            Some(name) if !self.is_truncated() => {
+                write!(f, "{name}")?;
+                if !self.immediate.is_empty() {
+                    write!(f, " 0x{}", hex::encode(self.immediate))?;
+                }
+                Ok(())
+            }
+            _ => write!(f, "0x{:02x}{}", self.byte, hex::encode(self.immediate)),
+        }
+    }
+}
+
+/// The size of the data following `byte`, non zero for PUSH1 to PUSH32.
+pub fn immediate_size(byte: u8) -> usize {
+    match byte {
+        opcodes::PUSH1..=opcodes::PUSH32 => (byte - opcodes::PUSH0) as usize,
+        _ => 0,
+    }
+}
+
+/// Splits off the CBOR encoded metadata solc appends to contracts, along with its two byte
+/// length.
+pub fn split_metadata(code: &[u8]) -> (&[u8], Option<&[u8]>) {
+    let Some((rest, &[high, low])) = code.split_last_chunk::<2>() else {
+        return (code, None);
+    };
+    let len = u16::from_be_bytes([high, low]) as usize;
+    let Some(start) = rest.len().checked_sub(len) else {
+        return (code, None);
+    };
+    let cbor = &rest[start..];
+    // A map of up to 7 entries whose first key is a short text string.
+    let is_metadata = match cbor {
+        [0xa1..=0xa7, key_header @ 0x60..=0x77, key @ ..] => {
+            let key_len = (key_header - 0x60) as usize;
+            key.get(..key_len)
+                .is_some_and(|key| METADATA_KEYS.contains(&key))
+        }
+        _ => false,
+    };
+    if is_metadata {
+        (&code[..start], Some(&code[start..]))
+    } else {
+        (code, None)
+    }
+}
+
+/// Iterates over the instructions of legacy code, PUSH data is never decoded as a instruction.
+#[derive(Clone, Debug)]
+pub struct Disassembler<'a> {
+    code: &'a [u8],
+    pc: usize,
+}
+
+impl<'a> Disassembler<'a> {
+    /// Decodes all of `code`, see [`split_metadata`] to leave out the solc metadata.
+    pub fn new(code: &'a [u8]) -> Self {
+        Self { code, pc: 0 }
+    }
+
+    /// Decodes `code` from `pc` on, which has to be the offset of a instruction.
+    pub fn with_offset(code: &'a [u8], pc: usize) -> Self {
+        Self { code, pc }
+    }
+}
+
+impl<'a> Iterator for Disassembler<'a> {
+    type Item = Instruction<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pc = self.pc;
+        let byte = *self.code.get(pc)?;
+        let end = (pc + 1 + immediate_size(byte)).min(self.code.len());
+        self.pc = end;
+        Some(Instruction {
+            pc,
+            byte,
+            opcode: Opcode::new(byte),
+            immediate: &self.code[pc + 1..end],
+        })
+    }
+}
+
+/// A listing with the offset of every instruction, jump targets, unknown bytes and truncated
+/// PUSHes marked, and the solc metadata on a line of its own.
+pub fn listing(code: &[u8]) -> String {
+    let (code, metadata) = split_metadata(code);
+    let mut listing = String::new();
+    for instruction in Disassembler::new(code) {
+        let line = format!("{:06x}: {instruction}", instruction.pc);
+        let note = if instruction.is_jumpdest() {
+            "jump target"
+        } else if instruction.is_unknown() {
+            "unknown opcode"
+        } else if instruction.is_truncated() {
+            "truncated"
+        } else {
+            ""
+        };
+        if note.is_empty() {
+            let _ = writeln!(listing, "{line}");
+        } else {
+            let _ = writeln!(listing, "{line:<24}; {note}");
+        }
+    }
+    if let Some(metadata) = metadata {
+        let _ = writeln!(
+            listing,
+            "{:06x}: metadata 0x{}",
+            code.len(),
+            hex::encode(metadata)
+        );
+    }
+    listing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A solc 0.8.19 metadata section with a IPFS hash.
+    const METADATA: &str = concat!(
+        "a2646970667358221220",
+        "1b7e0e5a6d3e4d2b7c6a9b8e7f6a5d4c3b2a19081726354453627180a9b8c7d6",
+        "64736f6c63430008130033"
+    );
+
+    #[test]
+    fn instructions() {
+        // PUSH1 0x80, PUSH0, JUMPDEST, 0x0c, PUSH2 with one byte left.
+        let code = [0x60, 0x80, 0x5f, 0x5b, 0x0c, 0x61, 0x01];
+        let instructions: Vec<_> = Disassembler::new(&code).collect();
+        assert_eq!(instructions.len(), 5);
+        assert_eq!(
+            instructions[0],
+            Instruction {
+                pc: 0,
+                byte: 0x60,
+                opcode: Opcode::new(0x60),
+                immediate: &[0x80],
+            }
+        );
+        assert_eq!(instructions[1].pc, 2);
+        assert!(instructions[2].is_jumpdest());
+        assert!(instructions[3].is_unknown());
+        assert!(instructions[4].is_truncated());
+        assert_eq!(instructions[4].size(), 2);
+
+        let text: Vec<_> = instructions.iter().map(ToString::to_string).collect();
+        assert_eq!(text, ["PUSH1 0x80", "PUSH0", "JUMPDEST", "0x0c", "0x6101"]);
+    }
+
+    #[test]
+    fn push_data_is_not_decoded() {
+        // PUSH2 0x5b5b, JUMPDEST
+        let code = [0x61, 0x5b, 0x5b, 0x5b];
+        let jumpdests: Vec<_> = Disassembler::new(&code)
+            .filter(Instruction::is_jumpdest)
+            .map(|instruction| instruction.pc)
+            .collect();
+        assert_eq!(jumpdests, [3]);
+    }
+
+    #[test]
+    fn split_metadata() {
+        let metadata = hex::decode(METADATA).unwrap();
+        let mut code = vec![0x60, 0x80, 0xfe];
+        code.extend(&metadata);
+        assert_eq!(
+            super::split_metadata(&code),
+            (&code[..3], Some(&metadata[..]))
+        );
+
+        // Too short, a length past the start and something other than a map.
+        assert_eq!(super::split_metadata(&[0x00]), (&[0x00][..], None));
+        assert_eq!(
+            super::split_metadata(&[0x00, 0x00, 0x05]),
+            (&[0x00, 0x00, 0x05][..], None)
+        );
+        let code = [0x60, 0x01, 0x00, 0x02];
+        assert_eq!(super::split_metadata(&code), (&code[..], None));
+    }
+
+    #[test]
+    fn listing() {
+        let mut code = hex::decode("6004565b0c6101").unwrap();
+        code.extend(hex::decode(METADATA).unwrap());
+        let (code_only, _) = super::split_metadata(&code);
+        assert_eq!(code_only.len(), 7);
+        assert_eq!(
+            super::listing(&code),
+            format!(
+                "{}\n{}\n{}\n{}\n{}\n000007: metadata 0x{METADATA}\n",
+                "000000: PUSH1 0x04",
+                "000002: JUMP",
+                "000003: JUMPDEST        ; jump target",
+                "000004: 0x0c            ; unknown opcode",
+                "000005: 0x6101          ; truncated",
+            )
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! What the `evm-rs` binary does, kept in the library so it can be tested.
+
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use bytes::Bytes;
+use hex::FromHexError;
+use ruint::aliases::U256;
+use serde_json::json;
+
+use crate::{
+    domain::{
+        bytecode::Bytecode,
+        env::{CfgEnv, Env, TxEnv},
+        primitives::Address,
+        spec::SpecId,
+        state::State,
+        trie,
+    },
+    evm::Evm,
+    inspector::{Inspector, NoopInspector},
+    interpreter::{gas::Gas, InstructionResult, Interpreter},
+    tracer::eip3155::{Summary, Tracer},
+};
+
+pub type Result<T> = std::result::Result<T, CliError>;
+
+#[derive(Debug)]
+pub enum CliError {
+    Io(PathBuf, io::Error),
+    Hex(FromHexError),
+}
+
+impl From<FromHexError> for CliError {
+    fn from(error: FromHexError) -> Self {
+        Self::Hex(error)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, error) => write!(f, "{}: {error}", path.display()),
+            Self::Hex(error) => write!(f, "invalid hex: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Hex bytecode, or `@path` to a file holding it. Whitespace is ignored.
+pub fn read_code(arg: &str) -> Result<Bytecode> {
+    let hex = match arg.strip_prefix('@') {
+        Some(path) => fs::read_to_string(path).map_err(|error| CliError::Io(path.into(), error))?,
+        None => arg.to_string(),
+    };
+    let hex: String = hex.split_whitespace().collect();
+    Ok(Bytecode::parse(&hex)?)
+}
+
+/// Decimal, or hex with a `0x` prefix.
+pub fn parse_u256(s: &str) -> std::result::Result<U256, String> {
+    s.parse()
+        .map_err(|error: ruint::ParseError| error.to_string())
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunOptions {
+    pub calldata: Bytes,
+    pub gas_limit: u64,
+    pub value: U256,
+    pub caller: Address,
+    pub spec: SpecId,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            calldata: Bytes::new(),
+            gas_limit: 30_000_000,
+            value: U256::ZERO,
+            caller: Address::ZERO,
+            spec: SpecId::default(),
+        }
+    }
+}
+
+/// How a run ended.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RunReport {
+    pub result: InstructionResult,
+    pub gas_used: u64,
+    pub output: Bytes,
+    /// From the bottom of the stack to the top.
+    pub stack: Vec<U256>,
+}
+
+impl RunReport {
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "result": format!("{:?}", self.result),
+            "success": self.result.is_ok(),
+            "gasUsed": self.gas_used,
+            "output": format!("0x{}", hex::encode(&self.output)),
+            "stack": self.stack,
+        })
+    }
+}
+
+impl fmt::Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "result:   {:?}", self.result)?;
+        writeln!(f, "gas used: {}", self.gas_used)?;
+        writeln!(f, "output:   0x{}", hex::encode(&self.output))?;
+        write!(f, "stack:")?;
+        if self.stack.is_empty() {
+            write!(f, "    empty")?;
+        }
+        for (depth, item) in self.stack.iter().rev().enumerate() {
+            write!(f, "\n  {depth:4}: {item:#066x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The host and interpreter of a single frame running `code` against an empty world state.
+fn frame<I: Inspector>(
+    code: Bytecode,
+    options: &RunOptions,
+    inspector: I,
+) -> (Evm<I>, Interpreter) {
+    let env = Env {
+        cfg: CfgEnv {
+            spec: options.spec,
+            ..Default::default()
+        },
+        tx: TxEnv {
+            caller: options.caller,
+            gas_limit: options.gas_limit,
+            value: options.value,
+            data: options.calldata.clone(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let evm = Evm::with_inspector(env, State::new(), inspector);
+
+    let mut interpreter = Interpreter::new(code.bytes().clone());
+    interpreter.gas = Gas::new(options.gas_limit);
+    interpreter.spec = options.spec;
+    interpreter.caller = options.caller;
+    interpreter.call_value = options.value;
+    interpreter.input = options.calldata.clone();
+    (evm, interpreter)
+}
+
+fn report(result: InstructionResult, mut interpreter: Interpreter) -> RunReport {
+    // As for a call frame, a exceptional halt returns nothing.
+    if result.is_error() {
+        interpreter.output = Bytes::new();
+    }
+    RunReport {
+        result,
+        gas_used: interpreter.gas.spent(),
+        output: interpreter.output,
+        stack: interpreter.stack.data().to_vec(),
+    }
+}
+
+/// Runs `code` in a single frame against an empty world state.
+pub fn run(code: Bytecode, options: &RunOptions) -> RunReport {
+    let (mut evm, mut interpreter) = frame(code, options, NoopInspector);
+    let result = evm.run_frame(&mut interpreter);
+    report(result, interpreter)
+}
+
+/// As [`run`], also writing an EIP-3155 trace to `out`.
+pub fn trace(code: Bytecode, options: &RunOptions, out: impl Write) -> io::Result<RunReport> {
+    let (mut evm, mut interpreter) = frame(code, options, Tracer::new(out));
+    let result = evm.run_frame(&mut interpreter);
+    let report = report(result, interpreter);
+    evm.inspector.summary(&Summary {
+        state_root: Some(trie::state_root(&evm.journaled_state.state)),
+        ..Summary::new(result, report.output.clone(), report.gas_used)
+    })?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{domain::primitives::parse_hex, interpreter::stack::StackError};
+
+    use super::*;
+
+    #[test]
+    fn read_code() {
+        assert_eq!(
+            super::read_code("0x6001 6002").unwrap(),
+            Bytecode::new_legacy(Bytes::from_static(&[0x60, 0x01, 0x60, 0x02]))
+        );
+
+        let path = std::env::temp_dir().join(format!("evm-rs-cli-{}.hex", std::process::id()));
+        fs::write(&path, "6001\n6002\n").unwrap();
+        let code = super::read_code(&format!("@{}", path.display())).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(code.len(), 4);
+
+        assert!(matches!(
+            super::read_code("0x60f"),
+            Err(CliError::Hex(FromHexError::OddLength))
+        ));
+        assert!(matches!(
+            super::read_code("@/does/not/exist"),
+            Err(CliError::Io(..))
+        ));
+    }
+
+    #[test]
+    fn run() {
+        // Leaves CALLDATALOAD(0) + CALLVALUE on the stack and returns a single 0x01 byte.
+        let code = Bytecode::parse("600035340160016000526001601ff3").unwrap();
+        assert_eq!(parse_u256("10"), Ok(U256::from(10)));
+        assert_eq!(parse_u256("0x10"), Ok(U256::from(16)));
+        assert!(parse_u256("ten").is_err());
+
+        let options = RunOptions {
+            calldata: parse_hex("0x02").unwrap(),
+            value: U256::from(3),
+            ..Default::default()
+        };
+        let report = super::run(code, &options);
+        assert_eq!(report.result, InstructionResult::Return);
+        assert_eq!(report.output[..], [0x01]);
+        assert_eq!(report.stack.len(), 1);
+        assert_eq!(report.stack[0], (U256::from(2) << 248) + U256::from(3));
+        assert_eq!(report.gas_used, 3 + 3 + 2 + 3 + 3 + 3 + 6 + 3 + 3);
+
+        let json = report.to_json();
+        assert_eq!(json["result"], "Return");
+        assert_eq!(json["success"], true);
+        assert_eq!(json["output"], "0x01");
+        assert_eq!(json["stack"][0], format!("{:#x}", report.stack[0]));
+
+        let report = super::run(Bytecode::parse("01").unwrap(), &RunOptions::default());
+        assert_eq!(
+            report.result,
+            InstructionResult::StackError(StackError::Underflow)
+        );
+        assert_eq!(report.gas_used, 30_000_000);
+        assert_eq!(
+            report.to_string(),
+            "result:   StackError(Underflow)\ngas used: 30000000\noutput:   0x\nstack:    empty"
+        );
+    }
+
+    #[test]
+    fn trace() {
+        let code = || Bytecode::parse("600035340160016000526001601ff3").unwrap();
+        let options = RunOptions {
+            calldata: parse_hex("0x02").unwrap(),
+            value: U256::from(3),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        let report = super::trace(code(), &options, &mut out).unwrap();
+        assert_eq!(report, super::run(code(), &options));
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[9]["opName"], "RETURN");
+        assert_eq!(lines[10]["output"], "01");
+        assert_eq!(lines[10]["gasUsed"], "0x1d");
+        assert_eq!(
+            lines[10]["stateRoot"],
+            format!("0x{}", hex::encode(trie::state_root(&State::new())))
+        );
+    }
+}
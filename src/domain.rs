@@ -1,3 +1,14 @@
+pub mod block;
 pub mod bytecode;
 pub mod constants;
+pub mod env;
+pub mod eof;
+pub mod journal;
+pub mod primitives;
+pub mod proof;
+pub mod receipt;
+pub mod rlp;
+pub mod spec;
 pub mod state;
+pub mod transaction;
+pub mod trie;
@@ -0,0 +1,718 @@
+use std::fmt;
+
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use super::{
+    env::{BlockEnv, MAX_BLOB_GAS_PER_BLOCK, TARGET_BLOB_GAS_PER_BLOCK},
+    primitives::{keccak256, Address, B256},
+    receipt::Bloom,
+    rlp::{self, Decodable, Encodable},
+    spec::SpecId,
+    transaction::SignedTransaction,
+    trie::{self, EMPTY_ROOT},
+};
+
+/// keccak-256 of the RLP of a empty list, the ommers hash of blocks without ommers.
+pub const EMPTY_OMMERS_HASH: B256 = [
+    0x1d, 0xcc, 0x4d, 0xe8, 0xde, 0xc7, 0x5d, 0x7a, 0xab, 0x85, 0xb5, 0x67, 0xb6, 0xcc, 0xd4, 0x1a,
+    0xd3, 0x12, 0x45, 0x1b, 0x94, 0x8a, 0x74, 0x13, 0xf0, 0xa1, 0x42, 0xfd, 0x40, 0xd4, 0x93, 0x47,
+];
+
+pub type Result<T> = std::result::Result<T, HeaderError>;
+
+/// EIP-1559 parameters.
+pub const ELASTICITY_MULTIPLIER: u64 = 2;
+pub const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+/// The base fee of the first London block.
+pub const INITIAL_BASE_FEE: u64 = 1_000_000_000;
+
+/// The gas limit may move by less than 1/1024 of the parent's per block.
+pub const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
+pub const MIN_GAS_LIMIT: u64 = 5000;
+
+/// Ways a header can be inconsistent with its parent.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HeaderError {
+    GasLimitTooLow {
+        gas_limit: u64,
+    },
+    /// The gas limit moved by 1/1024 of the parent gas limit or more.
+    GasLimitInvalidChange {
+        parent: u64,
+        gas_limit: u64,
+    },
+    GasUsedExceedsGasLimit {
+        gas_used: u64,
+        gas_limit: u64,
+    },
+    BaseFeeMissing,
+    BaseFeeMismatch {
+        expected: U256,
+        got: U256,
+    },
+    BlobGasMissing,
+    BlobGasUsedExceedsMax {
+        blob_gas_used: u64,
+    },
+    ExcessBlobGasMismatch {
+        expected: u64,
+        got: u64,
+    },
+    /// EIP-4788
+    ParentBeaconBlockRootMissing,
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GasLimitTooLow { gas_limit } => write!(f, "gas limit {gas_limit} too low"),
+            Self::GasLimitInvalidChange { parent, gas_limit } => {
+                write!(f, "gas limit {gas_limit} too far from parent {parent}")
+            }
+            Self::GasUsedExceedsGasLimit {
+                gas_used,
+                gas_limit,
+            } => write!(f, "gas used {gas_used} exceeds gas limit {gas_limit}"),
+            Self::BaseFeeMissing => write!(f, "missing base fee"),
+            Self::BaseFeeMismatch { expected, got } => {
+                write!(f, "base fee {got}, expected {expected}")
+            }
+            Self::BlobGasMissing => write!(f, "missing blob gas fields"),
+            Self::BlobGasUsedExceedsMax { blob_gas_used } => {
+                write!(f, "blob gas used {blob_gas_used} exceeds the maximum")
+            }
+            Self::ExcessBlobGasMismatch { expected, got } => {
+                write!(f, "excess blob gas {got}, expected {expected}")
+            }
+            Self::ParentBeaconBlockRootMissing => write!(f, "missing parent beacon block root"),
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {}
+
+/// The base fee following a block with `base_fee` that used `gas_used` of `gas_limit`. It
+/// moves by up to 1/8 towards keeping blocks half full (EIP-1559).
+pub fn calc_next_base_fee(gas_used: u64, gas_limit: u64, base_fee: U256) -> U256 {
+    let gas_target = gas_limit / ELASTICITY_MULTIPLIER;
+    if gas_target == 0 || gas_used == gas_target {
+        return base_fee;
+    }
+    let denominator = U256::from(gas_target) * U256::from(BASE_FEE_MAX_CHANGE_DENOMINATOR);
+    if gas_used > gas_target {
+        let delta = base_fee * U256::from(gas_used - gas_target) / denominator;
+        base_fee.saturating_add(delta.max(U256::from(1)))
+    } else {
+        let delta = base_fee * U256::from(gas_target - gas_used) / denominator;
+        base_fee - delta
+    }
+}
+
+/// The excess blob gas following a block, what it used above the target carries over
+/// (EIP-4844).
+pub fn calc_excess_blob_gas(parent_excess_blob_gas: u64, parent_blob_gas_used: u64) -> u64 {
+    parent_excess_blob_gas
+        .saturating_add(parent_blob_gas_used)
+        .saturating_sub(TARGET_BLOB_GAS_PER_BLOCK)
+}
+
+/// A block header. The optional fields were appended by later forks and are `None` before them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Header {
+    pub parent_hash: B256,
+    pub ommers_hash: B256,
+    pub beneficiary: Address,
+    pub state_root: B256,
+    pub transactions_root: B256,
+    pub receipts_root: B256,
+    pub logs_bloom: Bloom,
+    pub difficulty: U256,
+    pub number: u64,
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    pub extra_data: Bytes,
+    /// The PoW mix hash, PREVRANDAO from Paris on.
+    pub mix_hash: B256,
+    pub nonce: [u8; 8],
+    /// London, EIP-1559.
+    pub base_fee_per_gas: Option<U256>,
+    /// Shanghai, EIP-4895.
+    pub withdrawals_root: Option<B256>,
+    /// Cancun, EIP-4844.
+    pub blob_gas_used: Option<u64>,
+    /// Cancun, EIP-4844.
+    pub excess_blob_gas: Option<u64>,
+    /// Cancun, EIP-4788.
+    pub parent_beacon_block_root: Option<B256>,
+}
+
+impl Default for Header {
+    fn default() -> Self {
+        Self {
+            parent_hash: B256::default(),
+            ommers_hash: EMPTY_OMMERS_HASH,
+            beneficiary: Address::ZERO,
+            state_root: EMPTY_ROOT,
+            transactions_root: EMPTY_ROOT,
+            receipts_root: EMPTY_ROOT,
+            logs_bloom: Bloom::default(),
+            difficulty: U256::ZERO,
+            number: 0,
+            gas_limit: 0,
+            gas_used: 0,
+            timestamp: 0,
+            extra_data: Bytes::new(),
+            mix_hash: B256::default(),
+            nonce: [0; 8],
+            base_fee_per_gas: None,
+            withdrawals_root: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_block_root: None,
+        }
+    }
+}
+
+impl Header {
+    pub fn hash(&self) -> B256 {
+        keccak256(rlp::encode(self))
+    }
+
+    /// The base fee of the next block, `None` before London.
+    pub fn next_block_base_fee(&self) -> Option<U256> {
+        self.base_fee_per_gas
+            .map(|base_fee| calc_next_base_fee(self.gas_used, self.gas_limit, base_fee))
+    }
+
+    /// The excess blob gas of the next block, blocks before Cancun count as having none.
+    pub fn next_block_excess_blob_gas(&self) -> u64 {
+        calc_excess_blob_gas(
+            self.excess_blob_gas.unwrap_or_default(),
+            self.blob_gas_used.unwrap_or_default(),
+        )
+    }
+
+    /// Checks the gas limit, base fee and blob gas of the header, a block of `spec`, follow
+    /// from `parent`.
+    pub fn validate_against_parent(&self, parent: &Header, spec: SpecId) -> Result<()> {
+        if self.gas_used > self.gas_limit {
+            return Err(HeaderError::GasUsedExceedsGasLimit {
+                gas_used: self.gas_used,
+                gas_limit: self.gas_limit,
+            });
+        }
+
+        // The first London block doubles the gas limit, keeping the gas target the same.
+        let is_london_fork = parent.base_fee_per_gas.is_none();
+        let mut parent_gas_limit = parent.gas_limit;
+        if spec.is_enabled_in(SpecId::London) && is_london_fork {
+            parent_gas_limit = parent_gas_limit.checked_mul(ELASTICITY_MULTIPLIER).ok_or(
+                HeaderError::GasLimitInvalidChange {
+                    parent: parent.gas_limit,
+                    gas_limit: self.gas_limit,
+                },
+            )?;
+        }
+        if self.gas_limit.abs_diff(parent_gas_limit) >= parent_gas_limit / GAS_LIMIT_BOUND_DIVISOR {
+            return Err(HeaderError::GasLimitInvalidChange {
+                parent: parent_gas_limit,
+                gas_limit: self.gas_limit,
+            });
+        }
+        if self.gas_limit < MIN_GAS_LIMIT {
+            return Err(HeaderError::GasLimitTooLow {
+                gas_limit: self.gas_limit,
+            });
+        }
+
+        if spec.is_enabled_in(SpecId::London) {
+            let got = self.base_fee_per_gas.ok_or(HeaderError::BaseFeeMissing)?;
+            let expected = parent
+                .next_block_base_fee()
+                .unwrap_or(U256::from(INITIAL_BASE_FEE));
+            if got != expected {
+                return Err(HeaderError::BaseFeeMismatch { expected, got });
+            }
+        }
+
+        if spec.is_enabled_in(SpecId::Cancun) {
+            let (Some(blob_gas_used), Some(got)) = (self.blob_gas_used, self.excess_blob_gas)
+            else {
+                return Err(HeaderError::BlobGasMissing);
+            };
+            if blob_gas_used > MAX_BLOB_GAS_PER_BLOCK {
+                return Err(HeaderError::BlobGasUsedExceedsMax { blob_gas_used });
+            }
+            let expected = parent.next_block_excess_blob_gas();
+            if got != expected {
+                return Err(HeaderError::ExcessBlobGasMismatch { expected, got });
+            }
+        }
+        Ok(())
+    }
+
+    /// What the transactions of the block can observe of it.
+    pub fn block_env(&self) -> BlockEnv {
+        BlockEnv {
+            number: self.number,
+            coinbase: self.beneficiary,
+            timestamp: self.timestamp,
+            gas_limit: self.gas_limit,
+            basefee: self.base_fee_per_gas.unwrap_or_default(),
+            difficulty: self.difficulty,
+            prevrandao: Some(self.mix_hash),
+            excess_blob_gas: self.excess_blob_gas,
+        }
+    }
+}
+
+impl Encodable for Header {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let mut fields: Vec<&dyn Encodable> = vec![
+            &self.parent_hash,
+            &self.ommers_hash,
+            &self.beneficiary,
+            &self.state_root,
+            &self.transactions_root,
+            &self.receipts_root,
+            &self.logs_bloom,
+            &self.difficulty,
+            &self.number,
+            &self.gas_limit,
+            &self.gas_used,
+            &self.timestamp,
+            &self.extra_data,
+            &self.mix_hash,
+            &self.nonce,
+        ];
+        // Each optional field is only present if the ones before it are.
+        if let Some(base_fee_per_gas) = &self.base_fee_per_gas {
+            fields.push(base_fee_per_gas);
+        }
+        if let Some(withdrawals_root) = &self.withdrawals_root {
+            fields.push(withdrawals_root);
+        }
+        if let Some(blob_gas_used) = &self.blob_gas_used {
+            fields.push(blob_gas_used);
+        }
+        if let Some(excess_blob_gas) = &self.excess_blob_gas {
+            fields.push(excess_blob_gas);
+        }
+        if let Some(parent_beacon_block_root) = &self.parent_beacon_block_root {
+            fields.push(parent_beacon_block_root);
+        }
+        rlp::encode_list(&fields, out);
+    }
+}
+
+impl Decodable for Header {
+    fn decode(buf: &mut &[u8]) -> rlp::Result<Self> {
+        rlp::decode_list(buf, |buf| {
+            let mut header = Self {
+                parent_hash: Decodable::decode(buf)?,
+                ommers_hash: Decodable::decode(buf)?,
+                beneficiary: Decodable::decode(buf)?,
+                state_root: Decodable::decode(buf)?,
+                transactions_root: Decodable::decode(buf)?,
+                receipts_root: Decodable::decode(buf)?,
+                logs_bloom: Decodable::decode(buf)?,
+                difficulty: Decodable::decode(buf)?,
+                number: Decodable::decode(buf)?,
+                gas_limit: Decodable::decode(buf)?,
+                gas_used: Decodable::decode(buf)?,
+                timestamp: Decodable::decode(buf)?,
+                extra_data: Decodable::decode(buf)?,
+                mix_hash: Decodable::decode(buf)?,
+                nonce: Decodable::decode(buf)?,
+                ..Default::default()
+            };
+            if !buf.is_empty() {
+                header.base_fee_per_gas = Some(Decodable::decode(buf)?);
+            }
+            if !buf.is_empty() {
+                header.withdrawals_root = Some(Decodable::decode(buf)?);
+            }
+            if !buf.is_empty() {
+                header.blob_gas_used = Some(Decodable::decode(buf)?);
+                header.excess_blob_gas = Some(Decodable::decode(buf)?);
+            }
+            if !buf.is_empty() {
+                header.parent_beacon_block_root = Some(Decodable::decode(buf)?);
+            }
+            Ok(header)
+        })
+    }
+}
+
+/// A withdrawal from the beacon chain, credited at the end of the block (EIP-4895).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Withdrawal {
+    pub index: u64,
+    pub validator_index: u64,
+    pub address: Address,
+    /// In gwei.
+    pub amount: u64,
+}
+
+impl Encodable for Withdrawal {
+    fn encode(&self, out: &mut Vec<u8>) {
+        rlp::encode_list(
+            &[
+                &self.index,
+                &self.validator_index,
+                &self.address,
+                &self.amount,
+            ],
+            out,
+        );
+    }
+}
+
+impl Decodable for Withdrawal {
+    fn decode(buf: &mut &[u8]) -> rlp::Result<Self> {
+        rlp::decode_list(buf, |buf| {
+            Ok(Self {
+                index: Decodable::decode(buf)?,
+                validator_index: Decodable::decode(buf)?,
+                address: Decodable::decode(buf)?,
+                amount: Decodable::decode(buf)?,
+            })
+        })
+    }
+}
+
+pub fn withdrawals_root(withdrawals: &[Withdrawal]) -> B256 {
+    trie::ordered_trie_root(withdrawals.iter().map(rlp::encode))
+}
+
+/// A block, its transactions executed in order.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Block {
+    pub header: Header,
+    pub transactions: Vec<SignedTransaction>,
+    /// Uncle headers, rewarded before Paris.
+    pub ommers: Vec<Header>,
+    /// From Shanghai on.
+    pub withdrawals: Option<Vec<Withdrawal>>,
+}
+
+impl Block {
+    pub fn hash(&self) -> B256 {
+        self.header.hash()
+    }
+}
+
+/// `[header, transactions, ommers, withdrawals]`, the withdrawals only from Shanghai on.
+impl Encodable for Block {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let mut fields: Vec<&dyn Encodable> = vec![&self.header, &self.transactions, &self.ommers];
+        if let Some(withdrawals) = &self.withdrawals {
+            fields.push(withdrawals);
+        }
+        rlp::encode_list(&fields, out);
+    }
+}
+
+impl Decodable for Block {
+    fn decode(buf: &mut &[u8]) -> rlp::Result<Self> {
+        rlp::decode_list(buf, |buf| {
+            Ok(Self {
+                header: Decodable::decode(buf)?,
+                transactions: Decodable::decode(buf)?,
+                ommers: Decodable::decode(buf)?,
+                withdrawals: match buf.is_empty() {
+                    true => None,
+                    false => Some(Decodable::decode(buf)?),
+                },
+            })
+        })
+    }
+}
+
+/// Root of the transactions trie, keyed by index and holding the raw transactions.
+pub fn transactions_root(transactions: &[SignedTransaction]) -> B256 {
+    trie::ordered_trie_root(transactions.iter().map(SignedTransaction::encode))
+}
+
+pub fn ommers_hash(ommers: &[Header]) -> B256 {
+    keccak256(rlp::encode(ommers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::transaction::{Signature, Transaction, TxEip1559, TxLegacy};
+
+    #[test]
+    fn encoding_roundtrip() {
+        assert_eq!(EMPTY_OMMERS_HASH, keccak256([rlp::EMPTY_LIST]));
+
+        let mut header = Header {
+            number: 1,
+            gas_limit: 30_000_000,
+            extra_data: Bytes::from_static(b"extra"),
+            ..Default::default()
+        };
+        assert_eq!(
+            rlp::decode_exact::<Header>(&rlp::encode(&header)),
+            Ok(header.clone())
+        );
+
+        let legacy_hash = header.hash();
+        header.base_fee_per_gas = Some(U256::from(7));
+        header.withdrawals_root = Some(EMPTY_ROOT);
+        header.blob_gas_used = Some(0);
+        header.excess_blob_gas = Some(0);
+        header.parent_beacon_block_root = Some([1; 32]);
+        let encoded = rlp::encode(&header);
+        assert_eq!(rlp::decode_exact::<Header>(&encoded), Ok(header.clone()));
+        assert_ne!(header.hash(), legacy_hash);
+        assert_eq!(rlp::split_list(&encoded).unwrap().len(), 20);
+    }
+
+    #[test]
+    fn withdrawals() {
+        let withdrawal = Withdrawal {
+            index: 1,
+            validator_index: 2,
+            address: Address::with_last_byte(3),
+            amount: 4,
+        };
+        let encoded = rlp::encode(&withdrawal);
+        assert_eq!(encoded[..4], [0xd8, 0x01, 0x02, 0x94]);
+        assert_eq!(rlp::decode_exact(&encoded), Ok(withdrawal.clone()));
+        assert_eq!(withdrawals_root(&[]), EMPTY_ROOT);
+        assert_ne!(withdrawals_root(&[withdrawal]), EMPTY_ROOT);
+    }
+
+    #[test]
+    fn block_encoding() {
+        let signature = Signature {
+            r: U256::from(1),
+            s: U256::from(2),
+            odd_y_parity: true,
+        };
+        let transactions = vec![
+            SignedTransaction::new(Transaction::Legacy(TxLegacy::default()), signature),
+            SignedTransaction::new(
+                Transaction::DynamicFee(TxEip1559 {
+                    chain_id: 1,
+                    ..Default::default()
+                }),
+                signature,
+            ),
+        ];
+        let mut block = Block {
+            header: Header {
+                number: 1,
+                transactions_root: transactions_root(&transactions),
+                ..Default::default()
+            },
+            transactions,
+            ommers: Vec::new(),
+            withdrawals: None,
+        };
+        assert_eq!(ommers_hash(&block.ommers), EMPTY_OMMERS_HASH);
+        assert_ne!(block.header.transactions_root, EMPTY_ROOT);
+        assert_eq!(transactions_root(&[]), EMPTY_ROOT);
+
+        let encoded = rlp::encode(&block);
+        assert_eq!(rlp::split_list(&encoded).unwrap().len(), 3);
+        assert_eq!(rlp::decode_exact(&encoded), Ok(block.clone()));
+
+        block.withdrawals = Some(vec![Withdrawal::default()]);
+        let encoded = rlp::encode(&block);
+        assert_eq!(rlp::split_list(&encoded).unwrap().len(), 4);
+        assert_eq!(rlp::decode_exact::<Block>(&encoded), Ok(block.clone()));
+        assert_eq!(block.hash(), block.header.hash());
+    }
+
+    #[test]
+    fn block_env() {
+        let header = Header {
+            number: 5,
+            beneficiary: Address([0xcb; 20]),
+            base_fee_per_gas: Some(U256::from(7)),
+            mix_hash: [2; 32],
+            excess_blob_gas: Some(1),
+            ..Default::default()
+        };
+        let block = header.block_env();
+        assert_eq!(block.number, 5);
+        assert_eq!(block.coinbase, header.beneficiary);
+        assert_eq!(block.basefee, U256::from(7));
+        assert_eq!(block.prevrandao, Some([2; 32]));
+        assert_eq!(block.excess_blob_gas, Some(1));
+    }
+
+    #[test]
+    fn base_fee() {
+        let base_fee = U256::from(INITIAL_BASE_FEE);
+        assert_eq!(
+            calc_next_base_fee(15_000_000, 30_000_000, base_fee),
+            base_fee
+        );
+        // Full blocks raise it by 1/8, empty ones lower it by 1/8.
+        assert_eq!(
+            calc_next_base_fee(30_000_000, 30_000_000, base_fee),
+            U256::from(1_125_000_000)
+        );
+        assert_eq!(
+            calc_next_base_fee(0, 30_000_000, base_fee),
+            U256::from(875_000_000)
+        );
+        // Goes up by at least one.
+        assert_eq!(
+            calc_next_base_fee(15_000_001, 30_000_000, U256::from(7)),
+            U256::from(8)
+        );
+        assert_eq!(
+            calc_next_base_fee(0, 30_000_000, U256::from(7)),
+            U256::from(7)
+        );
+
+        // Projected over several full blocks.
+        let mut header = Header {
+            gas_limit: 30_000_000,
+            gas_used: 30_000_000,
+            base_fee_per_gas: Some(U256::from(100)),
+            ..Default::default()
+        };
+        for expected in [112, 126, 141, 158] {
+            header.base_fee_per_gas = header.next_block_base_fee();
+            assert_eq!(header.base_fee_per_gas, Some(U256::from(expected)));
+        }
+        header.base_fee_per_gas = None;
+        assert_eq!(header.next_block_base_fee(), None);
+    }
+
+    #[test]
+    fn excess_blob_gas() {
+        use crate::domain::env::GAS_PER_BLOB;
+
+        assert_eq!(calc_excess_blob_gas(0, 3 * GAS_PER_BLOB), 0);
+        assert_eq!(calc_excess_blob_gas(0, 6 * GAS_PER_BLOB), 3 * GAS_PER_BLOB);
+        assert_eq!(
+            calc_excess_blob_gas(3 * GAS_PER_BLOB, 2 * GAS_PER_BLOB),
+            2 * GAS_PER_BLOB
+        );
+        assert_eq!(calc_excess_blob_gas(GAS_PER_BLOB, 0), 0);
+        assert_eq!(
+            calc_excess_blob_gas(u64::MAX, u64::MAX),
+            u64::MAX - TARGET_BLOB_GAS_PER_BLOCK
+        );
+        assert_eq!(Header::default().next_block_excess_blob_gas(), 0);
+    }
+
+    #[test]
+    fn validate_against_parent() {
+        let parent = Header {
+            gas_limit: 30_000_000,
+            gas_used: 20_000_000,
+            base_fee_per_gas: Some(U256::from(INITIAL_BASE_FEE)),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            ..Default::default()
+        };
+        let child = Header {
+            gas_limit: 30_000_000 + 29_295,
+            base_fee_per_gas: parent.next_block_base_fee(),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            child.validate_against_parent(&parent, SpecId::Cancun),
+            Ok(())
+        );
+
+        let mut header = child.clone();
+        header.gas_limit = 30_000_000 + 29_296;
+        assert_eq!(
+            header.validate_against_parent(&parent, SpecId::Cancun),
+            Err(HeaderError::GasLimitInvalidChange {
+                parent: 30_000_000,
+                gas_limit: 30_029_296
+            })
+        );
+        header.gas_limit = 30_000_000 - 29_295;
+        assert_eq!(
+            header.validate_against_parent(&parent, SpecId::Cancun),
+            Ok(())
+        );
+
+        let mut header = child.clone();
+        header.base_fee_per_gas = Some(U256::from(INITIAL_BASE_FEE));
+        assert!(matches!(
+            header.validate_against_parent(&parent, SpecId::Cancun),
+            Err(HeaderError::BaseFeeMismatch { .. })
+        ));
+        header.base_fee_per_gas = None;
+        assert_eq!(
+            header.validate_against_parent(&parent, SpecId::Cancun),
+            Err(HeaderError::BaseFeeMissing)
+        );
+
+        let mut header = child.clone();
+        header.excess_blob_gas = Some(1);
+        assert_eq!(
+            header.validate_against_parent(&parent, SpecId::Cancun),
+            Err(HeaderError::ExcessBlobGasMismatch {
+                expected: 0,
+                got: 1
+            })
+        );
+        // Blob gas is not checked before Cancun.
+        assert_eq!(
+            header.validate_against_parent(&parent, SpecId::Shanghai),
+            Ok(())
+        );
+
+        // The first London block doubles the gas limit and starts at the initial base fee.
+        let berlin = Header {
+            gas_limit: 15_000_000,
+            ..Default::default()
+        };
+        let london = Header {
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(U256::from(INITIAL_BASE_FEE)),
+            ..Default::default()
+        };
+        assert_eq!(
+            london.validate_against_parent(&berlin, SpecId::London),
+            Ok(())
+        );
+        assert!(london
+            .validate_against_parent(&berlin, SpecId::Berlin)
+            .is_err());
+        // A parent gas limit too large to double.
+        let huge = Header {
+            gas_limit: u64::MAX,
+            ..Default::default()
+        };
+        assert_eq!(
+            london.validate_against_parent(&huge, SpecId::London),
+            Err(HeaderError::GasLimitInvalidChange {
+                parent: u64::MAX,
+                gas_limit: 30_000_000
+            })
+        );
+
+        let tiny = Header {
+            gas_limit: MIN_GAS_LIMIT,
+            ..Default::default()
+        };
+        let header = Header {
+            gas_limit: MIN_GAS_LIMIT - 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            header.validate_against_parent(&tiny, SpecId::Berlin),
+            Err(HeaderError::GasLimitTooLow {
+                gas_limit: MIN_GAS_LIMIT - 1
+            })
+        );
+    }
+}
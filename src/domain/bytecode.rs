@@ -1,6 +1,8 @@
 use bytes::Bytes;
 use hex::FromHexError;
 
+use super::primitives::parse_hex;
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Bytecode {
     Legacy(Bytes),
@@ -14,8 +16,7 @@ impl Bytecode {
     }
 
     pub fn parse(value: &str) -> Result<Self, FromHexError> {
-        let hex = hex::decode(value.strip_prefix("0x").unwrap_or(value))?;
-        Ok(Bytecode::Legacy(Bytes::from(hex)))
+        parse_hex(value).map(Bytecode::Legacy)
     }
 
     pub fn bytes(&self) -> &Bytes {
@@ -39,6 +40,22 @@ impl Bytecode {
         }
     }
 
+    /// Marks every JUMPDEST that is a instruction, rather than part of the data of a PUSH.
+    pub fn jumpdests(&self) -> Vec<bool> {
+        let code = self.bytes_slice();
+        let mut jumpdests = vec![false; code.len()];
+        let mut pc = 0;
+        while pc < code.len() {
+            match code[pc] {
+                0x5b => jumpdests[pc] = true,
+                push @ 0x60..=0x7f => pc += (push - 0x5f) as usize,
+                _ => {}
+            }
+            pc += 1;
+        }
+        jumpdests
+    }
+
     pub fn is_empty(&self) -> bool {
         match self {
             Bytecode::Legacy(bytes) => bytes.is_empty(),
@@ -65,4 +82,11 @@ mod tests {
             Bytecode::new_legacy(expected.clone())
         );
     }
+
+    #[test]
+    fn jumpdests() {
+        // JUMPDEST, PUSH2 0x5b5b, JUMPDEST
+        let code = Bytecode::parse("5b615b5b5b").unwrap();
+        assert_eq!(code.jumpdests(), vec![true, false, false, false, true]);
+    }
 }
@@ -1 +1,25 @@
+use super::primitives::Address;
 
+/// Maximum depth of nested calls and creates.
+pub const CALL_DEPTH_LIMIT: usize = 1024;
+
+/// EIP-170: Contract code size limit
+pub const MAX_CODE_SIZE: usize = 0x6000;
+
+/// EIP-3860: Limit and meter initcode
+pub const MAX_INITCODE_SIZE: usize = 2 * MAX_CODE_SIZE;
+
+/// The caller of system calls, which happen outside of any transaction.
+pub const SYSTEM_ADDRESS: Address = Address([
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xfe,
+]);
+
+/// Gas available to a system call, it is neither paid for nor counted towards the block.
+pub const SYSTEM_CALL_GAS_LIMIT: u64 = 30_000_000;
+
+/// EIP-4788: Beacon block root in the EVM
+pub const BEACON_ROOTS_ADDRESS: Address = Address([
+    0x00, 0x0f, 0x3f, 0xfd, 0x2b, 0x32, 0xa9, 0xff, 0x83, 0xed, 0x5b, 0xfd, 0x52, 0xe6, 0xc5, 0x7b,
+    0xd8, 0xcd, 0xdb, 0x02,
+]);
@@ -0,0 +1,170 @@
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use super::{
+    primitives::{Address, B256},
+    spec::SpecId,
+    state::AccessListItem,
+};
+
+/// EIP-4844 constants, as of Cancun.
+pub const GAS_PER_BLOB: u64 = 1 << 17;
+pub const MIN_BLOB_GASPRICE: u64 = 1;
+pub const BLOB_GASPRICE_UPDATE_FRACTION: u64 = 3_338_477;
+pub const MAX_BLOB_GAS_PER_BLOCK: u64 = 6 * GAS_PER_BLOB;
+pub const TARGET_BLOB_GAS_PER_BLOCK: u64 = 3 * GAS_PER_BLOB;
+
+/// Chain wide configuration, the same for every block and transaction.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CfgEnv {
+    pub chain_id: u64,
+    pub spec: SpecId,
+}
+
+impl Default for CfgEnv {
+    fn default() -> Self {
+        Self {
+            chain_id: 1,
+            spec: SpecId::default(),
+        }
+    }
+}
+
+/// The parts of the block header that execution can observe.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BlockEnv {
+    pub number: u64,
+    pub coinbase: Address,
+    pub timestamp: u64,
+    pub gas_limit: u64,
+    /// EIP-1559 base fee, zero before London.
+    pub basefee: U256,
+    /// Read by DIFFICULTY before the merge.
+    pub difficulty: U256,
+    /// Read by PREVRANDAO from Paris on (EIP-4399).
+    pub prevrandao: Option<B256>,
+    /// EIP-4844, `None` before Cancun.
+    pub excess_blob_gas: Option<u64>,
+}
+
+impl BlockEnv {
+    /// The price of a unit of blob gas, `None` before Cancun.
+    pub fn blob_gasprice(&self) -> Option<u128> {
+        self.excess_blob_gas.map(|excess_blob_gas| {
+            fake_exponential(
+                MIN_BLOB_GASPRICE,
+                excess_blob_gas,
+                BLOB_GASPRICE_UPDATE_FRACTION,
+            )
+        })
+    }
+}
+
+/// Approximates `factor * e ** (numerator / denominator)` using a Taylor expansion (EIP-4844).
+pub fn fake_exponential(factor: u64, numerator: u64, denominator: u64) -> u128 {
+    let factor = factor as u128;
+    let numerator = numerator as u128;
+    let denominator = denominator as u128;
+
+    let mut i = 1;
+    let mut output = 0;
+    let mut numerator_accum = factor * denominator;
+    while numerator_accum > 0 {
+        output += numerator_accum;
+        numerator_accum = (numerator_accum * numerator) / (denominator * i);
+        i += 1;
+    }
+    output / denominator
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum TransactTo {
+    Call(Address),
+    #[default]
+    Create,
+}
+
+/// A transaction as seen by the executor, after the signature has been checked.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TxEnv {
+    pub caller: Address,
+    pub gas_limit: u64,
+    /// The gas price of legacy transactions, the max fee per gas of EIP-1559 ones.
+    pub gas_price: U256,
+    /// EIP-1559 max priority fee per gas.
+    pub gas_priority_fee: Option<U256>,
+    pub transact_to: TransactTo,
+    pub value: U256,
+    pub data: Bytes,
+    /// Checked against the sender when set.
+    pub nonce: Option<u64>,
+    /// Checked against the chain when set (EIP-155).
+    pub chain_id: Option<u64>,
+    pub access_list: Vec<AccessListItem>,
+    /// EIP-4844 versioned hashes of the blobs carried by the transaction.
+    pub blob_hashes: Vec<B256>,
+    pub max_fee_per_blob_gas: Option<U256>,
+}
+
+impl TxEnv {
+    /// The price paid per unit of gas given the blocks base fee (EIP-1559).
+    pub fn effective_gas_price(&self, basefee: U256) -> U256 {
+        match self.gas_priority_fee {
+            Some(priority_fee) => self.gas_price.min(basefee.saturating_add(priority_fee)),
+            None => self.gas_price,
+        }
+    }
+
+    pub fn blob_gas(&self) -> u64 {
+        self.blob_hashes.len() as u64 * GAS_PER_BLOB
+    }
+}
+
+/// Everything a transaction executes against, besides the world state.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Env {
+    pub cfg: CfgEnv,
+    pub block: BlockEnv,
+    pub tx: TxEnv,
+}
+
+impl Env {
+    pub fn effective_gas_price(&self) -> U256 {
+        self.tx.effective_gas_price(self.block.basefee)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effective_gas_price() {
+        let mut tx = TxEnv {
+            gas_price: U256::from(100),
+            ..Default::default()
+        };
+        assert_eq!(tx.effective_gas_price(U256::from(7)), U256::from(100));
+
+        tx.gas_priority_fee = Some(U256::from(2));
+        assert_eq!(tx.effective_gas_price(U256::from(7)), U256::from(9));
+        // The max fee caps what the priority fee can add.
+        assert_eq!(tx.effective_gas_price(U256::from(99)), U256::from(100));
+    }
+
+    #[test]
+    fn blob_gasprice() {
+        let mut block = BlockEnv::default();
+        assert_eq!(block.blob_gasprice(), None);
+
+        block.excess_blob_gas = Some(0);
+        assert_eq!(block.blob_gasprice(), Some(1));
+        block.excess_blob_gas = Some(BLOB_GASPRICE_UPDATE_FRACTION);
+        // e ** 1, rounded down
+        assert_eq!(block.blob_gasprice(), Some(2));
+        assert_eq!(
+            fake_exponential(1, 10_000_000, BLOB_GASPRICE_UPDATE_FRACTION),
+            19
+        );
+    }
+}
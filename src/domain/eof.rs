@@ -0,0 +1,319 @@
+//! The EOF container format (EIP-3540), validated up to the section layout. The code sections
+//! themselves are not validated (EIP-3670, EIP-4200, EIP-5450).
+
+use std::fmt;
+
+use bytes::Bytes;
+
+pub const MAGIC: [u8; 2] = [0xef, 0x00];
+pub const VERSION: u8 = 1;
+
+const KIND_TYPES: u8 = 0x01;
+const KIND_CODE: u8 = 0x02;
+const KIND_CONTAINER: u8 = 0x03;
+const KIND_DATA: u8 = 0x04;
+const TERMINATOR: u8 = 0x00;
+
+pub const MAX_CODE_SECTIONS: usize = 1024;
+pub const MAX_CONTAINER_SECTIONS: usize = 256;
+/// Marks a code section that never returns to its caller.
+pub const NON_RETURNING: u8 = 0x80;
+pub const MAX_STACK_HEIGHT: u16 = 1023;
+
+pub type Result<T> = std::result::Result<T, EofError>;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum EofError {
+    InvalidMagic,
+    InvalidVersion(u8),
+    /// The header ended before it was complete.
+    IncompleteHeader,
+    /// A section kind other than the one expected next.
+    UnexpectedSectionKind {
+        expected: u8,
+        got: u8,
+    },
+    InvalidTypesSectionSize(u16),
+    ZeroCodeSections,
+    TooManyCodeSections(usize),
+    ZeroContainerSections,
+    TooManyContainerSections(usize),
+    ZeroSectionSize,
+    /// The body is shorter than the sizes in the header.
+    TruncatedBody,
+    /// The body is longer than the sizes in the header.
+    TrailingBytes,
+    /// The first code section has to take no inputs and never return.
+    InvalidFirstSectionType,
+    InvalidSectionType {
+        section: usize,
+    },
+    MaxStackHeightAboveLimit {
+        section: usize,
+    },
+}
+
+impl fmt::Display for EofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMagic => write!(f, "invalid magic"),
+            Self::InvalidVersion(version) => write!(f, "invalid version {version}"),
+            Self::IncompleteHeader => write!(f, "incomplete header"),
+            Self::UnexpectedSectionKind { expected, got } => {
+                write!(f, "section kind {got:#04x}, expected {expected:#04x}")
+            }
+            Self::InvalidTypesSectionSize(size) => write!(f, "invalid types section size {size}"),
+            Self::ZeroCodeSections => write!(f, "no code sections"),
+            Self::TooManyCodeSections(count) => write!(f, "{count} code sections"),
+            Self::ZeroContainerSections => write!(f, "no container sections"),
+            Self::TooManyContainerSections(count) => write!(f, "{count} container sections"),
+            Self::ZeroSectionSize => write!(f, "empty section"),
+            Self::TruncatedBody => write!(f, "truncated body"),
+            Self::TrailingBytes => write!(f, "trailing bytes after the body"),
+            Self::InvalidFirstSectionType => write!(f, "invalid type of the first code section"),
+            Self::InvalidSectionType { section } => {
+                write!(f, "invalid type of code section {section}")
+            }
+            Self::MaxStackHeightAboveLimit { section } => {
+                write!(
+                    f,
+                    "max stack height of code section {section} above the limit"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for EofError {}
+
+/// The entry of a code section in the types section.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TypeSection {
+    pub inputs: u8,
+    /// [`NON_RETURNING`] for sections that do not return.
+    pub outputs: u8,
+    pub max_stack_height: u16,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EofContainer {
+    pub types: Vec<TypeSection>,
+    pub code_sections: Vec<Bytes>,
+    /// Nested containers, for EOFCREATE and RETURNCONTRACT.
+    pub container_sections: Vec<Bytes>,
+    pub data: Bytes,
+}
+
+/// Reads a big-endian u16 off the front of `buf`.
+fn read_u16(buf: &mut &[u8]) -> Result<u16> {
+    let &[high, low, ref rest @ ..] = *buf else {
+        return Err(EofError::IncompleteHeader);
+    };
+    *buf = rest;
+    Ok(u16::from_be_bytes([high, low]))
+}
+
+fn read_kind(buf: &mut &[u8], expected: u8) -> Result<()> {
+    let &[got, ref rest @ ..] = *buf else {
+        return Err(EofError::IncompleteHeader);
+    };
+    if got != expected {
+        return Err(EofError::UnexpectedSectionKind { expected, got });
+    }
+    *buf = rest;
+    Ok(())
+}
+
+/// Reads the number of sections and their sizes, none of which may be zero.
+fn read_sizes(buf: &mut &[u8]) -> Result<Vec<usize>> {
+    let count = read_u16(buf)?;
+    (0..count)
+        .map(|_| match read_u16(buf)? {
+            0 => Err(EofError::ZeroSectionSize),
+            size => Ok(size as usize),
+        })
+        .collect()
+}
+
+/// Splits `len` bytes off the front of the body.
+fn take(body: &mut &[u8], len: usize) -> Result<Bytes> {
+    if body.len() < len {
+        return Err(EofError::TruncatedBody);
+    }
+    let (head, tail) = body.split_at(len);
+    *body = tail;
+    Ok(Bytes::copy_from_slice(head))
+}
+
+impl EofContainer {
+    /// Decodes `code` and checks the header, section sizes and types.
+    pub fn decode(code: &[u8]) -> Result<Self> {
+        let mut buf = code
+            .strip_prefix(&MAGIC[..])
+            .ok_or(EofError::InvalidMagic)?;
+        match buf.split_first() {
+            Some((&VERSION, rest)) => buf = rest,
+            Some((version, _)) => return Err(EofError::InvalidVersion(*version)),
+            None => return Err(EofError::IncompleteHeader),
+        }
+
+        read_kind(&mut buf, KIND_TYPES)?;
+        let types_size = read_u16(&mut buf)?;
+        read_kind(&mut buf, KIND_CODE)?;
+        let code_sizes = read_sizes(&mut buf)?;
+        if code_sizes.is_empty() {
+            return Err(EofError::ZeroCodeSections);
+        }
+        if code_sizes.len() > MAX_CODE_SECTIONS {
+            return Err(EofError::TooManyCodeSections(code_sizes.len()));
+        }
+        if types_size as usize != code_sizes.len() * 4 {
+            return Err(EofError::InvalidTypesSectionSize(types_size));
+        }
+        let container_sizes = if buf.first() == Some(&KIND_CONTAINER) {
+            buf = &buf[1..];
+            let sizes = read_sizes(&mut buf)?;
+            if sizes.is_empty() {
+                return Err(EofError::ZeroContainerSections);
+            }
+            if sizes.len() > MAX_CONTAINER_SECTIONS {
+                return Err(EofError::TooManyContainerSections(sizes.len()));
+            }
+            sizes
+        } else {
+            Vec::new()
+        };
+        read_kind(&mut buf, KIND_DATA)?;
+        let data_size = read_u16(&mut buf)? as usize;
+        read_kind(&mut buf, TERMINATOR)?;
+
+        let types = take(&mut buf, types_size as usize)?
+            .chunks(4)
+            .map(|chunk| TypeSection {
+                inputs: chunk[0],
+                outputs: chunk[1],
+                max_stack_height: u16::from_be_bytes([chunk[2], chunk[3]]),
+            })
+            .collect();
+        let code_sections = code_sizes
+            .into_iter()
+            .map(|size| take(&mut buf, size))
+            .collect::<Result<_>>()?;
+        let container_sections = container_sizes
+            .into_iter()
+            .map(|size| take(&mut buf, size))
+            .collect::<Result<_>>()?;
+        let data = take(&mut buf, data_size)?;
+        if !buf.is_empty() {
+            return Err(EofError::TrailingBytes);
+        }
+
+        let container = Self {
+            types,
+            code_sections,
+            container_sections,
+            data,
+        };
+        container.validate_types()?;
+        Ok(container)
+    }
+
+    fn validate_types(&self) -> Result<()> {
+        let first = self.types[0];
+        if first.inputs != 0 || first.outputs != NON_RETURNING {
+            return Err(EofError::InvalidFirstSectionType);
+        }
+        for (section, ty) in self.types.iter().enumerate() {
+            if ty.inputs > 0x7f || (ty.outputs > 0x7f && ty.outputs != NON_RETURNING) {
+                return Err(EofError::InvalidSectionType { section });
+            }
+            if ty.max_stack_height > MAX_STACK_HEIGHT {
+                return Err(EofError::MaxStackHeightAboveLimit { section });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(hex: &str) -> Result<EofContainer> {
+        EofContainer::decode(&hex::decode(hex).unwrap())
+    }
+
+    #[test]
+    fn valid_containers() {
+        // One code section holding STOP.
+        let container = decode(concat!("ef000101000402000100010400000000800000", "00")).unwrap();
+        assert_eq!(container.code_sections, [Bytes::from_static(&[0x00])]);
+        assert_eq!(container.types[0].outputs, NON_RETURNING);
+        assert!(container.data.is_empty());
+
+        // Two code sections, a subcontainer and two bytes of data.
+        let container = decode(concat!(
+            "ef0001010008020002000100010300010014040002000080000000000000",
+            "00",
+            "e4",
+            "ef000101000402000100010400000000800000",
+            "00",
+            "aabb"
+        ))
+        .unwrap();
+        assert_eq!(container.code_sections.len(), 2);
+        assert_eq!(container.container_sections.len(), 1);
+        assert!(EofContainer::decode(&container.container_sections[0]).is_ok());
+        assert_eq!(container.data[..], [0xaa, 0xbb]);
+    }
+
+    #[test]
+    fn invalid_containers() {
+        assert_eq!(decode("ef01"), Err(EofError::InvalidMagic));
+        assert_eq!(decode("ef0002"), Err(EofError::InvalidVersion(2)));
+        assert_eq!(decode("ef000101"), Err(EofError::IncompleteHeader));
+        assert_eq!(
+            decode("ef0001020004"),
+            Err(EofError::UnexpectedSectionKind {
+                expected: KIND_TYPES,
+                got: KIND_CODE
+            })
+        );
+        assert_eq!(
+            decode("ef00010100080200010001040000000080000000"),
+            Err(EofError::InvalidTypesSectionSize(8))
+        );
+        assert_eq!(
+            decode("ef0001010000020000040000"),
+            Err(EofError::ZeroCodeSections)
+        );
+        assert_eq!(
+            decode("ef0001010004020001000004000000008000"),
+            Err(EofError::ZeroSectionSize)
+        );
+        // Missing terminator.
+        assert_eq!(
+            decode("ef000101000402000100010400000100800000"),
+            Err(EofError::UnexpectedSectionKind {
+                expected: TERMINATOR,
+                got: 1
+            })
+        );
+        assert_eq!(
+            decode("ef00010100040200010001040000000080"),
+            Err(EofError::TruncatedBody)
+        );
+        assert_eq!(
+            decode("ef0001010004020001000104000000008000000000"),
+            Err(EofError::TrailingBytes)
+        );
+        assert_eq!(
+            decode(concat!("ef000101000402000100010400000001800000", "00")),
+            Err(EofError::InvalidFirstSectionType)
+        );
+        assert_eq!(
+            decode(concat!("ef000101000402000100010400000000800400", "00")),
+            Err(EofError::MaxStackHeightAboveLimit { section: 0 })
+        );
+    }
+}
@@ -0,0 +1,696 @@
+use bytes::Bytes;
+use hashbrown::{HashMap, HashSet};
+use ruint::aliases::U256;
+
+use super::{
+    primitives::{Address, B256},
+    state::{AccessListItem, Account, Log, State},
+};
+
+pub type Result<T> = std::result::Result<T, TransferError>;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TransferError {
+    OutOfFunds,
+    OverflowPayment,
+}
+
+/// A single reversible change to the world state. Each entry holds just enough to undo itself,
+/// so reverting a frame never requires a copy of the state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JournalEntry {
+    /// The account did not exist before, reverting removes it again.
+    AccountCreated {
+        address: Address,
+    },
+    BalanceTransfer {
+        from: Address,
+        to: Address,
+        value: U256,
+    },
+    BalanceChange {
+        address: Address,
+        had_balance: U256,
+    },
+    NonceChange {
+        address: Address,
+        had_nonce: u64,
+    },
+    CodeChange {
+        address: Address,
+        had_code: Bytes,
+        had_hash: B256,
+    },
+    StorageChange {
+        address: Address,
+        key: U256,
+        had_value: U256,
+    },
+    TransientStorageChange {
+        address: Address,
+        key: U256,
+        had_value: U256,
+    },
+    /// The address was cold, reverting makes it cold again (EIP-2929).
+    AccountWarmed {
+        address: Address,
+    },
+    StorageWarmed {
+        address: Address,
+        key: U256,
+    },
+    /// The account was touched for the first time in the transaction (EIP-161).
+    AccountTouched {
+        address: Address,
+    },
+    /// A contract was created at the address in the current transaction.
+    ContractCreated {
+        address: Address,
+    },
+    /// The account self destructed for the first time in the transaction.
+    AccountDestroyed {
+        address: Address,
+    },
+}
+
+/// Position in the journal and log list that a frame can be reverted back to.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct JournalCheckpoint {
+    journal_i: usize,
+    log_i: usize,
+}
+
+/// The world state together with a journal of every change made to it during a transaction.
+///
+/// Every call frame takes a `checkpoint()` before executing and either `commit()`s or
+/// `revert()`s it when done. Committed entries stay in the journal so a reverting parent frame
+/// still undoes the changes of its successful children.
+#[derive(Debug, Default)]
+pub struct JournaledState {
+    pub state: State,
+    /// EIP-1153 transient storage, discarded at the end of every transaction.
+    pub transient_storage: HashMap<(Address, U256), U256>,
+    pub logs: Vec<Log>,
+    pub journal: Vec<JournalEntry>,
+    pub depth: usize,
+    /// EIP-2929 accessed addresses of the current transaction.
+    pub warm_addresses: HashSet<Address>,
+    /// EIP-2929 accessed storage keys of the current transaction.
+    pub warm_storage: HashSet<(Address, U256)>,
+    /// Storage values as they were at the start of the transaction, recorded on first write.
+    pub original_storage: HashMap<(Address, U256), U256>,
+    /// Accounts touched by the transaction, the empty ones are removed at the end (EIP-161).
+    pub touched: HashSet<Address>,
+    /// Contracts created by the transaction (EIP-6780).
+    pub created: HashSet<Address>,
+    /// Accounts that self destructed, removed at the end of the transaction.
+    pub destroyed: HashSet<Address>,
+}
+
+impl JournaledState {
+    pub fn new(state: State) -> Self {
+        Self {
+            state,
+            ..Default::default()
+        }
+    }
+
+    pub fn account(&self, address: &Address) -> Option<&Account> {
+        self.state.get(address)
+    }
+
+    pub fn balance(&self, address: &Address) -> U256 {
+        self.account(address)
+            .map(|account| account.balance)
+            .unwrap_or_default()
+    }
+
+    pub fn nonce(&self, address: &Address) -> u64 {
+        self.account(address)
+            .map(|account| account.nonce)
+            .unwrap_or_default()
+    }
+
+    pub fn code(&self, address: &Address) -> Bytes {
+        self.account(address)
+            .map(|account| account.code.clone())
+            .unwrap_or_default()
+    }
+
+    /// Missing accounts are empty as well.
+    pub fn is_empty(&self, address: &Address) -> bool {
+        self.account(address).is_none_or(Account::is_empty)
+    }
+
+    /// Returns the account, creating it (and journaling that) if it does not exist.
+    fn load_or_create(&mut self, address: Address) -> &mut Account {
+        if !self.state.contains_key(&address) {
+            self.journal.push(JournalEntry::AccountCreated { address });
+        }
+        self.state.entry(address).or_default()
+    }
+
+    pub fn create_account(&mut self, address: Address) {
+        self.load_or_create(address);
+    }
+
+    pub fn transfer(&mut self, from: Address, to: Address, value: U256) -> Result<()> {
+        if value.is_zero() {
+            return Ok(());
+        }
+        let from_balance = self.balance(&from);
+        if from_balance < value {
+            return Err(TransferError::OutOfFunds);
+        }
+        if from != to {
+            let to_balance = self.balance(&to);
+            let new_to_balance = to_balance
+                .checked_add(value)
+                .ok_or(TransferError::OverflowPayment)?;
+            self.load_or_create(to).balance = new_to_balance;
+            self.load_or_create(from).balance = from_balance - value;
+        }
+        self.journal
+            .push(JournalEntry::BalanceTransfer { from, to, value });
+        Ok(())
+    }
+
+    pub fn balance_incr(&mut self, address: Address, value: U256) -> Result<()> {
+        let had_balance = self.balance(&address);
+        let balance = had_balance
+            .checked_add(value)
+            .ok_or(TransferError::OverflowPayment)?;
+        self.load_or_create(address).balance = balance;
+        self.journal.push(JournalEntry::BalanceChange {
+            address,
+            had_balance,
+        });
+        Ok(())
+    }
+
+    pub fn balance_decr(&mut self, address: Address, value: U256) -> Result<()> {
+        let had_balance = self.balance(&address);
+        let balance = had_balance
+            .checked_sub(value)
+            .ok_or(TransferError::OutOfFunds)?;
+        self.load_or_create(address).balance = balance;
+        self.journal.push(JournalEntry::BalanceChange {
+            address,
+            had_balance,
+        });
+        Ok(())
+    }
+
+    /// Increments the nonce and returns the new one.
+    pub fn inc_nonce(&mut self, address: Address) -> u64 {
+        let account = self.load_or_create(address);
+        let had_nonce = account.nonce;
+        account.nonce += 1;
+        self.journal
+            .push(JournalEntry::NonceChange { address, had_nonce });
+        had_nonce + 1
+    }
+
+    pub fn set_code(&mut self, address: Address, code: Bytes) {
+        let account = self.load_or_create(address);
+        let had_code = account.code.clone();
+        let had_hash = account.code_hash;
+        account.set_code(code);
+        self.journal.push(JournalEntry::CodeChange {
+            address,
+            had_code,
+            had_hash,
+        });
+    }
+
+    /// Pre-warms the accessed sets at the start of a transaction (EIP-2929, EIP-2930). This is
+    /// not journaled, a reverting top-level frame leaves these addresses warm.
+    pub fn prewarm(
+        &mut self,
+        sender: Address,
+        recipient: Option<Address>,
+        access_list: &[AccessListItem],
+        precompiles: impl IntoIterator<Item = Address>,
+    ) {
+        self.warm_addresses.insert(sender);
+        self.warm_addresses.extend(recipient);
+        self.warm_addresses.extend(precompiles);
+        for item in access_list {
+            self.warm_addresses.insert(item.address);
+            self.warm_storage
+                .extend(item.storage_keys.iter().map(|key| (item.address, *key)));
+        }
+    }
+
+    /// Marks the address as accessed, returns true if it was cold.
+    pub fn access_account(&mut self, address: Address) -> bool {
+        let is_cold = self.warm_addresses.insert(address);
+        if is_cold {
+            self.journal.push(JournalEntry::AccountWarmed { address });
+        }
+        is_cold
+    }
+
+    /// Marks the storage slot as accessed, returns true if it was cold.
+    pub fn access_storage(&mut self, address: Address, key: U256) -> bool {
+        let is_cold = self.warm_storage.insert((address, key));
+        if is_cold {
+            self.journal
+                .push(JournalEntry::StorageWarmed { address, key });
+        }
+        is_cold
+    }
+
+    /// The value of the slot at the start of the transaction (EIP-2200).
+    pub fn original_value(&self, address: &Address, key: U256) -> U256 {
+        self.original_storage
+            .get(&(*address, key))
+            .copied()
+            .unwrap_or_else(|| self.sload(address, key))
+    }
+
+    pub fn sload(&self, address: &Address, key: U256) -> U256 {
+        self.account(address)
+            .and_then(|account| account.storage.get(&key).copied())
+            .unwrap_or_default()
+    }
+
+    /// Writes a storage slot and returns the value it held before.
+    pub fn sstore(&mut self, address: Address, key: U256, value: U256) -> U256 {
+        if !self.original_storage.contains_key(&(address, key)) {
+            let original = self.sload(&address, key);
+            self.original_storage.insert((address, key), original);
+        }
+        let account = self.load_or_create(address);
+        let had_value = account.storage.insert(key, value).unwrap_or_default();
+        self.journal.push(JournalEntry::StorageChange {
+            address,
+            key,
+            had_value,
+        });
+        had_value
+    }
+
+    pub fn touch(&mut self, address: Address) {
+        if self.touched.insert(address) {
+            self.journal.push(JournalEntry::AccountTouched { address });
+        }
+    }
+
+    /// Records that a contract was deployed to `address` in this transaction.
+    pub fn mark_created(&mut self, address: Address) {
+        if self.created.insert(address) {
+            self.journal.push(JournalEntry::ContractCreated { address });
+        }
+    }
+
+    pub fn is_created(&self, address: &Address) -> bool {
+        self.created.contains(address)
+    }
+
+    /// Schedules the account for removal at the end of the transaction, returns true if it had
+    /// already self destructed.
+    pub fn selfdestruct(&mut self, address: Address) -> bool {
+        let previously_destroyed = !self.destroyed.insert(address);
+        if !previously_destroyed {
+            self.journal
+                .push(JournalEntry::AccountDestroyed { address });
+        }
+        previously_destroyed
+    }
+
+    pub fn tload(&self, address: Address, key: U256) -> U256 {
+        self.transient_storage
+            .get(&(address, key))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn tstore(&mut self, address: Address, key: U256, value: U256) {
+        let had_value = self
+            .transient_storage
+            .insert((address, key), value)
+            .unwrap_or_default();
+        self.journal.push(JournalEntry::TransientStorageChange {
+            address,
+            key,
+            had_value,
+        });
+    }
+
+    pub fn log(&mut self, log: Log) {
+        self.logs.push(log);
+    }
+
+    /// Marks the start of a new frame.
+    pub fn checkpoint(&mut self) -> JournalCheckpoint {
+        self.depth += 1;
+        JournalCheckpoint {
+            journal_i: self.journal.len(),
+            log_i: self.logs.len(),
+        }
+    }
+
+    /// Keeps the changes of the frame, they can still be reverted by a parent frame.
+    pub fn commit(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Undoes every change made since `checkpoint`, newest first.
+    pub fn revert(&mut self, checkpoint: JournalCheckpoint) {
+        self.depth -= 1;
+        self.logs.truncate(checkpoint.log_i);
+        while self.journal.len() > checkpoint.journal_i {
+            let entry = self.journal.pop().unwrap();
+            self.revert_entry(entry);
+        }
+    }
+
+    fn revert_entry(&mut self, entry: JournalEntry) {
+        match entry {
+            JournalEntry::AccountCreated { address } => {
+                self.state.remove(&address);
+            }
+            JournalEntry::BalanceTransfer { from, to, value } => {
+                if let Some(account) = self.state.get_mut(&to) {
+                    account.balance -= value;
+                }
+                if let Some(account) = self.state.get_mut(&from) {
+                    account.balance += value;
+                }
+            }
+            JournalEntry::BalanceChange {
+                address,
+                had_balance,
+            } => {
+                if let Some(account) = self.state.get_mut(&address) {
+                    account.balance = had_balance;
+                }
+            }
+            JournalEntry::NonceChange { address, had_nonce } => {
+                if let Some(account) = self.state.get_mut(&address) {
+                    account.nonce = had_nonce;
+                }
+            }
+            JournalEntry::CodeChange {
+                address,
+                had_code,
+                had_hash,
+            } => {
+                if let Some(account) = self.state.get_mut(&address) {
+                    account.code = had_code;
+                    account.code_hash = had_hash;
+                }
+            }
+            JournalEntry::StorageChange {
+                address,
+                key,
+                had_value,
+            } => {
+                if let Some(account) = self.state.get_mut(&address) {
+                    if had_value.is_zero() {
+                        account.storage.remove(&key);
+                    } else {
+                        account.storage.insert(key, had_value);
+                    }
+                }
+            }
+            JournalEntry::AccountWarmed { address } => {
+                self.warm_addresses.remove(&address);
+            }
+            JournalEntry::StorageWarmed { address, key } => {
+                self.warm_storage.remove(&(address, key));
+            }
+            JournalEntry::TransientStorageChange {
+                address,
+                key,
+                had_value,
+            } => {
+                if had_value.is_zero() {
+                    self.transient_storage.remove(&(address, key));
+                } else {
+                    self.transient_storage.insert((address, key), had_value);
+                }
+            }
+            JournalEntry::AccountTouched { address } => {
+                self.touched.remove(&address);
+            }
+            JournalEntry::ContractCreated { address } => {
+                self.created.remove(&address);
+            }
+            JournalEntry::AccountDestroyed { address } => {
+                self.destroyed.remove(&address);
+            }
+        }
+    }
+
+    /// Removes the accounts that self destructed during the transaction.
+    pub fn remove_destroyed(&mut self) {
+        for address in self.destroyed.drain() {
+            self.state.remove(&address);
+        }
+    }
+
+    /// Removes the touched accounts that ended up empty (EIP-161).
+    pub fn remove_touched_empty(&mut self) {
+        for address in self.touched.drain() {
+            if self.state.get(&address).is_some_and(Account::is_empty) {
+                self.state.remove(&address);
+            }
+        }
+    }
+
+    /// Ends the transaction: clears the journal, transient storage and accessed sets and returns
+    /// the logs.
+    pub fn finalize(&mut self) -> Vec<Log> {
+        self.journal.clear();
+        self.touched.clear();
+        self.created.clear();
+        self.destroyed.clear();
+        self.transient_storage.clear();
+        self.warm_addresses.clear();
+        self.warm_storage.clear();
+        self.original_storage.clear();
+        self.depth = 0;
+        std::mem::take(&mut self.logs)
+    }
+
+    pub fn into_state(self) -> State {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_state(accounts: &[(Address, u64)]) -> JournaledState {
+        let mut state = State::new();
+        for (address, balance) in accounts {
+            state.insert(*address, Account::with_balance(U256::from(*balance)));
+        }
+        JournaledState::new(state)
+    }
+
+    #[test]
+    fn revert_transfer() {
+        let (alice, bob) = (Address::with_last_byte(0xa), Address::with_last_byte(0xb));
+        let mut journal = build_state(&[(alice, 100)]);
+
+        let checkpoint = journal.checkpoint();
+        journal.transfer(alice, bob, U256::from(40)).unwrap();
+        assert_eq!(journal.balance(&alice), U256::from(60));
+        assert_eq!(journal.balance(&bob), U256::from(40));
+
+        journal.revert(checkpoint);
+        assert_eq!(journal.balance(&alice), U256::from(100));
+        assert!(journal.account(&bob).is_none());
+        assert_eq!(journal.depth, 0);
+
+        assert_eq!(
+            journal.transfer(alice, bob, U256::from(101)),
+            Err(TransferError::OutOfFunds)
+        );
+    }
+
+    #[test]
+    fn nested_revert_keeps_caller_changes() {
+        let contract = Address::with_last_byte(0xc);
+        let mut journal = build_state(&[(contract, 0)]);
+
+        journal.checkpoint();
+        journal.sstore(contract, U256::from(1), U256::from(1));
+        journal.tstore(contract, U256::from(1), U256::from(7));
+
+        let inner = journal.checkpoint();
+        journal.sstore(contract, U256::from(1), U256::from(2));
+        journal.sstore(contract, U256::from(2), U256::from(2));
+        journal.tstore(contract, U256::from(1), U256::from(8));
+        journal.log(Log {
+            address: contract,
+            topics: vec![],
+            data: Bytes::new(),
+        });
+        journal.revert(inner);
+
+        assert_eq!(journal.sload(&contract, U256::from(1)), U256::from(1));
+        assert_eq!(journal.sload(&contract, U256::from(2)), U256::ZERO);
+        assert_eq!(journal.tload(contract, U256::from(1)), U256::from(7));
+        assert!(journal.logs.is_empty());
+
+        journal.commit();
+        assert_eq!(journal.sload(&contract, U256::from(1)), U256::from(1));
+        assert_eq!(journal.depth, 0);
+    }
+
+    #[test]
+    fn parent_revert_undoes_committed_child() {
+        let (caller, created) = (Address::with_last_byte(0xa), Address::with_last_byte(0xd));
+        let mut journal = build_state(&[(caller, 10)]);
+
+        let outer = journal.checkpoint();
+        journal.inc_nonce(caller);
+
+        journal.checkpoint();
+        journal.create_account(created);
+        journal.inc_nonce(created);
+        journal.set_code(created, Bytes::from_static(&[0x5f]));
+        journal.transfer(caller, created, U256::from(10)).unwrap();
+        journal.commit();
+        assert_eq!(journal.code(&created), Bytes::from_static(&[0x5f]));
+
+        journal.revert(outer);
+        assert!(journal.account(&created).is_none());
+        assert_eq!(journal.nonce(&caller), 0);
+        assert_eq!(journal.balance(&caller), U256::from(10));
+        assert!(journal.journal.is_empty());
+    }
+
+    #[test]
+    fn simulate_then_discard() {
+        let alice = Address::with_last_byte(0xa);
+        let mut journal = build_state(&[(alice, 5)]);
+        let before = journal.state.clone();
+
+        let checkpoint = journal.checkpoint();
+        journal.balance_incr(alice, U256::from(5)).unwrap();
+        journal.balance_decr(alice, U256::from(3)).unwrap();
+        journal.sstore(alice, U256::ZERO, U256::MAX);
+        assert_eq!(journal.balance(&alice), U256::from(7));
+        journal.revert(checkpoint);
+
+        assert_eq!(journal.state, before);
+    }
+
+    #[test]
+    fn failed_balance_change_is_not_journaled() {
+        let (alice, bob) = (Address::with_last_byte(0xa), Address::with_last_byte(0xb));
+        let mut journal = build_state(&[(alice, 5)]);
+
+        journal.checkpoint();
+        assert_eq!(
+            journal.balance_decr(bob, U256::from(1)),
+            Err(TransferError::OutOfFunds)
+        );
+        assert_eq!(
+            journal.balance_incr(alice, U256::MAX),
+            Err(TransferError::OverflowPayment)
+        );
+        assert!(journal.account(&bob).is_none());
+        assert_eq!(journal.balance(&alice), U256::from(5));
+        assert!(journal.journal.is_empty());
+    }
+
+    #[test]
+    fn warm_cold_access() {
+        let (sender, token) = (Address::with_last_byte(0xa), Address::with_last_byte(0xb));
+        let mut journal = build_state(&[]);
+        journal.prewarm(
+            sender,
+            None,
+            &[AccessListItem {
+                address: token,
+                storage_keys: vec![U256::from(1)],
+            }],
+            [Address::with_last_byte(1)],
+        );
+        assert!(!journal.access_account(sender));
+        assert!(!journal.access_account(token));
+        assert!(!journal.access_account(Address::with_last_byte(1)));
+        assert!(!journal.access_storage(token, U256::from(1)));
+
+        let checkpoint = journal.checkpoint();
+        assert!(journal.access_storage(token, U256::from(2)));
+        assert!(!journal.access_storage(token, U256::from(2)));
+        assert!(journal.access_account(Address::with_last_byte(0xc)));
+        journal.revert(checkpoint);
+
+        // Reverted frames give back their warm accesses.
+        assert!(journal.access_storage(token, U256::from(2)));
+        assert!(journal.access_account(Address::with_last_byte(0xc)));
+
+        journal.finalize();
+        assert!(journal.access_account(sender));
+    }
+
+    #[test]
+    fn original_value() {
+        let contract = Address::with_last_byte(0xc);
+        let mut journal = build_state(&[(contract, 0)]);
+        journal.sstore(contract, U256::from(1), U256::from(5));
+        journal.finalize();
+
+        assert_eq!(
+            journal.original_value(&contract, U256::from(1)),
+            U256::from(5)
+        );
+        journal.sstore(contract, U256::from(1), U256::from(6));
+        journal.sstore(contract, U256::from(1), U256::from(7));
+        assert_eq!(
+            journal.original_value(&contract, U256::from(1)),
+            U256::from(5)
+        );
+    }
+
+    #[test]
+    fn finalize() {
+        let alice = Address::with_last_byte(0xa);
+        let mut journal = build_state(&[]);
+        journal.tstore(alice, U256::ZERO, U256::from(1));
+        journal.log(Log {
+            address: alice,
+            topics: vec![U256::from(1)],
+            data: Bytes::new(),
+        });
+
+        let logs = journal.finalize();
+        assert_eq!(logs.len(), 1);
+        assert!(journal.journal.is_empty());
+        assert_eq!(journal.tload(alice, U256::ZERO), U256::ZERO);
+    }
+
+    #[test]
+    fn end_of_transaction_cleanup() {
+        let (empty, funded, destroyed) = (
+            Address::with_last_byte(0xa),
+            Address::with_last_byte(0xb),
+            Address::with_last_byte(0xc),
+        );
+        let mut journal = build_state(&[(empty, 0), (funded, 1), (destroyed, 1)]);
+
+        journal.touch(empty);
+        journal.touch(funded);
+        let checkpoint = journal.checkpoint();
+        assert!(!journal.selfdestruct(destroyed));
+        assert!(journal.selfdestruct(destroyed));
+        journal.revert(checkpoint);
+        assert!(journal.destroyed.is_empty());
+
+        journal.selfdestruct(destroyed);
+        journal.remove_destroyed();
+        journal.remove_touched_empty();
+        assert!(journal.account(&empty).is_none());
+        assert!(journal.account(&destroyed).is_none());
+        assert!(journal.account(&funded).is_some());
+    }
+}
@@ -0,0 +1,180 @@
+use std::{fmt, str::FromStr};
+
+use bytes::Bytes;
+use hex::FromHexError;
+use ruint::aliases::U256;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sha3::{Digest, Keccak256};
+
+use super::rlp;
+
+/// 256 bit hash (keccak-256, storage roots etc).
+pub type B256 = [u8; 32];
+
+/// keccak-256 of the empty string, the code hash of every account without code.
+pub const KECCAK_EMPTY: B256 = [
+    0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0,
+    0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+];
+
+pub fn keccak256(data: impl AsRef<[u8]>) -> B256 {
+    Keccak256::digest(data.as_ref()).into()
+}
+
+/// `0x` prefixed or bare hex.
+pub fn parse_hex(s: &str) -> Result<Bytes, FromHexError> {
+    hex::decode(s.strip_prefix("0x").unwrap_or(s)).map(Bytes::from)
+}
+
+/// 160 bit account address.
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address(pub [u8; 20]);
+
+impl Address {
+    pub const ZERO: Self = Self([0u8; 20]);
+
+    /// Address with every byte zero except the last one, e.g. the precompiles.
+    pub const fn with_last_byte(byte: u8) -> Self {
+        let mut bytes = [0u8; 20];
+        bytes[19] = byte;
+        Self(bytes)
+    }
+
+    /// Panics if `slice` is not exactly 20 bytes.
+    pub fn from_slice(slice: &[u8]) -> Self {
+        Self(slice.try_into().expect("address must be 20 bytes"))
+    }
+
+    /// Takes the low 160 bits of a stack word.
+    pub fn from_word(word: U256) -> Self {
+        Self::from_slice(&word.to_be_bytes::<32>()[12..])
+    }
+
+    pub fn into_word(self) -> U256 {
+        U256::from_be_slice(&self.0)
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Address of a contract created by this account with CREATE, `keccak256(rlp([self, nonce]))`.
+    pub fn create(&self, nonce: u64) -> Self {
+        let mut out = Vec::with_capacity(31);
+        rlp::encode_list(&[self, &nonce], &mut out);
+        Self::from_slice(&keccak256(&out)[12..])
+    }
+
+    /// Address of a contract created by this account with CREATE2 (EIP-1014).
+    pub fn create2(&self, salt: B256, init_code_hash: B256) -> Self {
+        let mut preimage = [0u8; 85];
+        preimage[0] = 0xff;
+        preimage[1..21].copy_from_slice(&self.0);
+        preimage[21..53].copy_from_slice(&salt);
+        preimage[53..].copy_from_slice(&init_code_hash);
+        Self::from_slice(&keccak256(preimage)[12..])
+    }
+}
+
+impl FromStr for Address {
+    type Err = FromHexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice(s.strip_prefix("0x").unwrap_or(s), &mut bytes)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// As a `0x` prefixed hex string.
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keccak_empty() {
+        assert_eq!(keccak256([]), KECCAK_EMPTY);
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(parse_hex("0x0102").unwrap()[..], [1, 2]);
+        assert_eq!(parse_hex("0102").unwrap()[..], [1, 2]);
+        assert!(parse_hex("").unwrap().is_empty());
+        assert!(parse_hex("0x012").is_err());
+    }
+
+    #[test]
+    fn address_word_roundtrip() {
+        let address: Address = "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"
+            .parse()
+            .unwrap();
+        assert_eq!(Address::from_word(address.into_word()), address);
+        assert_eq!(
+            address.to_string(),
+            "0xd8da6bf26964af9d7eed9e03e53415d37aa96045"
+        );
+        assert_eq!(Address::with_last_byte(1).into_word(), U256::from(1));
+
+        let json = serde_json::to_string(&address).unwrap();
+        assert_eq!(json, "\"0xd8da6bf26964af9d7eed9e03e53415d37aa96045\"");
+        assert_eq!(serde_json::from_str::<Address>(&json).unwrap(), address);
+    }
+
+    #[test]
+    fn create_address() {
+        let sender: Address = "0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            sender.create(0).to_string(),
+            "0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"
+        );
+        assert_eq!(
+            sender.create(1).to_string(),
+            "0x343c43a37d37dff08ae8c4a11544c718abb4fcf8"
+        );
+    }
+
+    #[test]
+    fn create2_address() {
+        // Examples 0 and 1 of EIP-1014, init code 0x00 with a zero salt.
+        let init_code_hash = keccak256([0]);
+        assert_eq!(
+            Address::ZERO.create2([0; 32], init_code_hash).to_string(),
+            "0x4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38"
+        );
+        let deployer: Address = "0xdeadbeef00000000000000000000000000000000"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            deployer.create2([0; 32], init_code_hash).to_string(),
+            "0xb928f69bb1d91cd65274e3c79d8986362984fda3"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use super::{
+    primitives::{Address, B256, KECCAK_EMPTY},
+    rlp,
+    state::State,
+    trie::{self, ProofError, Result, Trie, EMPTY_ROOT},
+};
+
+/// A storage slot and the trie nodes proving its value, as returned by `eth_getProof`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StorageProof {
+    pub key: U256,
+    /// Zero for slots that are not in the storage trie.
+    pub value: U256,
+    pub proof: Vec<Bytes>,
+}
+
+/// An account and a selection of its storage, proven against the state root (EIP-1186).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountProof {
+    pub address: Address,
+    pub nonce: u64,
+    pub balance: U256,
+    pub code_hash: B256,
+    pub storage_hash: B256,
+    pub account_proof: Vec<Bytes>,
+    pub storage_proof: Vec<StorageProof>,
+}
+
+impl AccountProof {
+    /// Proves `address` and `slots` in `state`. An account that does not exist is proven absent
+    /// and reported as empty.
+    pub fn new(state: &State, address: Address, slots: &[U256]) -> Self {
+        let state_trie = Trie::new(state.iter().map(|(address, account)| {
+            (
+                trie::account_key(address).to_vec(),
+                trie::encode_account(account),
+            )
+        }));
+        let account_proof = state_trie.proof(&trie::account_key(&address));
+
+        let account = state.get(&address).cloned().unwrap_or_default();
+        let storage_trie = Trie::new(
+            account
+                .storage
+                .iter()
+                .filter(|(_, value)| !value.is_zero())
+                .map(|(slot, value)| (trie::storage_key(*slot).to_vec(), rlp::encode(value))),
+        );
+        let storage_proof = slots
+            .iter()
+            .map(|slot| StorageProof {
+                key: *slot,
+                value: account.storage.get(slot).copied().unwrap_or_default(),
+                proof: storage_trie.proof(&trie::storage_key(*slot)),
+            })
+            .collect();
+
+        Self {
+            address,
+            nonce: account.nonce,
+            balance: account.balance,
+            code_hash: account.code_hash,
+            storage_hash: storage_trie.root(),
+            account_proof,
+            storage_proof,
+        }
+    }
+
+    /// Checks the account against `state_root` and every storage slot against the storage hash
+    /// of the account.
+    pub fn verify(&self, state_root: B256) -> Result<()> {
+        let proven = trie::verify_proof(
+            state_root,
+            &trie::account_key(&self.address),
+            &self.account_proof,
+        )?;
+        let is_empty = self.nonce == 0
+            && self.balance.is_zero()
+            && self.code_hash == KECCAK_EMPTY
+            && self.storage_hash == EMPTY_ROOT;
+        match proven {
+            Some(value) if value == self.encode_account() => {}
+            // A proof of exclusion only backs an account without any state.
+            None if is_empty => {}
+            _ => return Err(ProofError::ValueMismatch),
+        }
+
+        for storage in &self.storage_proof {
+            let proven = trie::verify_proof(
+                self.storage_hash,
+                &trie::storage_key(storage.key),
+                &storage.proof,
+            )?;
+            let expected = (!storage.value.is_zero()).then(|| rlp::encode(&storage.value));
+            if proven != expected {
+                return Err(ProofError::ValueMismatch);
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_account(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        rlp::encode_list(
+            &[
+                &self.nonce,
+                &self.balance,
+                &self.storage_hash,
+                &self.code_hash,
+            ],
+            &mut out,
+        );
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::state::Account;
+
+    use super::*;
+
+    fn build_state() -> State {
+        let mut state = State::new();
+        for i in 1..=50 {
+            let mut account = Account::with_balance(U256::from(i));
+            for slot in 0..i {
+                account
+                    .storage
+                    .insert(U256::from(slot), U256::from(slot + 1));
+            }
+            state.insert(Address::with_last_byte(i), account);
+        }
+        state
+    }
+
+    #[test]
+    fn inclusion() {
+        let state = build_state();
+        let root = trie::state_root(&state);
+        let address = Address::with_last_byte(20);
+        let proof = AccountProof::new(&state, address, &[U256::from(3), U256::from(40)]);
+
+        assert_eq!(proof.balance, U256::from(20));
+        assert_eq!(
+            proof.storage_hash,
+            trie::storage_root(&state[&address].storage)
+        );
+        assert_eq!(proof.storage_proof[0].value, U256::from(4));
+        // Slot 40 was never written, its proof shows it absent.
+        assert_eq!(proof.storage_proof[1].value, U256::ZERO);
+        assert_eq!(proof.verify(root), Ok(()));
+
+        let mut forged = proof.clone();
+        forged.balance = U256::from(21);
+        assert_eq!(forged.verify(root), Err(ProofError::ValueMismatch));
+        forged = proof.clone();
+        forged.storage_proof[0].value = U256::from(5);
+        assert_eq!(forged.verify(root), Err(ProofError::ValueMismatch));
+        forged = proof;
+        forged.account_proof.pop();
+        assert_eq!(forged.verify(root), Err(ProofError::MissingNode));
+    }
+
+    #[test]
+    fn exclusion() {
+        let state = build_state();
+        let root = trie::state_root(&state);
+        let proof = AccountProof::new(&state, Address::with_last_byte(0xaa), &[U256::ZERO]);
+        assert_eq!(proof.code_hash, KECCAK_EMPTY);
+        assert_eq!(proof.storage_hash, EMPTY_ROOT);
+        assert!(proof.storage_proof[0].proof.is_empty());
+        assert_eq!(proof.verify(root), Ok(()));
+
+        // An absent account can not be passed off as having a balance.
+        let mut forged = proof;
+        forged.balance = U256::from(1);
+        assert_eq!(forged.verify(root), Err(ProofError::ValueMismatch));
+
+        // Nor can a proof be checked against a different root.
+        let proof = AccountProof::new(&state, Address::with_last_byte(1), &[]);
+        let err = proof.verify(EMPTY_ROOT).unwrap_err();
+        assert!(matches!(err, ProofError::NodeHashMismatch { .. }));
+    }
+}
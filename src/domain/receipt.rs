@@ -0,0 +1,199 @@
+use std::fmt;
+
+use super::{
+    primitives::{keccak256, B256},
+    rlp::{self, Decodable, Encodable},
+    state::Log,
+    trie,
+};
+
+pub const BLOOM_SIZE: usize = 256;
+
+/// 2048 bit bloom filter over the addresses and topics of logs.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Bloom(pub [u8; BLOOM_SIZE]);
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self([0; BLOOM_SIZE])
+    }
+}
+
+impl Bloom {
+    /// Sets the three bits selected by the first six bytes of the keccak-256 of `input`.
+    pub fn accrue(&mut self, input: &[u8]) {
+        let hash = keccak256(input);
+        for i in 0..3 {
+            let bit = (((hash[2 * i] as usize) << 8) | hash[2 * i + 1] as usize) & 2047;
+            self.0[BLOOM_SIZE - 1 - bit / 8] |= 1 << (bit % 8);
+        }
+    }
+
+    pub fn accrue_log(&mut self, log: &Log) {
+        self.accrue(log.address.as_slice());
+        for topic in &log.topics {
+            self.accrue(&topic.to_be_bytes::<32>());
+        }
+    }
+
+    pub fn accrue_bloom(&mut self, other: &Bloom) {
+        for (byte, other) in self.0.iter_mut().zip(other.0) {
+            *byte |= other;
+        }
+    }
+
+    /// False if `input` was certainly not accrued.
+    pub fn contains(&self, input: &[u8]) -> bool {
+        let mut bloom = Self::default();
+        bloom.accrue(input);
+        self.0
+            .iter()
+            .zip(bloom.0)
+            .all(|(byte, bits)| byte & bits == bits)
+    }
+
+    pub fn from_logs(logs: &[Log]) -> Self {
+        let mut bloom = Self::default();
+        for log in logs {
+            bloom.accrue_log(log);
+        }
+        bloom
+    }
+}
+
+impl fmt::Debug for Bloom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl Encodable for Bloom {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+    }
+}
+
+impl Decodable for Bloom {
+    fn decode(buf: &mut &[u8]) -> rlp::Result<Self> {
+        Ok(Self(Decodable::decode(buf)?))
+    }
+}
+
+/// `[address, [topic, ..], data]`, the topics as 32 byte strings.
+impl Encodable for Log {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let topics: Vec<B256> = self
+            .topics
+            .iter()
+            .map(|topic| topic.to_be_bytes())
+            .collect();
+        rlp::encode_list(&[&self.address, &topics, &self.data], out);
+    }
+}
+
+/// The outcome of a transaction as committed to by the receipts root of the block.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Receipt {
+    /// EIP-2718 type of the transaction.
+    pub tx_type: u8,
+    pub success: bool,
+    /// Before Byzantium receipts hold the state root after the transaction instead of the status.
+    pub post_state: Option<B256>,
+    /// Gas used in the block up to and including this transaction.
+    pub cumulative_gas_used: u64,
+    pub logs: Vec<Log>,
+    pub logs_bloom: Bloom,
+}
+
+impl Receipt {
+    /// The receipt as stored in the trie, prefixed with the type for typed transactions.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        if self.tx_type != 0 {
+            out.push(self.tx_type);
+        }
+        let status: &dyn Encodable = match &self.post_state {
+            Some(post_state) => post_state,
+            None => &self.success,
+        };
+        rlp::encode_list(
+            &[
+                status,
+                &self.cumulative_gas_used,
+                &self.logs_bloom,
+                &self.logs,
+            ],
+            &mut out,
+        );
+        out
+    }
+}
+
+pub fn receipts_root(receipts: &[Receipt]) -> B256 {
+    trie::ordered_trie_root(receipts.iter().map(Receipt::encode))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use ruint::aliases::U256;
+
+    use crate::domain::primitives::Address;
+
+    use super::*;
+
+    fn log() -> Log {
+        Log {
+            address: Address([0x11; 20]),
+            topics: vec![U256::from(1)],
+            data: Bytes::from_static(&[0xff]),
+        }
+    }
+
+    #[test]
+    fn bloom() {
+        let mut bloom = Bloom::default();
+        bloom.accrue_log(&log());
+        assert_eq!(bloom.0.iter().map(|byte| byte.count_ones()).sum::<u32>(), 6);
+        assert!(bloom.contains(&[0x11; 20]));
+        assert!(bloom.contains(&U256::from(1).to_be_bytes::<32>()));
+        assert!(!bloom.contains(&[0x22; 20]));
+
+        // keccak256("") = c5d2 4601 86f7.., bits 0x5d2, 0x601, 0x6f7.
+        let mut bloom = Bloom::default();
+        bloom.accrue(&[]);
+        assert_eq!(bloom.0[255 - 0x5d2 / 8], 1 << (0x5d2 % 8));
+        assert_eq!(bloom.0[255 - 0x601 / 8], 1 << (0x601 % 8));
+        assert_eq!(bloom.0[255 - 0x6f7 / 8], 1 << (0x6f7 % 8));
+
+        let mut combined = Bloom::from_logs(&[log()]);
+        combined.accrue_bloom(&bloom);
+        assert!(combined.contains(&[]) && combined.contains(&[0x11; 20]));
+    }
+
+    #[test]
+    fn encoding() {
+        let mut receipt = Receipt {
+            success: true,
+            cumulative_gas_used: 21000,
+            ..Default::default()
+        };
+        let legacy = receipt.encode();
+        // [0x01, 0x825208, bloom, []]
+        assert_eq!(legacy[..3], [0xf9, 0x01, 0x08]);
+        assert_eq!(legacy[3..7], [0x01, 0x82, 0x52, 0x08]);
+        assert_eq!(legacy.last(), Some(&rlp::EMPTY_LIST));
+
+        receipt.tx_type = 2;
+        assert_eq!(receipt.encode()[0], 2);
+        assert_eq!(receipt.encode()[1..], legacy);
+
+        receipt.post_state = Some([0xaa; 32]);
+        assert_eq!(receipt.encode()[4..6], [0xa0, 0xaa]);
+        assert_eq!(receipts_root(&[]), trie::EMPTY_ROOT);
+
+        let mut out = Vec::new();
+        log().encode(&mut out);
+        assert_eq!(out[..3], [0xf8, 0x39, 0x94]);
+    }
+}
@@ -0,0 +1,547 @@
+//! Recursive Length Prefix, the serialization of transactions, receipts and trie nodes.
+//!
+//! Values implement [`Encodable`] and [`Decodable`]. Structs are encoded as a list of their
+//! fields with [`encode_list`] and decoded with [`decode_list`]. Decoding only accepts the
+//! canonical encoding of a value, anything else is rejected.
+
+use std::fmt;
+
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use super::primitives::Address;
+
+pub type Result<T> = std::result::Result<T, RlpError>;
+
+/// Offset of the first byte of strings, short lists start at `EMPTY_LIST`.
+pub const EMPTY_STRING: u8 = 0x80;
+pub const EMPTY_LIST: u8 = 0xc0;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RlpError {
+    /// The input ended `needed` bytes short of the item.
+    InputTooShort {
+        needed: usize,
+    },
+    /// Bytes were left over after the item.
+    TrailingBytes(usize),
+    /// A single byte below 0x80 wrapped in a string header, it encodes as itself.
+    NonCanonicalSingleByte,
+    /// A length below 56 in the long form.
+    NonCanonicalSize,
+    /// A integer or length with leading zero bytes.
+    LeadingZero,
+    UnexpectedList,
+    UnexpectedString,
+    /// A integer that does not fit the type decoded into.
+    Overflow,
+    /// A fixed size value, e.g. a address, of the wrong length.
+    UnexpectedLength {
+        expected: usize,
+        got: usize,
+    },
+    /// The items of a list did not add up to its length.
+    ListLengthMismatch {
+        expected: usize,
+        got: usize,
+    },
+    Custom(&'static str),
+}
+
+impl fmt::Display for RlpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InputTooShort { needed } => {
+                write!(f, "input too short, {needed} more bytes needed")
+            }
+            Self::TrailingBytes(len) => write!(f, "{len} trailing bytes after the item"),
+            Self::NonCanonicalSingleByte => {
+                write!(f, "single byte below 0x80 with a string header")
+            }
+            Self::NonCanonicalSize => write!(f, "length below 56 encoded in the long form"),
+            Self::LeadingZero => write!(f, "leading zero bytes"),
+            Self::UnexpectedList => write!(f, "expected a string, got a list"),
+            Self::UnexpectedString => write!(f, "expected a list, got a string"),
+            Self::Overflow => write!(f, "integer does not fit the type"),
+            Self::UnexpectedLength { expected, got } => {
+                write!(f, "expected {expected} bytes, got {got}")
+            }
+            Self::ListLengthMismatch { expected, got } => {
+                write!(f, "list is {expected} bytes but its items are {got}")
+            }
+            Self::Custom(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for RlpError {}
+
+/// The prefix of a item, everything but the payload.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Header {
+    pub list: bool,
+    pub payload_length: usize,
+}
+
+impl Header {
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        let offset = if self.list { EMPTY_LIST } else { EMPTY_STRING };
+        if self.payload_length < 56 {
+            out.push(offset + self.payload_length as u8);
+        } else {
+            let len_bytes = self.payload_length.to_be_bytes();
+            let len_bytes = &len_bytes[self.payload_length.leading_zeros() as usize / 8..];
+            out.push(offset + 55 + len_bytes.len() as u8);
+            out.extend_from_slice(len_bytes);
+        }
+    }
+
+    /// Length of the encoded header.
+    pub fn length(&self) -> usize {
+        if self.payload_length < 56 {
+            1
+        } else {
+            1 + (usize::BITS - self.payload_length.leading_zeros()).div_ceil(8) as usize
+        }
+    }
+
+    /// Reads the header, advancing `buf` to the start of the payload. A single byte below 0x80
+    /// is its own payload, `buf` is left as is.
+    pub fn decode(buf: &mut &[u8]) -> Result<Self> {
+        let first = *buf.first().ok_or(RlpError::InputTooShort { needed: 1 })?;
+        let header = match first {
+            0..EMPTY_STRING => {
+                return Ok(Self {
+                    list: false,
+                    payload_length: 1,
+                })
+            }
+            EMPTY_STRING..=0xb7 => {
+                *buf = &buf[1..];
+                let payload_length = (first - EMPTY_STRING) as usize;
+                if payload_length == 1 && buf.first().is_some_and(|byte| *byte < EMPTY_STRING) {
+                    return Err(RlpError::NonCanonicalSingleByte);
+                }
+                Self {
+                    list: false,
+                    payload_length,
+                }
+            }
+            0xb8..EMPTY_LIST | 0xf8.. => {
+                *buf = &buf[1..];
+                let list = first >= EMPTY_LIST;
+                let offset = if list { 0xf7 } else { 0xb7 };
+                let len_of_len = (first - offset) as usize;
+                let len_bytes = take(buf, len_of_len)?;
+                if len_bytes[0] == 0 {
+                    return Err(RlpError::LeadingZero);
+                }
+                if len_of_len > size_of::<usize>() {
+                    return Err(RlpError::Overflow);
+                }
+                let payload_length = len_bytes
+                    .iter()
+                    .fold(0usize, |len, byte| (len << 8) | *byte as usize);
+                if payload_length < 56 {
+                    return Err(RlpError::NonCanonicalSize);
+                }
+                Self {
+                    list,
+                    payload_length,
+                }
+            }
+            EMPTY_LIST..=0xf7 => {
+                *buf = &buf[1..];
+                Self {
+                    list: true,
+                    payload_length: (first - EMPTY_LIST) as usize,
+                }
+            }
+        };
+        if buf.len() < header.payload_length {
+            return Err(RlpError::InputTooShort {
+                needed: header.payload_length - buf.len(),
+            });
+        }
+        Ok(header)
+    }
+
+    /// Reads a string header and returns its payload, advancing `buf` past it.
+    pub fn decode_bytes<'a>(buf: &mut &'a [u8]) -> Result<&'a [u8]> {
+        let header = Self::decode(buf)?;
+        if header.list {
+            return Err(RlpError::UnexpectedList);
+        }
+        take(buf, header.payload_length)
+    }
+}
+
+/// Splits `len` bytes off the front of `buf`.
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if buf.len() < len {
+        return Err(RlpError::InputTooShort {
+            needed: len - buf.len(),
+        });
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+pub trait Encodable {
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Length of the encoding.
+    fn length(&self) -> usize {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out.len()
+    }
+}
+
+pub trait Decodable: Sized {
+    /// Decodes one item from the front of `buf` and advances past it.
+    fn decode(buf: &mut &[u8]) -> Result<Self>;
+}
+
+pub fn encode<T: Encodable + ?Sized>(value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.encode(&mut out);
+    out
+}
+
+/// Decodes `buf` as exactly one item.
+pub fn decode_exact<T: Decodable>(mut buf: &[u8]) -> Result<T> {
+    let value = T::decode(&mut buf)?;
+    if !buf.is_empty() {
+        return Err(RlpError::TrailingBytes(buf.len()));
+    }
+    Ok(value)
+}
+
+/// Encodes a byte string, a single byte below 0x80 is its own encoding.
+pub fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    if let [byte] = bytes {
+        if *byte < EMPTY_STRING {
+            out.push(*byte);
+            return;
+        }
+    }
+    Header {
+        list: false,
+        payload_length: bytes.len(),
+    }
+    .encode(out);
+    out.extend_from_slice(bytes);
+}
+
+/// Encodes the fields of a struct, in order, as a list.
+pub fn encode_list(fields: &[&dyn Encodable], out: &mut Vec<u8>) {
+    let mut payload = Vec::new();
+    for field in fields {
+        field.encode(&mut payload);
+    }
+    Header {
+        list: true,
+        payload_length: payload.len(),
+    }
+    .encode(out);
+    out.extend_from_slice(&payload);
+}
+
+/// Decodes a list with `decode_items`, which has to consume exactly the payload of the list.
+pub fn decode_list<T>(
+    buf: &mut &[u8],
+    decode_items: impl FnOnce(&mut &[u8]) -> Result<T>,
+) -> Result<T> {
+    let header = Header::decode(buf)?;
+    if !header.list {
+        return Err(RlpError::UnexpectedString);
+    }
+    let mut payload = take(buf, header.payload_length)?;
+    let value = decode_items(&mut payload)?;
+    if !payload.is_empty() {
+        return Err(RlpError::ListLengthMismatch {
+            expected: header.payload_length,
+            got: header.payload_length - payload.len(),
+        });
+    }
+    Ok(value)
+}
+
+/// Splits a list into the raw encodings of its items, without decoding them.
+pub fn split_list(mut buf: &[u8]) -> Result<Vec<&[u8]>> {
+    let header = Header::decode(&mut buf)?;
+    if !header.list {
+        return Err(RlpError::UnexpectedString);
+    }
+    if buf.len() != header.payload_length {
+        return Err(RlpError::TrailingBytes(buf.len() - header.payload_length));
+    }
+    let mut items = Vec::new();
+    while !buf.is_empty() {
+        let start = buf;
+        let header = Header::decode(&mut buf)?;
+        take(&mut buf, header.payload_length)?;
+        items.push(&start[..start.len() - buf.len()]);
+    }
+    Ok(items)
+}
+
+/// Big-endian bytes of a integer with the leading zeros stripped, how integers are encoded.
+fn decode_uint<const N: usize>(buf: &mut &[u8]) -> Result<[u8; N]> {
+    let bytes = Header::decode_bytes(buf)?;
+    if bytes.first() == Some(&0) {
+        return Err(RlpError::LeadingZero);
+    }
+    if bytes.len() > N {
+        return Err(RlpError::Overflow);
+    }
+    let mut padded = [0u8; N];
+    padded[N - bytes.len()..].copy_from_slice(bytes);
+    Ok(padded)
+}
+
+macro_rules! impl_uint {
+    ($($t:ty),*) => {$(
+        impl Encodable for $t {
+            fn encode(&self, out: &mut Vec<u8>) {
+                let bytes = self.to_be_bytes();
+                encode_bytes(&bytes[self.leading_zeros() as usize / 8..], out);
+            }
+        }
+
+        impl Decodable for $t {
+            fn decode(buf: &mut &[u8]) -> Result<Self> {
+                decode_uint(buf).map(<$t>::from_be_bytes)
+            }
+        }
+    )*};
+}
+
+impl_uint!(u8, u16, u32, u64, u128, usize);
+
+impl Encodable for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u8).encode(out)
+    }
+}
+
+impl Decodable for bool {
+    fn decode(buf: &mut &[u8]) -> Result<Self> {
+        match u8::decode(buf)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(RlpError::Custom("bool must be 0 or 1")),
+        }
+    }
+}
+
+impl Encodable for U256 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let bytes = self.to_be_bytes::<32>();
+        encode_bytes(&bytes[self.leading_zeros() / 8..], out);
+    }
+}
+
+impl Decodable for U256 {
+    fn decode(buf: &mut &[u8]) -> Result<Self> {
+        decode_uint::<32>(buf).map(U256::from_be_bytes)
+    }
+}
+
+impl Encodable for Bytes {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_bytes(self, out)
+    }
+}
+
+impl Decodable for Bytes {
+    fn decode(buf: &mut &[u8]) -> Result<Self> {
+        Header::decode_bytes(buf).map(Bytes::copy_from_slice)
+    }
+}
+
+/// Fixed size byte strings, e.g. hashes.
+impl<const N: usize> Encodable for [u8; N] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_bytes(self, out)
+    }
+}
+
+impl<const N: usize> Decodable for [u8; N] {
+    fn decode(buf: &mut &[u8]) -> Result<Self> {
+        let bytes = Header::decode_bytes(buf)?;
+        bytes.try_into().map_err(|_| RlpError::UnexpectedLength {
+            expected: N,
+            got: bytes.len(),
+        })
+    }
+}
+
+impl Encodable for Address {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out)
+    }
+}
+
+impl Decodable for Address {
+    fn decode(buf: &mut &[u8]) -> Result<Self> {
+        <[u8; 20]>::decode(buf).map(Self)
+    }
+}
+
+impl<T: Encodable> Encodable for [T] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let fields: Vec<&dyn Encodable> = self.iter().map(|item| item as _).collect();
+        encode_list(&fields, out)
+    }
+}
+
+impl<T: Encodable> Encodable for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_slice().encode(out)
+    }
+}
+
+impl<T: Decodable> Decodable for Vec<T> {
+    fn decode(buf: &mut &[u8]) -> Result<Self> {
+        decode_list(buf, |payload| {
+            let mut items = Vec::new();
+            while !payload.is_empty() {
+                items.push(T::decode(payload)?);
+            }
+            Ok(items)
+        })
+    }
+}
+
+impl<T: Encodable + ?Sized> Encodable for &T {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roundtrip<T: Encodable + Decodable + PartialEq + fmt::Debug>(value: T, rlp: &[u8]) {
+        assert_eq!(encode(&value), rlp);
+        assert_eq!(value.length(), rlp.len());
+        assert_eq!(decode_exact::<T>(rlp).unwrap(), value);
+    }
+
+    #[test]
+    fn integers() {
+        assert_roundtrip(0u64, &[0x80]);
+        assert_roundtrip(0x0fu64, &[0x0f]);
+        assert_roundtrip(0x80u64, &[0x81, 0x80]);
+        assert_roundtrip(1024u64, &[0x82, 0x04, 0x00]);
+        assert_roundtrip(true, &[0x01]);
+        assert_roundtrip(U256::ZERO, &[0x80]);
+        assert_roundtrip(U256::from(0x0100_0000u64), &[0x84, 1, 0, 0, 0]);
+
+        let mut max = vec![0xa0];
+        max.extend_from_slice(&[0xff; 32]);
+        assert_roundtrip(U256::MAX, &max);
+    }
+
+    #[test]
+    fn strings_and_lists() {
+        assert_roundtrip(Bytes::new(), &[0x80]);
+        assert_roundtrip(Bytes::from_static(b"dog"), b"\x83dog");
+        assert_roundtrip(Bytes::from_static(&[0x7f]), &[0x7f]);
+
+        let long = Bytes::from(vec![0xaa; 56]);
+        let mut rlp = vec![0xb8, 56];
+        rlp.extend_from_slice(&long);
+        assert_roundtrip(long, &rlp);
+
+        assert_roundtrip(Vec::<u64>::new(), &[0xc0]);
+        assert_roundtrip(
+            vec![Bytes::from_static(b"cat"), Bytes::from_static(b"dog")],
+            b"\xc8\x83cat\x83dog",
+        );
+        assert_roundtrip(vec![vec![], vec![0u8]], &[0xc3, 0xc0, 0xc1, 0x80]);
+        assert_roundtrip(Address::with_last_byte(1), &{
+            let mut rlp = [0u8; 21];
+            rlp[0] = 0x94;
+            rlp[20] = 1;
+            rlp
+        });
+    }
+
+    #[test]
+    fn struct_fields() {
+        let mut out = Vec::new();
+        encode_list(&[&1u64, &Bytes::from_static(b"dog")], &mut out);
+        assert_eq!(out, b"\xc5\x01\x83dog");
+
+        let decoded = decode_list(&mut out.as_slice(), |buf| {
+            Ok((u64::decode(buf)?, Bytes::decode(buf)?))
+        });
+        assert_eq!(decoded, Ok((1, Bytes::from_static(b"dog"))));
+
+        // A field too few leaves part of the list unread.
+        let decoded = decode_list(&mut out.as_slice(), u64::decode);
+        assert_eq!(
+            decoded,
+            Err(RlpError::ListLengthMismatch {
+                expected: 5,
+                got: 1
+            })
+        );
+    }
+
+    #[test]
+    fn split_raw_items() {
+        let rlp = b"\xc8\x83cat\xc1\x01\x02\x80";
+        assert_eq!(
+            split_list(rlp),
+            Ok(vec![&b"\x83cat"[..], &[0xc1, 0x01], &[0x02], &[0x80]])
+        );
+        assert_eq!(split_list(&[0x80]), Err(RlpError::UnexpectedString));
+    }
+
+    #[test]
+    fn rejects_non_canonical() {
+        assert_eq!(decode_exact::<u64>(&[0x00]), Err(RlpError::LeadingZero));
+        assert_eq!(
+            decode_exact::<u64>(&[0x82, 0x00, 0x01]),
+            Err(RlpError::LeadingZero)
+        );
+        assert_eq!(
+            decode_exact::<u64>(&[0x81, 0x05]),
+            Err(RlpError::NonCanonicalSingleByte)
+        );
+        assert_eq!(
+            decode_exact::<Bytes>(&[0xb8, 0x02, 0xaa, 0xbb]),
+            Err(RlpError::NonCanonicalSize)
+        );
+        assert_eq!(
+            decode_exact::<u8>(&[0x82, 0x01, 0x00]),
+            Err(RlpError::Overflow)
+        );
+        assert_eq!(
+            decode_exact::<Bytes>(&[0x83, b'd', b'o']),
+            Err(RlpError::InputTooShort { needed: 1 })
+        );
+        assert_eq!(
+            decode_exact::<u64>(&[0x01, 0x02]),
+            Err(RlpError::TrailingBytes(1))
+        );
+        assert_eq!(decode_exact::<u64>(&[0xc0]), Err(RlpError::UnexpectedList));
+        assert_eq!(
+            decode_exact::<Vec<u64>>(&[0x80]),
+            Err(RlpError::UnexpectedString)
+        );
+        assert_eq!(
+            decode_exact::<Address>(&[0x82, 0x01, 0x02]),
+            Err(RlpError::UnexpectedLength {
+                expected: 20,
+                got: 2
+            })
+        );
+    }
+}
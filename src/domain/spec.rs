@@ -0,0 +1,88 @@
+use std::{fmt, str::FromStr};
+
+/// The hard forks, in activation order. Forks that only moved the difficulty bomb are left out.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SpecId {
+    Frontier,
+    Homestead,
+    TangerineWhistle,
+    SpuriousDragon,
+    Byzantium,
+    Constantinople,
+    Petersburg,
+    Istanbul,
+    Berlin,
+    London,
+    Paris,
+    Shanghai,
+    #[default]
+    Cancun,
+}
+
+impl SpecId {
+    /// True if the rules of `fork` apply under `self`.
+    pub fn is_enabled_in(self, fork: SpecId) -> bool {
+        self >= fork
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownSpec(pub String);
+
+impl fmt::Display for UnknownSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown fork {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownSpec {}
+
+impl FromStr for SpecId {
+    type Err = UnknownSpec;
+
+    /// Accepts the fork names used by the ethereum/tests fixtures.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Frontier" => Self::Frontier,
+            "Homestead" => Self::Homestead,
+            "EIP150" | "TangerineWhistle" => Self::TangerineWhistle,
+            "EIP158" | "SpuriousDragon" => Self::SpuriousDragon,
+            "Byzantium" => Self::Byzantium,
+            "Constantinople" => Self::Constantinople,
+            "ConstantinopleFix" | "Petersburg" => Self::Petersburg,
+            "Istanbul" | "MuirGlacier" => Self::Istanbul,
+            "Berlin" => Self::Berlin,
+            "London" | "ArrowGlacier" | "GrayGlacier" => Self::London,
+            "Merge" | "Paris" => Self::Paris,
+            "Shanghai" => Self::Shanghai,
+            "Cancun" => Self::Cancun,
+            _ => return Err(UnknownSpec(s.to_string())),
+        })
+    }
+}
+
+impl fmt::Display for SpecId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ordering() {
+        assert!(SpecId::Cancun.is_enabled_in(SpecId::Berlin));
+        assert!(SpecId::Berlin.is_enabled_in(SpecId::Berlin));
+        assert!(!SpecId::Istanbul.is_enabled_in(SpecId::Berlin));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("Merge".parse(), Ok(SpecId::Paris));
+        assert_eq!("ConstantinopleFix".parse(), Ok(SpecId::Petersburg));
+        assert_eq!(SpecId::Shanghai.to_string().parse(), Ok(SpecId::Shanghai));
+        assert!("Prague".parse::<SpecId>().is_err());
+    }
+}
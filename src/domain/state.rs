@@ -1,32 +1,69 @@
-// use std::vec::{self, Vec};
-
-// use super::constants::U256;
-// use hashbrown::HashMap;
-
-// #[derive(Debug, Clone)]
-// pub struct Account {
-//     nonce: u64,
-//     balance: U256,
-//     code: Vec<u8>,
-//     storage: HashMap<U256, u64>,
-// }
-
-// impl Default for Account {
-//     /// This denotes an empty account.
-//     fn default() -> Self {
-//         Self {
-//             nonce: 0,
-//             balance: U256::from(0),
-//             code: Vec::new(),
-//             storage: HashMap::new(),
-//         }
-//     }
-// }
-
-// #[derive(Debug, Clone)]
-// pub struct Transaction {
-//     to: u64,
-//     value: u64,
-//     data: Vec<u8>,
-//     sender: u64,
-// }
+use bytes::Bytes;
+use hashbrown::HashMap;
+use ruint::aliases::U256;
+
+use super::primitives::{keccak256, Address, B256, KECCAK_EMPTY};
+
+/// The world state, every account that exists keyed by its address.
+pub type State = HashMap<Address, Account>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub nonce: u64,
+    pub balance: U256,
+    pub code: Bytes,
+    pub code_hash: B256,
+    pub storage: HashMap<U256, U256>,
+}
+
+impl Default for Account {
+    /// This denotes an empty account.
+    fn default() -> Self {
+        Self {
+            nonce: 0,
+            balance: U256::ZERO,
+            code: Bytes::new(),
+            code_hash: KECCAK_EMPTY,
+            storage: HashMap::new(),
+        }
+    }
+}
+
+impl Account {
+    pub fn with_balance(balance: U256) -> Self {
+        Self {
+            balance,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_code(code: Bytes) -> Self {
+        let mut account = Self::default();
+        account.set_code(code);
+        account
+    }
+
+    pub fn set_code(&mut self, code: Bytes) {
+        self.code_hash = keccak256(&code);
+        self.code = code;
+    }
+
+    /// A account is empty if it has no code, zero nonce and zero balance.
+    pub fn is_empty(&self) -> bool {
+        self.code_hash == KECCAK_EMPTY && self.nonce == 0 && self.balance.is_zero()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Log {
+    pub address: Address,
+    pub topics: Vec<U256>,
+    pub data: Bytes,
+}
+
+/// A entry of a EIP-2930 access list, pre-warming the address and storage keys.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessListItem {
+    pub address: Address,
+    pub storage_keys: Vec<U256>,
+}
@@ -0,0 +1,813 @@
+use std::fmt;
+
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use crate::precompiles::ecrecover::ecrecover;
+
+use super::{
+    env::{TransactTo, TxEnv, GAS_PER_BLOB},
+    primitives::{keccak256, Address, B256},
+    rlp::{self, Decodable, Encodable, Header, RlpError, EMPTY_LIST},
+    spec::SpecId,
+    state::AccessListItem,
+};
+
+pub type Result<T> = std::result::Result<T, TransactionError>;
+
+/// Order of the secp256k1 curve.
+pub const SECP256K1N: U256 = U256::from_limbs([
+    0xbfd2_5e8c_d036_4141,
+    0xbaae_dce6_af48_a03b,
+    0xffff_ffff_ffff_fffe,
+    0xffff_ffff_ffff_ffff,
+]);
+/// EIP-2: signatures with a `s` above this are rejected, only one of `s` and `n - s` is valid.
+pub const SECP256K1N_HALF: U256 = U256::from_limbs([
+    0xdfe9_2f46_681b_20a0,
+    0x5d57_6e73_57a4_501d,
+    0xffff_ffff_ffff_ffff,
+    0x7fff_ffff_ffff_ffff,
+]);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TransactionError {
+    Rlp(RlpError),
+    /// A EIP-2718 type this crate does not know.
+    UnsupportedType(u8),
+    /// A legacy `v` that is neither 27, 28 nor EIP-155.
+    InvalidV(U256),
+    InvalidSignature,
+}
+
+impl From<RlpError> for TransactionError {
+    fn from(error: RlpError) -> Self {
+        Self::Rlp(error)
+    }
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rlp(error) => write!(f, "invalid rlp: {error}"),
+            Self::UnsupportedType(tx_type) => write!(f, "unsupported transaction type {tx_type}"),
+            Self::InvalidV(v) => write!(f, "invalid v {v}"),
+            Self::InvalidSignature => write!(f, "invalid signature"),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+/// A pre-EIP-2718 transaction, replay protected by EIP-155 when it carries a chain id.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TxLegacy {
+    pub chain_id: Option<u64>,
+    pub nonce: u64,
+    pub gas_price: U256,
+    pub gas_limit: u64,
+    pub to: TransactTo,
+    pub value: U256,
+    pub input: Bytes,
+}
+
+/// EIP-2930: Optional access lists
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TxEip2930 {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub gas_price: U256,
+    pub gas_limit: u64,
+    pub to: TransactTo,
+    pub value: U256,
+    pub input: Bytes,
+    pub access_list: Vec<AccessListItem>,
+}
+
+/// EIP-1559: Fee market change for ETH 1.0 chain
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TxEip1559 {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: u64,
+    pub to: TransactTo,
+    pub value: U256,
+    pub input: Bytes,
+    pub access_list: Vec<AccessListItem>,
+}
+
+/// EIP-4844: Shard Blob Transactions, these can not create contracts.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TxEip4844 {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: u64,
+    pub to: Address,
+    pub value: U256,
+    pub input: Bytes,
+    pub access_list: Vec<AccessListItem>,
+    pub max_fee_per_blob_gas: U256,
+    pub blob_versioned_hashes: Vec<B256>,
+}
+
+/// A unsigned transaction of any of the supported EIP-2718 types.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Transaction {
+    Legacy(TxLegacy),
+    AccessList(TxEip2930),
+    DynamicFee(TxEip1559),
+    Blob(TxEip4844),
+}
+
+impl Default for Transaction {
+    fn default() -> Self {
+        Self::Legacy(TxLegacy::default())
+    }
+}
+
+impl Transaction {
+    /// The EIP-2718 type byte, zero for legacy transactions.
+    pub fn tx_type(&self) -> u8 {
+        match self {
+            Self::Legacy(_) => 0,
+            Self::AccessList(_) => 1,
+            Self::DynamicFee(_) => 2,
+            Self::Blob(_) => 3,
+        }
+    }
+
+    /// `None` only for legacy transactions without EIP-155 replay protection.
+    pub fn chain_id(&self) -> Option<u64> {
+        match self {
+            Self::Legacy(tx) => tx.chain_id,
+            Self::AccessList(tx) => Some(tx.chain_id),
+            Self::DynamicFee(tx) => Some(tx.chain_id),
+            Self::Blob(tx) => Some(tx.chain_id),
+        }
+    }
+
+    pub fn nonce(&self) -> u64 {
+        match self {
+            Self::Legacy(tx) => tx.nonce,
+            Self::AccessList(tx) => tx.nonce,
+            Self::DynamicFee(tx) => tx.nonce,
+            Self::Blob(tx) => tx.nonce,
+        }
+    }
+
+    pub fn gas_limit(&self) -> u64 {
+        match self {
+            Self::Legacy(tx) => tx.gas_limit,
+            Self::AccessList(tx) => tx.gas_limit,
+            Self::DynamicFee(tx) => tx.gas_limit,
+            Self::Blob(tx) => tx.gas_limit,
+        }
+    }
+
+    pub fn to(&self) -> TransactTo {
+        match self {
+            Self::Legacy(tx) => tx.to,
+            Self::AccessList(tx) => tx.to,
+            Self::DynamicFee(tx) => tx.to,
+            Self::Blob(tx) => TransactTo::Call(tx.to),
+        }
+    }
+
+    pub fn value(&self) -> U256 {
+        match self {
+            Self::Legacy(tx) => tx.value,
+            Self::AccessList(tx) => tx.value,
+            Self::DynamicFee(tx) => tx.value,
+            Self::Blob(tx) => tx.value,
+        }
+    }
+
+    pub fn input(&self) -> &Bytes {
+        match self {
+            Self::Legacy(tx) => &tx.input,
+            Self::AccessList(tx) => &tx.input,
+            Self::DynamicFee(tx) => &tx.input,
+            Self::Blob(tx) => &tx.input,
+        }
+    }
+
+    pub fn access_list(&self) -> &[AccessListItem] {
+        match self {
+            Self::Legacy(_) => &[],
+            Self::AccessList(tx) => &tx.access_list,
+            Self::DynamicFee(tx) => &tx.access_list,
+            Self::Blob(tx) => &tx.access_list,
+        }
+    }
+
+    /// The gas price of transactions before EIP-1559, the max fee per gas after.
+    pub fn max_fee_per_gas(&self) -> U256 {
+        match self {
+            Self::Legacy(tx) => tx.gas_price,
+            Self::AccessList(tx) => tx.gas_price,
+            Self::DynamicFee(tx) => tx.max_fee_per_gas,
+            Self::Blob(tx) => tx.max_fee_per_gas,
+        }
+    }
+
+    pub fn max_priority_fee_per_gas(&self) -> Option<U256> {
+        match self {
+            Self::Legacy(_) | Self::AccessList(_) => None,
+            Self::DynamicFee(tx) => Some(tx.max_priority_fee_per_gas),
+            Self::Blob(tx) => Some(tx.max_priority_fee_per_gas),
+        }
+    }
+
+    pub fn max_fee_per_blob_gas(&self) -> Option<U256> {
+        match self {
+            Self::Blob(tx) => Some(tx.max_fee_per_blob_gas),
+            _ => None,
+        }
+    }
+
+    pub fn blob_versioned_hashes(&self) -> &[B256] {
+        match self {
+            Self::Blob(tx) => &tx.blob_versioned_hashes,
+            _ => &[],
+        }
+    }
+
+    pub fn blob_gas(&self) -> u64 {
+        self.blob_versioned_hashes().len() as u64 * GAS_PER_BLOB
+    }
+
+    /// The price paid per unit of gas in a block with `basefee`, the max fee capped to the base
+    /// fee plus the priority fee for EIP-1559 transactions.
+    pub fn effective_gas_price(&self, basefee: U256) -> U256 {
+        match self.max_priority_fee_per_gas() {
+            Some(priority_fee) => self
+                .max_fee_per_gas()
+                .min(basefee.saturating_add(priority_fee)),
+            None => self.max_fee_per_gas(),
+        }
+    }
+
+    /// The transaction as the executor sees it, sent by `caller`.
+    pub fn tx_env(&self, caller: Address) -> TxEnv {
+        TxEnv {
+            caller,
+            gas_limit: self.gas_limit(),
+            gas_price: self.max_fee_per_gas(),
+            gas_priority_fee: self.max_priority_fee_per_gas(),
+            transact_to: self.to(),
+            value: self.value(),
+            data: self.input().clone(),
+            nonce: Some(self.nonce()),
+            chain_id: self.chain_id(),
+            access_list: self.access_list().to_vec(),
+            blob_hashes: self.blob_versioned_hashes().to_vec(),
+            max_fee_per_blob_gas: self.max_fee_per_blob_gas(),
+        }
+    }
+}
+
+impl Transaction {
+    /// Encodes the unsigned fields followed by `extra` as a list.
+    fn encode_fields(&self, extra: &[&dyn Encodable], out: &mut Vec<u8>) {
+        let mut fields: Vec<&dyn Encodable> = match self {
+            Self::Legacy(tx) => vec![
+                &tx.nonce,
+                &tx.gas_price,
+                &tx.gas_limit,
+                &tx.to,
+                &tx.value,
+                &tx.input,
+            ],
+            Self::AccessList(tx) => vec![
+                &tx.chain_id,
+                &tx.nonce,
+                &tx.gas_price,
+                &tx.gas_limit,
+                &tx.to,
+                &tx.value,
+                &tx.input,
+                &tx.access_list,
+            ],
+            Self::DynamicFee(tx) => vec![
+                &tx.chain_id,
+                &tx.nonce,
+                &tx.max_priority_fee_per_gas,
+                &tx.max_fee_per_gas,
+                &tx.gas_limit,
+                &tx.to,
+                &tx.value,
+                &tx.input,
+                &tx.access_list,
+            ],
+            Self::Blob(tx) => vec![
+                &tx.chain_id,
+                &tx.nonce,
+                &tx.max_priority_fee_per_gas,
+                &tx.max_fee_per_gas,
+                &tx.gas_limit,
+                &tx.to,
+                &tx.value,
+                &tx.input,
+                &tx.access_list,
+                &tx.max_fee_per_blob_gas,
+                &tx.blob_versioned_hashes,
+            ],
+        };
+        fields.extend_from_slice(extra);
+        rlp::encode_list(&fields, out);
+    }
+
+    /// The hash the sender signed. EIP-155 legacy transactions sign `[.., chain_id, 0, 0]`,
+    /// typed ones the type byte followed by the fields.
+    pub fn signature_hash(&self) -> B256 {
+        let mut out = Vec::new();
+        match self {
+            Self::Legacy(TxLegacy {
+                chain_id: Some(chain_id),
+                ..
+            }) => self.encode_fields(&[chain_id, &0u8, &0u8], &mut out),
+            Self::Legacy(_) => self.encode_fields(&[], &mut out),
+            _ => {
+                out.push(self.tx_type());
+                self.encode_fields(&[], &mut out);
+            }
+        }
+        keccak256(out)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Signature {
+    pub r: U256,
+    pub s: U256,
+    /// Parity of the y coordinate of the curve point `r` is the x coordinate of.
+    pub odd_y_parity: bool,
+}
+
+/// A transaction together with its signature and hash.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignedTransaction {
+    pub transaction: Transaction,
+    pub signature: Signature,
+    /// keccak-256 of the raw transaction.
+    pub hash: B256,
+}
+
+impl SignedTransaction {
+    pub fn new(transaction: Transaction, signature: Signature) -> Self {
+        let mut signed = Self {
+            transaction,
+            signature,
+            hash: B256::default(),
+        };
+        signed.hash = keccak256(signed.encode());
+        signed
+    }
+
+    /// The raw transaction, a RLP list for legacy transactions and the type byte followed by a
+    /// RLP list for typed ones (EIP-2718).
+    pub fn encode(&self) -> Vec<u8> {
+        let Signature { r, s, odd_y_parity } = &self.signature;
+        let mut out = Vec::new();
+        match &self.transaction {
+            Transaction::Legacy(tx) => {
+                // A chain id above `(u64::MAX - 36) / 2` gives a `v` wider than a u64.
+                let v = match tx.chain_id {
+                    Some(chain_id) => U256::from(chain_id) * U256::from(2) + U256::from(35),
+                    None => U256::from(27),
+                } + U256::from(*odd_y_parity as u8);
+                self.transaction.encode_fields(&[&v, r, s], &mut out);
+            }
+            transaction => {
+                out.push(transaction.tx_type());
+                transaction.encode_fields(&[odd_y_parity, r, s], &mut out);
+            }
+        }
+        out
+    }
+
+    pub fn decode(raw: &[u8]) -> Result<Self> {
+        let first = *raw.first().ok_or(RlpError::InputTooShort { needed: 1 })?;
+        let mut buf = &raw[1..];
+        let (transaction, signature) = match first {
+            EMPTY_LIST.. => {
+                buf = raw;
+                decode_legacy(&mut buf)?
+            }
+            1 => rlp::decode_list(&mut buf, |buf| {
+                let tx = TxEip2930 {
+                    chain_id: u64::decode(buf)?,
+                    nonce: u64::decode(buf)?,
+                    gas_price: U256::decode(buf)?,
+                    gas_limit: u64::decode(buf)?,
+                    to: TransactTo::decode(buf)?,
+                    value: U256::decode(buf)?,
+                    input: Bytes::decode(buf)?,
+                    access_list: Vec::decode(buf)?,
+                };
+                Ok((Transaction::AccessList(tx), decode_signature(buf)?))
+            })?,
+            2 => rlp::decode_list(&mut buf, |buf| {
+                let tx = TxEip1559 {
+                    chain_id: u64::decode(buf)?,
+                    nonce: u64::decode(buf)?,
+                    max_priority_fee_per_gas: U256::decode(buf)?,
+                    max_fee_per_gas: U256::decode(buf)?,
+                    gas_limit: u64::decode(buf)?,
+                    to: TransactTo::decode(buf)?,
+                    value: U256::decode(buf)?,
+                    input: Bytes::decode(buf)?,
+                    access_list: Vec::decode(buf)?,
+                };
+                Ok((Transaction::DynamicFee(tx), decode_signature(buf)?))
+            })?,
+            3 => rlp::decode_list(&mut buf, |buf| {
+                let tx = TxEip4844 {
+                    chain_id: u64::decode(buf)?,
+                    nonce: u64::decode(buf)?,
+                    max_priority_fee_per_gas: U256::decode(buf)?,
+                    max_fee_per_gas: U256::decode(buf)?,
+                    gas_limit: u64::decode(buf)?,
+                    to: Address::decode(buf)?,
+                    value: U256::decode(buf)?,
+                    input: Bytes::decode(buf)?,
+                    access_list: Vec::decode(buf)?,
+                    max_fee_per_blob_gas: U256::decode(buf)?,
+                    blob_versioned_hashes: Vec::decode(buf)?,
+                };
+                Ok((Transaction::Blob(tx), decode_signature(buf)?))
+            })?,
+            0..0x80 => return Err(TransactionError::UnsupportedType(first)),
+            _ => return Err(RlpError::UnexpectedString.into()),
+        };
+        if !buf.is_empty() {
+            return Err(RlpError::TrailingBytes(buf.len()).into());
+        }
+        Ok(Self {
+            transaction,
+            signature,
+            hash: keccak256(raw),
+        })
+    }
+
+    /// Recovers the address that signed the transaction. From Homestead on signatures with a
+    /// high `s` are rejected (EIP-2).
+    pub fn recover_sender(&self, spec: SpecId) -> Result<Address> {
+        let Signature { r, s, odd_y_parity } = self.signature;
+        if r.is_zero() || s.is_zero() || r >= SECP256K1N || s >= SECP256K1N {
+            return Err(TransactionError::InvalidSignature);
+        }
+        if spec.is_enabled_in(SpecId::Homestead) && s > SECP256K1N_HALF {
+            return Err(TransactionError::InvalidSignature);
+        }
+        let mut sig = [0u8; 64];
+        sig[..32].copy_from_slice(&r.to_be_bytes::<32>());
+        sig[32..].copy_from_slice(&s.to_be_bytes::<32>());
+        ecrecover(&sig, odd_y_parity as u8, &self.transaction.signature_hash())
+            .ok_or(TransactionError::InvalidSignature)
+    }
+}
+
+/// A legacy transaction, the chain id and y parity are both packed into `v` (EIP-155).
+fn decode_legacy(buf: &mut &[u8]) -> Result<(Transaction, Signature)> {
+    let (mut tx, v, r, s) = rlp::decode_list(buf, |buf| {
+        let tx = TxLegacy {
+            chain_id: None,
+            nonce: u64::decode(buf)?,
+            gas_price: U256::decode(buf)?,
+            gas_limit: u64::decode(buf)?,
+            to: TransactTo::decode(buf)?,
+            value: U256::decode(buf)?,
+            input: Bytes::decode(buf)?,
+        };
+        Ok((
+            tx,
+            U256::decode(buf)?,
+            U256::decode(buf)?,
+            U256::decode(buf)?,
+        ))
+    })?;
+    let odd_y_parity = match u64::try_from(v) {
+        Ok(27 | 28) => v == U256::from(28),
+        _ if v >= U256::from(35) => {
+            let chain_id = (v - U256::from(35)) / U256::from(2);
+            tx.chain_id = Some(
+                chain_id
+                    .try_into()
+                    .map_err(|_| TransactionError::InvalidV(v))?,
+            );
+            !v.bit(0)
+        }
+        _ => return Err(TransactionError::InvalidV(v)),
+    };
+    Ok((Transaction::Legacy(tx), Signature { r, s, odd_y_parity }))
+}
+
+fn decode_signature(buf: &mut &[u8]) -> rlp::Result<Signature> {
+    Ok(Signature {
+        odd_y_parity: bool::decode(buf)?,
+        r: U256::decode(buf)?,
+        s: U256::decode(buf)?,
+    })
+}
+
+/// The recipient, a empty string for contract creations.
+impl Encodable for TransactTo {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Call(address) => address.encode(out),
+            Self::Create => rlp::encode_bytes(&[], out),
+        }
+    }
+}
+
+impl Decodable for TransactTo {
+    fn decode(buf: &mut &[u8]) -> rlp::Result<Self> {
+        match Header::decode_bytes(buf)? {
+            [] => Ok(Self::Create),
+            bytes if bytes.len() == 20 => Ok(Self::Call(Address::from_slice(bytes))),
+            bytes => Err(RlpError::UnexpectedLength {
+                expected: 20,
+                got: bytes.len(),
+            }),
+        }
+    }
+}
+
+/// As in a block body: legacy transactions as their list, typed ones wrapped in a string.
+impl Encodable for SignedTransaction {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self.transaction {
+            Transaction::Legacy(_) => out.extend_from_slice(&self.encode()),
+            _ => rlp::encode_bytes(&self.encode(), out),
+        }
+    }
+}
+
+impl Decodable for SignedTransaction {
+    fn decode(buf: &mut &[u8]) -> rlp::Result<Self> {
+        let start = *buf;
+        let header = Header::decode(buf)?;
+        let raw = match header.list {
+            true => {
+                // The header checked the payload is there.
+                let len = start.len() - buf.len() + header.payload_length;
+                *buf = &start[len..];
+                &start[..len]
+            }
+            false => {
+                *buf = start;
+                Header::decode_bytes(buf)?
+            }
+        };
+        Self::decode(raw).map_err(|error| match error {
+            TransactionError::Rlp(error) => error,
+            TransactionError::UnsupportedType(_) => {
+                RlpError::Custom("unsupported transaction type")
+            }
+            TransactionError::InvalidV(_) => RlpError::Custom("invalid v"),
+            TransactionError::InvalidSignature => RlpError::Custom("invalid signature"),
+        })
+    }
+}
+
+/// `[address, [storage_key, ..]]`, the keys as 32 byte strings rather than integers.
+impl Encodable for AccessListItem {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let storage_keys: Vec<B256> = self
+            .storage_keys
+            .iter()
+            .map(|key| key.to_be_bytes())
+            .collect();
+        rlp::encode_list(&[&self.address, &storage_keys], out);
+    }
+}
+
+impl Decodable for AccessListItem {
+    fn decode(buf: &mut &[u8]) -> rlp::Result<Self> {
+        rlp::decode_list(buf, |buf| {
+            let address = Address::decode(buf)?;
+            let storage_keys = Vec::<B256>::decode(buf)?
+                .into_iter()
+                .map(U256::from_be_bytes)
+                .collect();
+            Ok(Self {
+                address,
+                storage_keys,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::SigningKey;
+
+    use super::*;
+
+    /// The example of EIP-155, signed with the key `0x4646..46`.
+    const EIP155_TX: &str = "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+    const EIP155_SENDER: &str = "9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f";
+
+    fn sign(transaction: Transaction) -> SignedTransaction {
+        let key = SigningKey::from_slice(&[0x46; 32]).unwrap();
+        let (signature, recid) = key
+            .sign_prehash_recoverable(&transaction.signature_hash())
+            .unwrap();
+        let signature = Signature {
+            r: U256::from_be_slice(&signature.r().to_bytes()),
+            s: U256::from_be_slice(&signature.s().to_bytes()),
+            odd_y_parity: recid.is_y_odd(),
+        };
+        SignedTransaction::new(transaction, signature)
+    }
+
+    #[test]
+    fn effective_gas_price() {
+        let legacy = Transaction::Legacy(TxLegacy {
+            gas_price: U256::from(20),
+            ..Default::default()
+        });
+        assert_eq!(legacy.effective_gas_price(U256::from(7)), U256::from(20));
+
+        let dynamic_fee = Transaction::DynamicFee(TxEip1559 {
+            max_fee_per_gas: U256::from(20),
+            max_priority_fee_per_gas: U256::from(3),
+            ..Default::default()
+        });
+        assert_eq!(
+            dynamic_fee.effective_gas_price(U256::from(7)),
+            U256::from(10)
+        );
+        assert_eq!(
+            dynamic_fee.effective_gas_price(U256::from(18)),
+            U256::from(20)
+        );
+    }
+
+    #[test]
+    fn tx_env() {
+        let to = Address::with_last_byte(0xaa);
+        let tx = Transaction::Blob(TxEip4844 {
+            chain_id: 1,
+            nonce: 4,
+            max_fee_per_gas: U256::from(20),
+            max_priority_fee_per_gas: U256::from(3),
+            gas_limit: 50_000,
+            to,
+            max_fee_per_blob_gas: U256::from(2),
+            blob_versioned_hashes: vec![[1; 32], [2; 32]],
+            ..Default::default()
+        });
+        assert_eq!(tx.tx_type(), 3);
+        assert_eq!(tx.blob_gas(), 2 * GAS_PER_BLOB);
+
+        let caller = Address::with_last_byte(0xca);
+        let env = tx.tx_env(caller);
+        assert_eq!(env.caller, caller);
+        assert_eq!(env.transact_to, TransactTo::Call(to));
+        assert_eq!(env.nonce, Some(4));
+        assert_eq!(env.chain_id, Some(1));
+        assert_eq!(env.gas_priority_fee, Some(U256::from(3)));
+        assert_eq!(env.blob_gas(), tx.blob_gas());
+        assert_eq!(
+            env.effective_gas_price(U256::from(7)),
+            tx.effective_gas_price(U256::from(7))
+        );
+    }
+
+    #[test]
+    fn decode_eip155() {
+        let raw = hex::decode(EIP155_TX).unwrap();
+        let signed = SignedTransaction::decode(&raw).unwrap();
+        let Transaction::Legacy(tx) = &signed.transaction else {
+            panic!("expected a legacy transaction");
+        };
+        assert_eq!(tx.chain_id, Some(1));
+        assert_eq!(tx.nonce, 9);
+        assert_eq!(tx.gas_price, U256::from(20_000_000_000u64));
+        assert_eq!(tx.gas_limit, 21000);
+        assert_eq!(tx.to, TransactTo::Call(Address([0x35; 20])));
+        assert_eq!(tx.value, U256::from(1_000_000_000_000_000_000u64));
+        assert_eq!(
+            hex::encode(signed.transaction.signature_hash()),
+            "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+        );
+        assert_eq!(signed.hash, keccak256(&raw));
+        assert_eq!(signed.encode(), raw);
+        assert_eq!(
+            signed.recover_sender(SpecId::Cancun),
+            Ok(EIP155_SENDER.parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn typed_roundtrip() {
+        let access_list = vec![AccessListItem {
+            address: Address::with_last_byte(0xaa),
+            storage_keys: vec![U256::ZERO, U256::from(1)],
+        }];
+        let transactions = [
+            Transaction::Legacy(TxLegacy {
+                nonce: 1,
+                gas_price: U256::from(10),
+                gas_limit: 53000,
+                input: Bytes::from_static(&[0x60, 0x00]),
+                ..Default::default()
+            }),
+            // Its `v` does not fit a u64.
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(u64::MAX),
+                gas_limit: 21000,
+                ..Default::default()
+            }),
+            Transaction::AccessList(TxEip2930 {
+                chain_id: 1,
+                gas_limit: 30000,
+                to: TransactTo::Call(Address::with_last_byte(0xbb)),
+                access_list: access_list.clone(),
+                ..Default::default()
+            }),
+            Transaction::DynamicFee(TxEip1559 {
+                chain_id: 1,
+                max_fee_per_gas: U256::from(100),
+                max_priority_fee_per_gas: U256::from(2),
+                gas_limit: 21000,
+                to: TransactTo::Call(Address::with_last_byte(0xbb)),
+                value: U256::from(5),
+                access_list,
+                ..Default::default()
+            }),
+            Transaction::Blob(TxEip4844 {
+                chain_id: 1,
+                gas_limit: 21000,
+                max_fee_per_blob_gas: U256::from(3),
+                blob_versioned_hashes: vec![[1; 32]],
+                ..Default::default()
+            }),
+        ];
+
+        let sender = EIP155_SENDER.parse().unwrap();
+        for transaction in transactions {
+            let signed = sign(transaction);
+            let raw = signed.encode();
+            if signed.transaction.tx_type() != 0 {
+                assert_eq!(raw[0], signed.transaction.tx_type());
+            }
+            assert_eq!(SignedTransaction::decode(&raw), Ok(signed.clone()));
+            assert_eq!(signed.recover_sender(SpecId::Cancun), Ok(sender));
+
+            // In a block body typed transactions are wrapped in a string.
+            let body = rlp::encode(&signed);
+            assert_eq!(body[0] >= EMPTY_LIST, signed.transaction.tx_type() == 0);
+            assert_eq!(rlp::decode_exact(&body), Ok(signed));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        let raw = hex::decode(EIP155_TX).unwrap();
+        let mut signed = SignedTransaction::decode(&raw).unwrap();
+        // The other valid `s` for the same signature, ruled out by EIP-2.
+        signed.signature.s = SECP256K1N - signed.signature.s;
+        signed.signature.odd_y_parity ^= true;
+        assert_eq!(
+            signed.recover_sender(SpecId::Homestead),
+            Err(TransactionError::InvalidSignature)
+        );
+        assert_eq!(
+            signed.recover_sender(SpecId::Frontier),
+            Ok(EIP155_SENDER.parse().unwrap())
+        );
+        signed.signature.s = SECP256K1N;
+        assert_eq!(
+            signed.recover_sender(SpecId::Frontier),
+            Err(TransactionError::InvalidSignature)
+        );
+        assert_eq!(SECP256K1N >> 1, SECP256K1N_HALF);
+
+        assert_eq!(
+            SignedTransaction::decode(&[0x05, 0xc0]),
+            Err(TransactionError::UnsupportedType(5))
+        );
+        let mut trailing = raw.clone();
+        trailing.push(0);
+        assert_eq!(
+            SignedTransaction::decode(&trailing),
+            Err(TransactionError::Rlp(RlpError::TrailingBytes(1)))
+        );
+
+        // v = 29 is neither pre nor post EIP-155.
+        let mut bad_v = raw;
+        let v = bad_v.len() - 67;
+        bad_v[v] = 29;
+        assert_eq!(
+            SignedTransaction::decode(&bad_v),
+            Err(TransactionError::InvalidV(U256::from(29)))
+        );
+    }
+}
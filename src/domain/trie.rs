@@ -0,0 +1,557 @@
+//! Merkle Patricia Trie, built from scratch out of a set of key/value pairs whenever a root is
+//! needed. The world state is kept as plain maps, so nothing is persisted between roots.
+
+use hashbrown::HashMap;
+use ruint::aliases::U256;
+
+use std::fmt;
+
+use bytes::Bytes;
+
+use super::{
+    primitives::{keccak256, Address, B256},
+    rlp::{self, Encodable, Header, RlpError},
+    state::{Account, State},
+    transaction::SignedTransaction,
+};
+
+pub type Result<T> = std::result::Result<T, ProofError>;
+
+/// Root of the trie without any entries, `keccak256(rlp(""))`.
+pub const EMPTY_ROOT: B256 = [
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Node {
+    Leaf {
+        /// The rest of the key, one nibble per byte.
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Extension {
+        /// Nibbles shared by every key below.
+        prefix: Vec<u8>,
+        child: Box<Node>,
+    },
+    Branch {
+        children: [Option<Box<Node>>; 16],
+        /// Value of the key that ends at this node.
+        value: Option<Vec<u8>>,
+    },
+}
+
+impl Node {
+    /// Builds the node for `entries`, sorted by key and without duplicates, below `depth`
+    /// nibbles of their keys.
+    fn build(entries: &[(Vec<u8>, Vec<u8>)], depth: usize) -> Self {
+        if let [(key, value)] = entries {
+            return Self::Leaf {
+                key: key[depth..].to_vec(),
+                value: value.clone(),
+            };
+        }
+
+        // The keys are sorted, the prefix shared by the first and last is shared by all.
+        let first = &entries[0].0[depth..];
+        let last = &entries[entries.len() - 1].0[depth..];
+        let prefix_len = first.iter().zip(last).take_while(|(a, b)| a == b).count();
+        if prefix_len > 0 {
+            return Self::Extension {
+                prefix: first[..prefix_len].to_vec(),
+                child: Box::new(Self::build(entries, depth + prefix_len)),
+            };
+        }
+
+        // Only the first key can end here, it sorts before the keys it is a prefix of.
+        let (value, mut rest) = match entries.split_first() {
+            Some(((key, value), rest)) if key.len() == depth => (Some(value.clone()), rest),
+            _ => (None, entries),
+        };
+        let mut children: [Option<Box<Node>>; 16] = Default::default();
+        while let Some((key, _)) = rest.first() {
+            let nibble = key[depth];
+            let len = rest
+                .iter()
+                .take_while(|(key, _)| key[depth] == nibble)
+                .count();
+            children[nibble as usize] = Some(Box::new(Self::build(&rest[..len], depth + 1)));
+            rest = &rest[len..];
+        }
+        Self::Branch { children, value }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        match self {
+            Self::Leaf { key, value } => {
+                rlp::encode_bytes(&hex_prefix(key, true), &mut payload);
+                rlp::encode_bytes(value, &mut payload);
+            }
+            Self::Extension { prefix, child } => {
+                rlp::encode_bytes(&hex_prefix(prefix, false), &mut payload);
+                child.encode_reference(&mut payload);
+            }
+            Self::Branch { children, value } => {
+                for child in children {
+                    match child {
+                        Some(child) => child.encode_reference(&mut payload),
+                        None => rlp::encode_bytes(&[], &mut payload),
+                    }
+                }
+                rlp::encode_bytes(value.as_deref().unwrap_or_default(), &mut payload);
+            }
+        }
+        let mut out = Vec::with_capacity(payload.len() + 3);
+        Header {
+            list: true,
+            payload_length: payload.len(),
+        }
+        .encode(&mut out);
+        out.extend_from_slice(&payload);
+        out
+    }
+
+    /// How a parent refers to this node, nodes shorter than a hash are inlined.
+    fn encode_reference(&self, out: &mut Vec<u8>) {
+        let encoded = self.encode();
+        if encoded.len() < 32 {
+            out.extend_from_slice(&encoded);
+        } else {
+            keccak256(&encoded).encode(out);
+        }
+    }
+}
+
+/// Compact encoding of a nibble path, the first nibble flags a leaf and a odd length.
+pub fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let mut out = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        out.push(((flag + 1) << 4) | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        out.push(flag << 4);
+        nibbles
+    };
+    out.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    out
+}
+
+/// Splits every byte of `key` into two nibbles.
+pub fn to_nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect()
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Trie {
+    root: Option<Node>,
+}
+
+impl Trie {
+    /// Builds the trie of `entries`, a later value for the same key replaces the earlier one.
+    pub fn new(entries: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>) -> Self {
+        let mut entries: Vec<_> = entries
+            .into_iter()
+            .map(|(key, value)| (to_nibbles(&key), value))
+            .collect();
+        // Stable, so the last of equal keys is kept by the reversed dedup below.
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.reverse();
+        entries.dedup_by(|a, b| a.0 == b.0);
+        entries.reverse();
+
+        Self {
+            root: (!entries.is_empty()).then(|| Node::build(&entries, 0)),
+        }
+    }
+
+    pub fn root_node(&self) -> Option<&Node> {
+        self.root.as_ref()
+    }
+
+    /// The root hash, unlike any other node the root is hashed even when it is short.
+    pub fn root(&self) -> B256 {
+        match &self.root {
+            Some(node) => keccak256(node.encode()),
+            None => EMPTY_ROOT,
+        }
+    }
+
+    /// The nodes on the path to `key`, starting at the root. Nodes inlined into their parent
+    /// are not repeated. The path ends where the key would be if it is not in the trie, which
+    /// makes this a proof of exclusion.
+    pub fn proof(&self, key: &[u8]) -> Vec<Bytes> {
+        let Some(mut node) = self.root.as_ref() else {
+            return Vec::new();
+        };
+        let nibbles = to_nibbles(key);
+        let mut path = nibbles.as_slice();
+        let mut proof = vec![Bytes::from(node.encode())];
+        loop {
+            let child = match node {
+                Node::Leaf { .. } => break,
+                Node::Extension { prefix, child } if path.starts_with(prefix) => {
+                    path = &path[prefix.len()..];
+                    child
+                }
+                Node::Extension { .. } => break,
+                Node::Branch { children, .. } => match path.split_first() {
+                    Some((nibble, rest)) => match &children[*nibble as usize] {
+                        Some(child) => {
+                            path = rest;
+                            child
+                        }
+                        None => break,
+                    },
+                    None => break,
+                },
+            };
+            let encoded = child.encode();
+            if encoded.len() >= 32 {
+                proof.push(encoded.into());
+            }
+            node = child;
+        }
+        proof
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProofError {
+    Rlp(RlpError),
+    /// A node that does not hash to the reference its parent holds.
+    NodeHashMismatch {
+        expected: B256,
+        got: B256,
+    },
+    /// The proof ends before the path to the key does.
+    MissingNode,
+    /// A list that is neither a branch, extension nor leaf.
+    InvalidNode,
+    /// Nodes left over once the path to the key ended.
+    UnusedNodes(usize),
+    /// The proven value is not the expected one.
+    ValueMismatch,
+}
+
+impl From<RlpError> for ProofError {
+    fn from(error: RlpError) -> Self {
+        Self::Rlp(error)
+    }
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rlp(error) => write!(f, "invalid rlp: {error}"),
+            Self::NodeHashMismatch { expected, got } => write!(
+                f,
+                "node hashes to 0x{} instead of 0x{}",
+                hex::encode(got),
+                hex::encode(expected)
+            ),
+            Self::MissingNode => write!(f, "proof ends before the path"),
+            Self::InvalidNode => write!(f, "invalid trie node"),
+            Self::UnusedNodes(count) => write!(f, "{count} unused nodes in proof"),
+            Self::ValueMismatch => write!(f, "proven value does not match"),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+/// Walks `proof` from `root` along the path to `key`. Returns the value of the key, or `None`
+/// if the proof shows it is not in the trie.
+pub fn verify_proof(root: B256, key: &[u8], proof: &[Bytes]) -> Result<Option<Vec<u8>>> {
+    if proof.is_empty() && root == EMPTY_ROOT {
+        return Ok(None);
+    }
+    let nibbles = to_nibbles(key);
+    let mut path = nibbles.as_slice();
+    let mut proof = proof.iter();
+    // A child is referenced either by hash or, when shorter than one, inline.
+    let mut hash = Some(root);
+    let mut inline: &[u8] = &[];
+
+    let value = loop {
+        let node = match hash {
+            Some(expected) => {
+                let node = proof.next().ok_or(ProofError::MissingNode)?;
+                let got = keccak256(node);
+                if got != expected {
+                    return Err(ProofError::NodeHashMismatch { expected, got });
+                }
+                node.as_ref()
+            }
+            None => inline,
+        };
+
+        let items = rlp::split_list(node)?;
+        let child = match items.as_slice() {
+            [children @ .., value] if children.len() == 16 => match path.split_first() {
+                Some((nibble, rest)) => {
+                    path = rest;
+                    children[*nibble as usize]
+                }
+                None => break Some(*value),
+            },
+            [encoded_path, rest] => {
+                let encoded_path = rlp::Header::decode_bytes(&mut &encoded_path[..])?;
+                let (key_part, is_leaf) = decode_hex_prefix(encoded_path)?;
+                if is_leaf {
+                    break (path == key_part.as_slice()).then_some(*rest);
+                }
+                if !path.starts_with(&key_part) {
+                    break None;
+                }
+                path = &path[key_part.len()..];
+                *rest
+            }
+            _ => return Err(ProofError::InvalidNode),
+        };
+
+        match child {
+            [rlp::EMPTY_STRING] => break None,
+            [first, ..] if *first >= rlp::EMPTY_LIST => {
+                hash = None;
+                inline = child;
+            }
+            _ => {
+                hash = Some(<B256 as rlp::Decodable>::decode(&mut &child[..])?);
+            }
+        }
+    };
+
+    let unused = proof.count();
+    if unused > 0 {
+        return Err(ProofError::UnusedNodes(unused));
+    }
+    match value {
+        Some(value) => {
+            let value = rlp::Header::decode_bytes(&mut &value[..])?;
+            Ok((!value.is_empty()).then(|| value.to_vec()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Inverse of `hex_prefix`, returns the nibbles and whether they end in a leaf.
+fn decode_hex_prefix(encoded: &[u8]) -> Result<(Vec<u8>, bool)> {
+    let (first, rest) = encoded.split_first().ok_or(ProofError::InvalidNode)?;
+    let flag = first >> 4;
+    if flag > 3 || (flag & 1 == 0 && first & 0x0f != 0) {
+        return Err(ProofError::InvalidNode);
+    }
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(to_nibbles(rest));
+    Ok((nibbles, flag & 2 == 2))
+}
+
+/// Root of a trie keyed by the RLP encoded index of each item, e.g. the transactions and
+/// receipts of a block.
+pub fn ordered_trie_root(items: impl IntoIterator<Item = Vec<u8>>) -> B256 {
+    Trie::new(
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| (rlp::encode(&i), item)),
+    )
+    .root()
+}
+
+pub fn transactions_root(transactions: &[SignedTransaction]) -> B256 {
+    ordered_trie_root(transactions.iter().map(SignedTransaction::encode))
+}
+
+/// Root of the storage of a account, keyed by the hash of the slot. Zero slots are absent.
+pub fn storage_root(storage: &HashMap<U256, U256>) -> B256 {
+    Trie::new(
+        storage
+            .iter()
+            .filter(|(_, value)| !value.is_zero())
+            .map(|(slot, value)| (storage_key(*slot).to_vec(), rlp::encode(value))),
+    )
+    .root()
+}
+
+/// `rlp([nonce, balance, storage_root, code_hash])`, the value of a account in the state trie.
+pub fn encode_account(account: &Account) -> Vec<u8> {
+    let mut out = Vec::new();
+    rlp::encode_list(
+        &[
+            &account.nonce,
+            &account.balance,
+            &storage_root(&account.storage),
+            &account.code_hash,
+        ],
+        &mut out,
+    );
+    out
+}
+
+/// Root of the world state, keyed by the hash of each address.
+pub fn state_root(state: &State) -> B256 {
+    Trie::new(
+        state
+            .iter()
+            .map(|(address, account)| (account_key(address).to_vec(), encode_account(account))),
+    )
+    .root()
+}
+
+/// Hashed key of `address` in the state trie.
+pub fn account_key(address: &Address) -> B256 {
+    keccak256(address.as_slice())
+}
+
+/// Hashed key of `slot` in a storage trie.
+pub fn storage_key(slot: U256) -> B256 {
+    keccak256(slot.to_be_bytes::<32>())
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn root(entries: &[(&str, &str)]) -> String {
+        let trie = Trie::new(
+            entries
+                .iter()
+                .map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec())),
+        );
+        hex::encode(trie.root())
+    }
+
+    #[test]
+    fn hex_prefix_encoding() {
+        assert_eq!(hex_prefix(&[1, 2, 3, 4, 5], false), [0x11, 0x23, 0x45]);
+        assert_eq!(
+            hex_prefix(&[0, 1, 2, 3, 4, 5], false),
+            [0x00, 0x01, 0x23, 0x45]
+        );
+        assert_eq!(
+            hex_prefix(&[0, 0xf, 1, 0xc, 0xb, 8], true),
+            [0x20, 0x0f, 0x1c, 0xb8]
+        );
+        assert_eq!(hex_prefix(&[0xf, 1, 0xc, 0xb, 8], true), [0x3f, 0x1c, 0xb8]);
+    }
+
+    #[test]
+    fn known_roots() {
+        assert_eq!(Trie::default().root(), EMPTY_ROOT);
+        assert_eq!(EMPTY_ROOT, keccak256([rlp::EMPTY_STRING]));
+        assert_eq!(
+            root(&[
+                ("doe", "reindeer"),
+                ("dog", "puppy"),
+                ("dogglesworth", "cat")
+            ]),
+            "8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3"
+        );
+        assert_eq!(
+            root(&[
+                ("do", "verb"),
+                ("horse", "stallion"),
+                ("doge", "coin"),
+                ("dog", "puppy"),
+            ]),
+            "5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84"
+        );
+    }
+
+    #[test]
+    fn later_values_replace_earlier() {
+        assert_eq!(
+            root(&[("dog", "cat"), ("doe", "reindeer"), ("dog", "puppy")]),
+            root(&[("doe", "reindeer"), ("dog", "puppy")])
+        );
+    }
+
+    #[test]
+    fn inline_nodes() {
+        // Both leaves encode to less than 32 bytes and are embedded in the branch.
+        let trie = Trie::new([(vec![0x10], vec![1]), (vec![0x20], vec![2])]);
+        let Some(Node::Branch { children, .. }) = trie.root_node() else {
+            panic!("expected a branch");
+        };
+        assert!(children[1].is_some() && children[2].is_some());
+        let encoded = trie.root_node().unwrap().encode();
+        // [0x80, [0x30, 0x01], [0x30, 0x02], 0x80 * 14]
+        assert_eq!(
+            encoded[..8],
+            [0xd5, 0x80, 0xc2, 0x30, 0x01, 0xc2, 0x30, 0x02]
+        );
+    }
+
+    #[test]
+    fn state_roots() {
+        let mut state = State::new();
+        assert_eq!(state_root(&state), EMPTY_ROOT);
+
+        // Zero slots do not count towards the storage root.
+        let mut account = Account::with_code(Bytes::from_static(&[0x00]));
+        account.storage.insert(U256::from(1), U256::ZERO);
+        assert_eq!(storage_root(&account.storage), EMPTY_ROOT);
+        state.insert(Address::with_last_byte(1), account.clone());
+        let root = state_root(&state);
+
+        account.storage.insert(U256::from(1), U256::from(1));
+        state.insert(Address::with_last_byte(1), account);
+        assert_ne!(state_root(&state), root);
+        assert_eq!(ordered_trie_root([]), EMPTY_ROOT);
+    }
+
+    #[test]
+    fn proofs() {
+        let entries: Vec<_> = (0u8..40)
+            .map(|i| (vec![i, i.wrapping_mul(7)], vec![i; 40]))
+            .collect();
+        let trie = Trie::new(entries.clone());
+        for (key, value) in &entries {
+            let proof = trie.proof(key);
+            assert_eq!(
+                verify_proof(trie.root(), key, &proof),
+                Ok(Some(value.clone()))
+            );
+        }
+        let missing = [0xff, 0xff];
+        let proof = trie.proof(&missing);
+        assert_eq!(verify_proof(trie.root(), &missing, &proof), Ok(None));
+        assert_eq!(verify_proof(EMPTY_ROOT, &missing, &[]), Ok(None));
+
+        let mut tampered = trie.proof(&entries[3].0);
+        let mut node = tampered[1].to_vec();
+        *node.last_mut().unwrap() ^= 1;
+        tampered[1] = node.into();
+        assert!(matches!(
+            verify_proof(trie.root(), &entries[3].0, &tampered),
+            Err(ProofError::NodeHashMismatch { .. })
+        ));
+
+        let mut extra = trie.proof(&entries[3].0);
+        extra.push(Bytes::from_static(&[0xc0]));
+        assert_eq!(
+            verify_proof(trie.root(), &entries[3].0, &extra),
+            Err(ProofError::UnusedNodes(1))
+        );
+
+        // Inlined children travel inside their parent.
+        let small = Trie::new([(vec![0x10], vec![1]), (vec![0x20], vec![2])]);
+        let proof = small.proof(&[0x10]);
+        assert_eq!(proof.len(), 1);
+        assert_eq!(
+            verify_proof(small.root(), &[0x10], &proof),
+            Ok(Some(vec![1]))
+        );
+        assert_eq!(verify_proof(small.root(), &[0x30], &proof), Ok(None));
+    }
+}
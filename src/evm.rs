@@ -0,0 +1,629 @@
+use bytes::Bytes;
+use hashbrown::HashMap;
+use ruint::aliases::U256;
+
+use crate::{
+    domain::{
+        constants::{CALL_DEPTH_LIMIT, MAX_CODE_SIZE},
+        env::Env,
+        journal::{JournalCheckpoint, JournaledState},
+        primitives::{keccak256, Address, B256, KECCAK_EMPTY},
+        spec::SpecId,
+        state::{Log, State},
+    },
+    inspector::{Inspector, NoopInspector},
+    interpreter::{
+        gas::{self, Gas},
+        host::{
+            CallInputs, CallOutcome, CreateInputs, CreateOutcome, CreateScheme, Host, SStoreResult,
+            SelfDestructResult, StateLoad,
+        },
+        InstructionResult, Interpreter,
+    },
+    precompiles::{PrecompileError, Precompiles},
+};
+
+/// Runs call and create frames against the journaled state, every interpreter of a transaction
+/// shares it as its host.
+#[derive(Debug)]
+pub struct Evm<I = NoopInspector> {
+    pub env: Env,
+    pub journaled_state: JournaledState,
+    pub precompiles: Precompiles,
+    /// Hashes of recent blocks for BLOCKHASH, missing ones read as zero.
+    pub block_hashes: HashMap<u64, B256>,
+    pub inspector: I,
+}
+
+impl Default for Evm {
+    fn default() -> Self {
+        Self {
+            env: Env::default(),
+            journaled_state: JournaledState::default(),
+            precompiles: Precompiles::default(),
+            block_hashes: HashMap::new(),
+            inspector: NoopInspector,
+        }
+    }
+}
+
+impl Evm {
+    pub fn new(env: Env, state: State) -> Self {
+        Self::with_inspector(env, state, NoopInspector)
+    }
+}
+
+impl<I: Inspector> Evm<I> {
+    pub fn with_inspector(env: Env, state: State, inspector: I) -> Self {
+        Self {
+            precompiles: Precompiles::new(env.cfg.spec),
+            env,
+            journaled_state: JournaledState::new(state),
+            block_hashes: HashMap::new(),
+            inspector,
+        }
+    }
+
+    /// Runs `interpreter` as a frame of its own, like the outermost one of a transaction. Its
+    /// changes are reverted if it fails.
+    pub fn run_frame(&mut self, interpreter: &mut Interpreter) -> InstructionResult {
+        let checkpoint = self.journaled_state.checkpoint();
+        let result = self.run_interpreter(interpreter);
+        self.finish_frame(checkpoint, result, &mut interpreter.gas);
+        result
+    }
+
+    /// Runs the interpreter to the end with this as its host, the inspector sees every step and
+    /// can halt the frame before it.
+    fn run_interpreter(&mut self, interpreter: &mut Interpreter) -> InstructionResult {
+        while interpreter.instruction_result == InstructionResult::Continue {
+            self.inspector.step(interpreter, &mut self.journaled_state);
+            if interpreter.instruction_result == InstructionResult::Continue {
+                interpreter.step(self);
+            }
+            self.inspector
+                .step_end(interpreter, &mut self.journaled_state);
+        }
+        interpreter.instruction_result
+    }
+
+    fn spec(&self) -> SpecId {
+        self.env.cfg.spec
+    }
+
+    /// Commits the frame if it succeeded, reverts it otherwise. Only a revert keeps the gas that
+    /// is left.
+    fn finish_frame(
+        &mut self,
+        checkpoint: JournalCheckpoint,
+        result: InstructionResult,
+        gas: &mut Gas,
+    ) {
+        if result.is_ok() {
+            self.journaled_state.commit();
+        } else {
+            self.journaled_state.revert(checkpoint);
+            if !result.is_revert() {
+                gas.spend_all();
+            }
+        }
+    }
+
+    fn call_precompile(
+        &mut self,
+        inputs: &CallInputs,
+        checkpoint: JournalCheckpoint,
+    ) -> CallOutcome {
+        let precompile = self.precompiles.get(&inputs.code_address).unwrap();
+        let mut gas = Gas::new(inputs.gas_limit);
+        let (result, output) = match precompile(&inputs.input, inputs.gas_limit) {
+            Ok(output) => {
+                gas.record_cost(output.gas_used);
+                (InstructionResult::Return, output.bytes)
+            }
+            Err(PrecompileError::OutOfGas) => (InstructionResult::OutOfGas, Bytes::new()),
+            Err(_) => (InstructionResult::PrecompileError, Bytes::new()),
+        };
+        self.finish_frame(checkpoint, result, &mut gas);
+        CallOutcome {
+            result,
+            gas,
+            output,
+        }
+    }
+}
+
+impl<I: Inspector> Host for Evm<I> {
+    fn env(&self) -> &Env {
+        &self.env
+    }
+
+    fn load_account(&mut self, address: Address) -> StateLoad<bool> {
+        let is_cold = self.journaled_state.access_account(address);
+        // Before EIP-161 only accounts that do not exist at all count as empty.
+        let data = if self.spec().is_enabled_in(SpecId::SpuriousDragon) {
+            self.journaled_state.is_empty(&address)
+        } else {
+            self.journaled_state.account(&address).is_none()
+        };
+        StateLoad { data, is_cold }
+    }
+
+    fn block_hash(&mut self, number: u64) -> B256 {
+        self.block_hashes.get(&number).copied().unwrap_or_default()
+    }
+
+    fn balance(&mut self, address: Address) -> StateLoad<U256> {
+        StateLoad {
+            is_cold: self.journaled_state.access_account(address),
+            data: self.journaled_state.balance(&address),
+        }
+    }
+
+    fn code(&mut self, address: Address) -> StateLoad<Bytes> {
+        StateLoad {
+            is_cold: self.journaled_state.access_account(address),
+            data: self.journaled_state.code(&address),
+        }
+    }
+
+    fn code_hash(&mut self, address: Address) -> StateLoad<B256> {
+        StateLoad {
+            is_cold: self.journaled_state.access_account(address),
+            data: match self.journaled_state.account(&address) {
+                Some(account) if !account.is_empty() => account.code_hash,
+                _ => B256::default(),
+            },
+        }
+    }
+
+    fn sload(&mut self, address: Address, key: U256) -> StateLoad<U256> {
+        StateLoad {
+            is_cold: self.journaled_state.access_storage(address, key),
+            data: self.journaled_state.sload(&address, key),
+        }
+    }
+
+    fn sstore(&mut self, address: Address, key: U256, value: U256) -> StateLoad<SStoreResult> {
+        let is_cold = self.journaled_state.access_storage(address, key);
+        let original = self.journaled_state.original_value(&address, key);
+        let present = self.journaled_state.sstore(address, key, value);
+        StateLoad {
+            data: SStoreResult {
+                original,
+                present,
+                new: value,
+            },
+            is_cold,
+        }
+    }
+
+    fn tload(&mut self, address: Address, key: U256) -> U256 {
+        self.journaled_state.tload(address, key)
+    }
+
+    fn tstore(&mut self, address: Address, key: U256, value: U256) {
+        self.journaled_state.tstore(address, key, value)
+    }
+
+    fn log(&mut self, log: Log) {
+        self.inspector.log(&log);
+        self.journaled_state.log(log)
+    }
+
+    fn selfdestruct(&mut self, address: Address, target: Address) -> StateLoad<SelfDestructResult> {
+        let StateLoad {
+            data: target_is_empty,
+            is_cold,
+        } = self.load_account(target);
+        let balance = self.journaled_state.balance(&address);
+        self.inspector.selfdestruct(address, target, balance);
+        if address != target {
+            // The balance is in the state already, moving it can not overflow.
+            let _ = self.journaled_state.transfer(address, target, balance);
+            self.journaled_state.touch(target);
+        }
+
+        // EIP-6780: from Cancun on only contracts created in the same transaction are removed.
+        let destroys =
+            !self.spec().is_enabled_in(SpecId::Cancun) || self.journaled_state.is_created(&address);
+        let previously_destroyed = if destroys {
+            if address == target {
+                // Sending the balance to itself burns it.
+                let _ = self.journaled_state.balance_decr(address, balance);
+            }
+            self.journaled_state.selfdestruct(address)
+        } else {
+            false
+        };
+
+        StateLoad {
+            data: SelfDestructResult {
+                had_value: !balance.is_zero(),
+                target_is_empty,
+                previously_destroyed,
+            },
+            is_cold,
+        }
+    }
+
+    fn call(&mut self, mut inputs: CallInputs) -> CallOutcome {
+        let mut outcome = match self.inspector.call(&mut inputs, &mut self.journaled_state) {
+            Some(outcome) => outcome,
+            None => self.call_frame(&inputs),
+        };
+        self.inspector
+            .call_end(&inputs, &mut outcome, &mut self.journaled_state);
+        outcome
+    }
+
+    fn create(&mut self, mut inputs: CreateInputs) -> CreateOutcome {
+        let mut outcome = match self
+            .inspector
+            .create(&mut inputs, &mut self.journaled_state)
+        {
+            Some(outcome) => outcome,
+            None => self.create_frame(&inputs),
+        };
+        self.inspector
+            .create_end(&inputs, &mut outcome, &mut self.journaled_state);
+        outcome
+    }
+}
+
+impl<I: Inspector> Evm<I> {
+    fn call_frame(&mut self, inputs: &CallInputs) -> CallOutcome {
+        let mut gas = Gas::new(inputs.gas_limit);
+        if self.journaled_state.depth > CALL_DEPTH_LIMIT {
+            return CallOutcome {
+                result: InstructionResult::CallTooDeep,
+                gas,
+                output: Bytes::new(),
+            };
+        }
+
+        let checkpoint = self.journaled_state.checkpoint();
+        self.journaled_state.touch(inputs.target_address);
+        if !self.spec().is_enabled_in(SpecId::SpuriousDragon) {
+            // Before EIP-161 every call brings its target into existence.
+            self.journaled_state.create_account(inputs.target_address);
+        }
+        if inputs.transfers_value
+            && self
+                .journaled_state
+                .transfer(inputs.caller, inputs.target_address, inputs.value)
+                .is_err()
+        {
+            self.journaled_state.revert(checkpoint);
+            return CallOutcome {
+                result: InstructionResult::OutOfFunds,
+                gas,
+                output: Bytes::new(),
+            };
+        }
+
+        if self.precompiles.contains(&inputs.code_address) {
+            return self.call_precompile(inputs, checkpoint);
+        }
+
+        let code = self.journaled_state.code(&inputs.code_address);
+        if code.is_empty() {
+            self.journaled_state.commit();
+            return CallOutcome {
+                result: InstructionResult::Stop,
+                gas,
+                output: Bytes::new(),
+            };
+        }
+
+        let mut interpreter = Interpreter::new(code);
+        interpreter.gas = gas;
+        interpreter.spec = self.spec();
+        interpreter.address = inputs.target_address;
+        interpreter.caller = inputs.caller;
+        interpreter.call_value = inputs.value;
+        interpreter.input = inputs.input.clone();
+        interpreter.is_static = inputs.is_static;
+        let result = self.run_interpreter(&mut interpreter);
+
+        gas = interpreter.gas;
+        self.finish_frame(checkpoint, result, &mut gas);
+        CallOutcome {
+            result,
+            gas,
+            output: if result.is_error() {
+                Bytes::new()
+            } else {
+                interpreter.output
+            },
+        }
+    }
+
+    fn create_frame(&mut self, inputs: &CreateInputs) -> CreateOutcome {
+        let mut gas = Gas::new(inputs.gas_limit);
+        let fail = |result, gas| CreateOutcome {
+            result,
+            address: None,
+            gas,
+            output: Bytes::new(),
+        };
+        if self.journaled_state.depth > CALL_DEPTH_LIMIT {
+            return fail(InstructionResult::CallTooDeep, gas);
+        }
+        if self.journaled_state.balance(&inputs.caller) < inputs.value {
+            return fail(InstructionResult::OutOfFunds, gas);
+        }
+        let nonce = self.journaled_state.nonce(&inputs.caller);
+        if nonce == u64::MAX {
+            return fail(InstructionResult::NonceOverflow, gas);
+        }
+        self.journaled_state.inc_nonce(inputs.caller);
+
+        let address = match inputs.scheme {
+            CreateScheme::Create => inputs.caller.create(nonce),
+            CreateScheme::Create2 { salt } => inputs
+                .caller
+                .create2(salt.to_be_bytes(), keccak256(&inputs.init_code)),
+        };
+        self.journaled_state.access_account(address);
+
+        let checkpoint = self.journaled_state.checkpoint();
+        // EIP-684 and EIP-7610: never deploy over a account with a nonce, code or storage.
+        if let Some(account) = self.journaled_state.account(&address) {
+            if account.nonce != 0
+                || account.code_hash != KECCAK_EMPTY
+                || !account.storage.is_empty()
+            {
+                self.journaled_state.revert(checkpoint);
+                gas.spend_all();
+                return fail(InstructionResult::CreateCollision, gas);
+            }
+        }
+
+        self.journaled_state.create_account(address);
+        self.journaled_state.mark_created(address);
+        self.journaled_state.touch(address);
+        if self.spec().is_enabled_in(SpecId::SpuriousDragon) {
+            // EIP-161: contracts start at nonce one.
+            self.journaled_state.inc_nonce(address);
+        }
+        // The balance was checked above.
+        let _ = self
+            .journaled_state
+            .transfer(inputs.caller, address, inputs.value);
+
+        let mut interpreter = Interpreter::new(inputs.init_code.clone());
+        interpreter.gas = gas;
+        interpreter.spec = self.spec();
+        interpreter.address = address;
+        interpreter.caller = inputs.caller;
+        interpreter.call_value = inputs.value;
+        let mut result = self.run_interpreter(&mut interpreter);
+        gas = interpreter.gas;
+
+        if result.is_ok() {
+            let code = interpreter.output.clone();
+            if self.spec().is_enabled_in(SpecId::London) && code.first() == Some(&0xef) {
+                // EIP-3541
+                result = InstructionResult::CreateContractStartingWithEF;
+            } else if self.spec().is_enabled_in(SpecId::SpuriousDragon)
+                && code.len() > MAX_CODE_SIZE
+            {
+                // EIP-170
+                result = InstructionResult::CreateContractSizeLimit;
+            } else if gas.record_cost(gas::CODEDEPOSIT * code.len() as u64) {
+                self.journaled_state.set_code(address, code);
+            } else if self.spec().is_enabled_in(SpecId::Homestead) {
+                // EIP-2: running out of gas for the deposit fails the creation.
+                result = InstructionResult::OutOfGas;
+            }
+            // Before Homestead the contract is left without code instead.
+        }
+
+        self.finish_frame(checkpoint, result, &mut gas);
+        if result.is_ok() {
+            CreateOutcome {
+                result: InstructionResult::Return,
+                address: Some(address),
+                gas,
+                output: Bytes::new(),
+            }
+        } else if result.is_revert() {
+            CreateOutcome {
+                result,
+                address: None,
+                gas,
+                output: interpreter.output,
+            }
+        } else {
+            fail(result, gas)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{domain::state::Account, interpreter::opcodes};
+
+    use super::*;
+
+    const CALLER: Address = Address([0xca; 20]);
+
+    fn build_evm(accounts: &[(Address, Account)]) -> Evm {
+        let mut state = State::new();
+        state.insert(CALLER, Account::with_balance(U256::from(1000)));
+        state.extend(accounts.iter().cloned());
+        Evm::new(Env::default(), state)
+    }
+
+    fn call_inputs(target: Address, value: u64) -> CallInputs {
+        CallInputs {
+            gas_limit: 100_000,
+            target_address: target,
+            code_address: target,
+            caller: CALLER,
+            value: U256::from(value),
+            transfers_value: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn call_returns_output() {
+        let target = Address::with_last_byte(0xaa);
+        let code = Bytes::from_static(&[
+            opcodes::CALLVALUE,
+            opcodes::PUSH0,
+            opcodes::MSTORE,
+            opcodes::PUSH1,
+            0x20,
+            opcodes::PUSH0,
+            opcodes::RETURN,
+        ]);
+        let mut evm = build_evm(&[(target, Account::with_code(code))]);
+
+        let outcome = evm.call(call_inputs(target, 7));
+        assert_eq!(outcome.result, InstructionResult::Return);
+        assert_eq!(U256::from_be_slice(&outcome.output), U256::from(7));
+        assert_eq!(outcome.gas.spent(), 2 + 2 + 3 + 3 + 3 + 2);
+        assert_eq!(evm.journaled_state.balance(&target), U256::from(7));
+        assert_eq!(evm.journaled_state.depth, 0);
+    }
+
+    #[test]
+    fn reverted_call_keeps_gas_and_undoes_transfer() {
+        let target = Address::with_last_byte(0xaa);
+        let code = Bytes::from_static(&[opcodes::PUSH0, opcodes::PUSH0, opcodes::REVERT]);
+        let mut evm = build_evm(&[(target, Account::with_code(code))]);
+
+        let outcome = evm.call(call_inputs(target, 7));
+        assert_eq!(outcome.result, InstructionResult::Revert);
+        assert_eq!(outcome.gas.spent(), 4);
+        assert_eq!(evm.journaled_state.balance(&target), U256::ZERO);
+
+        let outcome = evm.call(call_inputs(target, 1001));
+        assert_eq!(outcome.result, InstructionResult::OutOfFunds);
+        assert_eq!(outcome.gas.spent(), 0);
+    }
+
+    #[test]
+    fn failed_call_spends_all_gas() {
+        let target = Address::with_last_byte(0xaa);
+        let code = Bytes::from_static(&[opcodes::INVALID]);
+        let mut evm = build_evm(&[(target, Account::with_code(code))]);
+
+        let outcome = evm.call(call_inputs(target, 0));
+        assert_eq!(outcome.result, InstructionResult::InvalidFEOpcode);
+        assert_eq!(outcome.gas.remaining(), 0);
+        assert!(outcome.output.is_empty());
+    }
+
+    #[test]
+    fn call_precompile() {
+        let mut evm = build_evm(&[]);
+        let mut inputs = call_inputs(Address::with_last_byte(4), 0);
+        inputs.input = Bytes::from_static(&[1, 2, 3]);
+
+        let outcome = evm.call(inputs.clone());
+        assert_eq!(outcome.result, InstructionResult::Return);
+        assert_eq!(outcome.output, inputs.input);
+        assert_eq!(outcome.gas.spent(), 18);
+
+        inputs.gas_limit = 17;
+        let outcome = evm.call(inputs);
+        assert_eq!(outcome.result, InstructionResult::OutOfGas);
+        assert_eq!(outcome.gas.remaining(), 0);
+    }
+
+    #[test]
+    fn create_deploys_returned_code() {
+        let mut evm = build_evm(&[]);
+        // Returns the single byte 0x2a.
+        let init_code = Bytes::from_static(&[
+            opcodes::PUSH1,
+            0x2a,
+            opcodes::PUSH0,
+            opcodes::MSTORE8,
+            opcodes::PUSH1,
+            0x01,
+            opcodes::PUSH0,
+            opcodes::RETURN,
+        ]);
+        let outcome = evm.create(CreateInputs {
+            caller: CALLER,
+            value: U256::from(5),
+            init_code,
+            gas_limit: 100_000,
+            ..Default::default()
+        });
+
+        let address = CALLER.create(0);
+        assert_eq!(outcome.result, InstructionResult::Return);
+        assert_eq!(outcome.address, Some(address));
+        assert_eq!(outcome.gas.spent(), 3 + 2 + 3 + 3 + 3 + 2 + 200);
+        let account = evm.journaled_state.account(&address).unwrap();
+        assert_eq!(account.code, Bytes::from_static(&[0x2a]));
+        assert_eq!(account.nonce, 1);
+        assert_eq!(account.balance, U256::from(5));
+        assert_eq!(evm.journaled_state.nonce(&CALLER), 1);
+    }
+
+    #[test]
+    fn create_failures() {
+        let mut evm = build_evm(&[]);
+        // Returns 0xef, rejected by EIP-3541.
+        let init_code = Bytes::from_static(&[
+            opcodes::PUSH1,
+            0xef,
+            opcodes::PUSH0,
+            opcodes::MSTORE8,
+            opcodes::PUSH1,
+            0x01,
+            opcodes::PUSH0,
+            opcodes::RETURN,
+        ]);
+        let inputs = CreateInputs {
+            caller: CALLER,
+            init_code,
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+        let outcome = evm.create(inputs.clone());
+        assert_eq!(
+            outcome.result,
+            InstructionResult::CreateContractStartingWithEF
+        );
+        assert_eq!(outcome.gas.remaining(), 0);
+        assert!(evm.journaled_state.account(&CALLER.create(0)).is_none());
+        // The nonce is bumped even though the creation failed.
+        assert_eq!(evm.journaled_state.nonce(&CALLER), 1);
+
+        // The next address already has code.
+        let taken = CALLER.create(1);
+        evm.journaled_state
+            .state
+            .insert(taken, Account::with_code(Bytes::from_static(&[0])));
+        let outcome = evm.create(inputs);
+        assert_eq!(outcome.result, InstructionResult::CreateCollision);
+        assert_eq!(outcome.address, None);
+    }
+
+    #[test]
+    fn selfdestruct_eip6780() {
+        let contract = Address::with_last_byte(0xaa);
+        let target = Address::with_last_byte(0xbb);
+        let mut evm = build_evm(&[(contract, Account::with_balance(U256::from(3)))]);
+
+        let result = evm.selfdestruct(contract, target);
+        assert!(result.is_cold);
+        assert!(result.data.had_value && result.data.target_is_empty);
+        // Moves the balance but keeps the account, it was not created in this transaction.
+        assert_eq!(evm.journaled_state.balance(&target), U256::from(3));
+        assert!(evm.journaled_state.destroyed.is_empty());
+
+        evm.env.cfg.spec = SpecId::Shanghai;
+        evm.selfdestruct(contract, contract);
+        assert!(evm.journaled_state.destroyed.contains(&contract));
+        assert!(evm.selfdestruct(contract, target).data.previously_destroyed);
+    }
+}
@@ -0,0 +1,686 @@
+pub mod block;
+
+use bytes::Bytes;
+use hashbrown::HashMap;
+use ruint::aliases::U256;
+
+use crate::{
+    domain::{
+        constants::{MAX_INITCODE_SIZE, SYSTEM_ADDRESS, SYSTEM_CALL_GAS_LIMIT},
+        env::{BlockEnv, CfgEnv, Env, TransactTo, TxEnv, MAX_BLOB_GAS_PER_BLOCK},
+        journal::JournaledState,
+        primitives::{Address, B256, KECCAK_EMPTY},
+        spec::SpecId,
+        state::{Log, State},
+    },
+    evm::Evm,
+    inspector::{Inspector, NoopInspector},
+    interpreter::{
+        gas,
+        host::{CallInputs, CallKind, CreateInputs, Host},
+        InstructionResult,
+    },
+    precompiles::{point_evaluation::VERSIONED_HASH_VERSION_KZG, Precompiles},
+};
+
+pub type Result<T> = std::result::Result<T, InvalidTransaction>;
+
+/// Reasons a transaction can not be included in a block at all, as opposed to a transaction
+/// that executes and fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvalidTransaction {
+    InvalidChainId,
+    /// A access list before Berlin.
+    Eip2930NotSupported,
+    /// A priority fee before London.
+    Eip1559NotSupported,
+    /// Blobs before Cancun.
+    Eip4844NotSupported,
+    PriorityFeeGreaterThanMaxFee,
+    GasPriceLessThanBasefee,
+    CallerGasLimitMoreThanBlock,
+    /// The gas limit does not cover the intrinsic gas.
+    CallGasCostMoreThanGasLimit,
+    /// EIP-3607: only accounts without code can send transactions.
+    RejectCallerWithCode,
+    LackOfFundForMaxFee {
+        fee: U256,
+        balance: U256,
+    },
+    OverflowPaymentInTransaction,
+    NonceOverflowInTransaction,
+    NonceTooHigh {
+        tx: u64,
+        state: u64,
+    },
+    NonceTooLow {
+        tx: u64,
+        state: u64,
+    },
+    /// EIP-3860
+    CreateInitCodeSizeLimit,
+    BlobCreateTransaction,
+    EmptyBlobs,
+    TooManyBlobs,
+    BlobVersionNotSupported,
+    BlobGasPriceGreaterThanMax,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Output {
+    Call(Bytes),
+    Create(Address),
+}
+
+/// The outcome of a transaction that made it into the block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExecutionResult {
+    Success {
+        reason: InstructionResult,
+        gas_used: u64,
+        gas_refunded: u64,
+        logs: Vec<Log>,
+        output: Output,
+    },
+    /// The top-level frame reverted, the gas that was left is returned.
+    Revert { gas_used: u64, output: Bytes },
+    /// The top-level frame failed and consumed all of its gas.
+    Halt {
+        reason: InstructionResult,
+        gas_used: u64,
+    },
+}
+
+impl ExecutionResult {
+    fn new(
+        result: InstructionResult,
+        gas_used: u64,
+        gas_refunded: u64,
+        logs: Vec<Log>,
+        output: Bytes,
+        output_kind: Output,
+    ) -> Self {
+        if result.is_ok() {
+            Self::Success {
+                reason: result,
+                gas_used,
+                gas_refunded,
+                logs,
+                output: output_kind,
+            }
+        } else if result.is_revert() {
+            Self::Revert { gas_used, output }
+        } else {
+            Self::Halt {
+                reason: result,
+                gas_used,
+            }
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success { .. })
+    }
+
+    pub fn gas_used(&self) -> u64 {
+        match self {
+            Self::Success { gas_used, .. }
+            | Self::Revert { gas_used, .. }
+            | Self::Halt { gas_used, .. } => *gas_used,
+        }
+    }
+}
+
+/// Validates and executes transactions, one at a time, against a world state.
+#[derive(Clone, Debug, Default)]
+pub struct Executor {
+    pub cfg: CfgEnv,
+    pub precompiles: Precompiles,
+    /// Hashes of recent blocks for BLOCKHASH.
+    pub block_hashes: HashMap<u64, B256>,
+}
+
+impl Executor {
+    pub fn new(cfg: CfgEnv) -> Self {
+        Self {
+            precompiles: Precompiles::new(cfg.spec),
+            cfg,
+            block_hashes: HashMap::new(),
+        }
+    }
+
+    fn spec(&self) -> SpecId {
+        self.cfg.spec
+    }
+
+    /// Executes `tx` in `block` and applies the result to `state`. A invalid transaction leaves
+    /// `state` untouched.
+    pub fn transact(
+        &self,
+        tx: &TxEnv,
+        block: &BlockEnv,
+        state: &mut State,
+    ) -> Result<ExecutionResult> {
+        self.transact_with_inspector(tx, block, state, NoopInspector)
+    }
+
+    /// As [`Executor::transact`], with `inspector` hooked into the execution.
+    pub fn transact_with_inspector<I: Inspector>(
+        &self,
+        tx: &TxEnv,
+        block: &BlockEnv,
+        state: &mut State,
+        inspector: I,
+    ) -> Result<ExecutionResult> {
+        let env = Env {
+            cfg: self.cfg,
+            block: block.clone(),
+            tx: tx.clone(),
+        };
+        self.validate_env(&env)?;
+        let is_create = tx.transact_to == TransactTo::Create;
+        let intrinsic_gas = gas::intrinsic_gas(self.spec(), &tx.data, is_create, &tx.access_list);
+        if intrinsic_gas > tx.gas_limit {
+            return Err(InvalidTransaction::CallGasCostMoreThanGasLimit);
+        }
+        validate_against_state(&env, state)?;
+
+        let mut evm = Evm {
+            env,
+            journaled_state: JournaledState::new(std::mem::take(state)),
+            precompiles: self.precompiles.clone(),
+            block_hashes: self.block_hashes.clone(),
+            inspector,
+        };
+        let result = self.execute(&mut evm, intrinsic_gas);
+        evm.inspector.transaction_end(tx, &result);
+        *state = evm.journaled_state.into_state();
+        Ok(result)
+    }
+
+    fn validate_env(&self, env: &Env) -> Result<()> {
+        let Env { block, tx, .. } = env;
+        let spec = self.spec();
+        if tx
+            .chain_id
+            .is_some_and(|chain_id| chain_id != self.cfg.chain_id)
+        {
+            return Err(InvalidTransaction::InvalidChainId);
+        }
+        if !tx.access_list.is_empty() && !spec.is_enabled_in(SpecId::Berlin) {
+            return Err(InvalidTransaction::Eip2930NotSupported);
+        }
+        if let Some(priority_fee) = tx.gas_priority_fee {
+            if !spec.is_enabled_in(SpecId::London) {
+                return Err(InvalidTransaction::Eip1559NotSupported);
+            }
+            if priority_fee > tx.gas_price {
+                return Err(InvalidTransaction::PriorityFeeGreaterThanMaxFee);
+            }
+        }
+        if spec.is_enabled_in(SpecId::London) && tx.gas_price < block.basefee {
+            return Err(InvalidTransaction::GasPriceLessThanBasefee);
+        }
+        if tx.gas_limit > block.gas_limit {
+            return Err(InvalidTransaction::CallerGasLimitMoreThanBlock);
+        }
+        if spec.is_enabled_in(SpecId::Shanghai)
+            && tx.transact_to == TransactTo::Create
+            && tx.data.len() > MAX_INITCODE_SIZE
+        {
+            return Err(InvalidTransaction::CreateInitCodeSizeLimit);
+        }
+
+        if let Some(max_fee_per_blob_gas) = tx.max_fee_per_blob_gas {
+            if !spec.is_enabled_in(SpecId::Cancun) {
+                return Err(InvalidTransaction::Eip4844NotSupported);
+            }
+            if tx.transact_to == TransactTo::Create {
+                return Err(InvalidTransaction::BlobCreateTransaction);
+            }
+            if tx.blob_hashes.is_empty() {
+                return Err(InvalidTransaction::EmptyBlobs);
+            }
+            if tx.blob_gas() > MAX_BLOB_GAS_PER_BLOCK {
+                return Err(InvalidTransaction::TooManyBlobs);
+            }
+            if tx
+                .blob_hashes
+                .iter()
+                .any(|hash| hash[0] != VERSIONED_HASH_VERSION_KZG)
+            {
+                return Err(InvalidTransaction::BlobVersionNotSupported);
+            }
+            let blob_gasprice = U256::from(block.blob_gasprice().unwrap_or_default());
+            if max_fee_per_blob_gas < blob_gasprice {
+                return Err(InvalidTransaction::BlobGasPriceGreaterThanMax);
+            }
+        }
+        Ok(())
+    }
+
+    fn execute<I: Inspector>(&self, evm: &mut Evm<I>, intrinsic_gas: u64) -> ExecutionResult {
+        let spec = self.spec();
+        let tx = evm.env.tx.clone();
+        let block = evm.env.block.clone();
+        let gas_price = evm.env.effective_gas_price();
+        let blob_fee =
+            U256::from(tx.blob_gas()) * U256::from(block.blob_gasprice().unwrap_or_default());
+
+        // Buys the gas, the balance was checked against the maximum this can cost.
+        let journaled_state = &mut evm.journaled_state;
+        let _ = journaled_state
+            .balance_decr(tx.caller, U256::from(tx.gas_limit) * gas_price + blob_fee);
+        journaled_state.touch(tx.caller);
+        let recipient = match tx.transact_to {
+            TransactTo::Call(address) => {
+                // CREATE bumps the nonce itself.
+                journaled_state.inc_nonce(tx.caller);
+                Some(address)
+            }
+            TransactTo::Create => None,
+        };
+        journaled_state.prewarm(
+            tx.caller,
+            recipient,
+            &tx.access_list,
+            self.precompiles.addresses(),
+        );
+        if spec.is_enabled_in(SpecId::Shanghai) {
+            // EIP-3651: Warm COINBASE
+            journaled_state.warm_addresses.insert(block.coinbase);
+        }
+
+        let gas_limit = tx.gas_limit - intrinsic_gas;
+        let (result, frame_gas, output, output_kind) = match tx.transact_to {
+            TransactTo::Call(address) => {
+                let outcome = evm.call(CallInputs {
+                    kind: CallKind::Call,
+                    input: tx.data,
+                    gas_limit,
+                    target_address: address,
+                    code_address: address,
+                    caller: tx.caller,
+                    value: tx.value,
+                    transfers_value: true,
+                    is_static: false,
+                });
+                let kind = Output::Call(outcome.output.clone());
+                (outcome.result, outcome.gas, outcome.output, kind)
+            }
+            TransactTo::Create => {
+                let outcome = evm.create(CreateInputs {
+                    caller: tx.caller,
+                    value: tx.value,
+                    init_code: tx.data,
+                    gas_limit,
+                    ..Default::default()
+                });
+                let kind = Output::Create(outcome.address.unwrap_or_default());
+                (outcome.result, outcome.gas, outcome.output, kind)
+            }
+        };
+
+        // EIP-3529: Reduction in refunds
+        let max_refund_quotient = if spec.is_enabled_in(SpecId::London) {
+            5
+        } else {
+            2
+        };
+        let gas_spent = tx.gas_limit - frame_gas.remaining();
+        let gas_refunded = if result.is_ok() {
+            (frame_gas.refunded().max(0) as u64).min(gas_spent / max_refund_quotient)
+        } else {
+            0
+        };
+        let gas_used = gas_spent - gas_refunded;
+
+        // Returns what was not used, the base fee is burned and the rest goes to the coinbase.
+        let journaled_state = &mut evm.journaled_state;
+        let _ = journaled_state
+            .balance_incr(tx.caller, U256::from(tx.gas_limit - gas_used) * gas_price);
+        let coinbase_gas_price = if spec.is_enabled_in(SpecId::London) {
+            gas_price - block.basefee
+        } else {
+            gas_price
+        };
+        let _ =
+            journaled_state.balance_incr(block.coinbase, U256::from(gas_used) * coinbase_gas_price);
+        journaled_state.touch(block.coinbase);
+
+        journaled_state.remove_destroyed();
+        if spec.is_enabled_in(SpecId::SpuriousDragon) {
+            journaled_state.remove_touched_empty();
+        }
+        let logs = journaled_state.finalize();
+
+        ExecutionResult::new(result, gas_used, gas_refunded, logs, output, output_kind)
+    }
+
+    /// Calls `address` from [`SYSTEM_ADDRESS`] at the start of `block`, e.g. to store the beacon
+    /// root (EIP-4788). Unlike a transaction, no gas is bought, nothing is paid to the coinbase
+    /// and the gas used does not count towards the block.
+    pub fn system_call(
+        &self,
+        address: Address,
+        data: Bytes,
+        block: &BlockEnv,
+        state: &mut State,
+    ) -> ExecutionResult {
+        let env = Env {
+            cfg: self.cfg,
+            block: block.clone(),
+            tx: TxEnv {
+                caller: SYSTEM_ADDRESS,
+                gas_limit: SYSTEM_CALL_GAS_LIMIT,
+                transact_to: TransactTo::Call(address),
+                data: data.clone(),
+                ..Default::default()
+            },
+        };
+        let mut evm = Evm {
+            env,
+            journaled_state: JournaledState::new(std::mem::take(state)),
+            precompiles: self.precompiles.clone(),
+            block_hashes: self.block_hashes.clone(),
+            inspector: NoopInspector,
+        };
+        let outcome = evm.call(CallInputs {
+            kind: CallKind::Call,
+            input: data,
+            gas_limit: SYSTEM_CALL_GAS_LIMIT,
+            target_address: address,
+            code_address: address,
+            caller: SYSTEM_ADDRESS,
+            value: U256::ZERO,
+            transfers_value: false,
+            is_static: false,
+        });
+
+        let journaled_state = &mut evm.journaled_state;
+        journaled_state.remove_touched_empty();
+        let logs = journaled_state.finalize();
+        *state = evm.journaled_state.into_state();
+
+        let gas_used = SYSTEM_CALL_GAS_LIMIT - outcome.gas.remaining();
+        let output_kind = Output::Call(outcome.output.clone());
+        ExecutionResult::new(
+            outcome.result,
+            gas_used,
+            0,
+            logs,
+            outcome.output,
+            output_kind,
+        )
+    }
+}
+
+/// Checks the sender can pay for the transaction and its nonce lines up.
+fn validate_against_state(env: &Env, state: &State) -> Result<()> {
+    let tx = &env.tx;
+    let (nonce, balance, code_hash) = state
+        .get(&tx.caller)
+        .map(|account| (account.nonce, account.balance, account.code_hash))
+        .unwrap_or((0, U256::ZERO, KECCAK_EMPTY));
+
+    if code_hash != KECCAK_EMPTY {
+        return Err(InvalidTransaction::RejectCallerWithCode);
+    }
+    // EIP-2681: Limit account nonce to 2^64-1
+    if nonce == u64::MAX {
+        return Err(InvalidTransaction::NonceOverflowInTransaction);
+    }
+    if let Some(tx_nonce) = tx.nonce {
+        if tx_nonce > nonce {
+            return Err(InvalidTransaction::NonceTooHigh {
+                tx: tx_nonce,
+                state: nonce,
+            });
+        }
+        if tx_nonce < nonce {
+            return Err(InvalidTransaction::NonceTooLow {
+                tx: tx_nonce,
+                state: nonce,
+            });
+        }
+    }
+
+    // The balance has to cover the gas at the max fee, not just what ends up being paid.
+    let blob_fee = tx
+        .max_fee_per_blob_gas
+        .unwrap_or_default()
+        .checked_mul(U256::from(tx.blob_gas()));
+    let fee = U256::from(tx.gas_limit)
+        .checked_mul(tx.gas_price)
+        .zip(blob_fee)
+        .and_then(|(fee, blob_fee)| fee.checked_add(blob_fee))
+        .and_then(|fee| fee.checked_add(tx.value))
+        .ok_or(InvalidTransaction::OverflowPaymentInTransaction)?;
+    if balance < fee {
+        return Err(InvalidTransaction::LackOfFundForMaxFee { fee, balance });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{domain::state::Account, interpreter::opcodes};
+
+    use super::*;
+
+    const CALLER: Address = Address([0xca; 20]);
+    const COINBASE: Address = Address([0xcb; 20]);
+    const ETHER: u64 = 1_000_000_000_000_000_000;
+
+    fn build_state(accounts: &[(Address, Account)]) -> State {
+        let mut state = State::new();
+        state.insert(CALLER, Account::with_balance(U256::from(ETHER)));
+        state.extend(accounts.iter().cloned());
+        state
+    }
+
+    fn block() -> BlockEnv {
+        BlockEnv {
+            coinbase: COINBASE,
+            gas_limit: 30_000_000,
+            basefee: U256::from(7),
+            ..Default::default()
+        }
+    }
+
+    fn tx(transact_to: TransactTo, data: &'static [u8]) -> TxEnv {
+        TxEnv {
+            caller: CALLER,
+            gas_limit: 100_000,
+            gas_price: U256::from(10),
+            gas_priority_fee: Some(U256::from(2)),
+            transact_to,
+            data: Bytes::from_static(data),
+            nonce: Some(0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn value_transfer() {
+        let executor = Executor::new(CfgEnv::default());
+        let to = Address::with_last_byte(0xaa);
+        let mut state = build_state(&[]);
+        let mut tx = tx(TransactTo::Call(to), &[]);
+        tx.value = U256::from(5);
+
+        let result = executor.transact(&tx, &block(), &mut state).unwrap();
+        assert_eq!(
+            result,
+            ExecutionResult::Success {
+                reason: InstructionResult::Stop,
+                gas_used: 21000,
+                gas_refunded: 0,
+                logs: vec![],
+                output: Output::Call(Bytes::new()),
+            }
+        );
+        // Pays base fee plus priority fee, only the priority fee reaches the coinbase.
+        let sender = &state[&CALLER];
+        assert_eq!(sender.balance, U256::from(ETHER - 5 - 21000 * 9));
+        assert_eq!(sender.nonce, 1);
+        assert_eq!(state[&to].balance, U256::from(5));
+        assert_eq!(state[&COINBASE].balance, U256::from(21000 * 2));
+    }
+
+    #[test]
+    fn invalid_transactions() {
+        let executor = Executor::new(CfgEnv::default());
+        let to = Address::with_last_byte(0xaa);
+        let mut state = build_state(&[]);
+        let valid = tx(TransactTo::Call(to), &[]);
+
+        let mut tx = valid.clone();
+        tx.nonce = Some(1);
+        assert_eq!(
+            executor.transact(&tx, &block(), &mut state),
+            Err(InvalidTransaction::NonceTooHigh { tx: 1, state: 0 })
+        );
+
+        tx = valid.clone();
+        tx.gas_limit = 20999;
+        assert_eq!(
+            executor.transact(&tx, &block(), &mut state),
+            Err(InvalidTransaction::CallGasCostMoreThanGasLimit)
+        );
+
+        tx = valid.clone();
+        tx.gas_price = U256::from(6);
+        tx.gas_priority_fee = None;
+        assert_eq!(
+            executor.transact(&tx, &block(), &mut state),
+            Err(InvalidTransaction::GasPriceLessThanBasefee)
+        );
+
+        tx = valid.clone();
+        tx.value = U256::from(ETHER);
+        assert_eq!(
+            executor.transact(&tx, &block(), &mut state),
+            Err(InvalidTransaction::LackOfFundForMaxFee {
+                fee: U256::from(ETHER + 100_000 * 10),
+                balance: U256::from(ETHER),
+            })
+        );
+
+        tx = valid.clone();
+        tx.chain_id = Some(5);
+        assert_eq!(
+            executor.transact(&tx, &block(), &mut state),
+            Err(InvalidTransaction::InvalidChainId)
+        );
+        // None of these touched the state.
+        assert_eq!(state, build_state(&[]));
+    }
+
+    #[test]
+    fn refund_is_capped() {
+        let executor = Executor::new(CfgEnv::default());
+        let to = Address::with_last_byte(0xaa);
+        // Clears slot 1.
+        let mut account = Account::with_code(Bytes::from_static(&[
+            opcodes::PUSH0,
+            opcodes::PUSH1,
+            0x01,
+            opcodes::SSTORE,
+        ]));
+        account.storage.insert(U256::from(1), U256::from(1));
+        let mut state = build_state(&[(to, account)]);
+
+        let result = executor
+            .transact(&tx(TransactTo::Call(to), &[]), &block(), &mut state)
+            .unwrap();
+        let gas_spent = 21000 + 2 + 3 + 5000;
+        // The 4800 refund fits under the cap of a fifth of the gas spent.
+        assert!(result.is_success());
+        assert_eq!(result.gas_used(), gas_spent - 4800);
+        assert!(state[&to].storage[&U256::from(1)].is_zero());
+    }
+
+    #[test]
+    fn create_contract() {
+        let executor = Executor::new(CfgEnv::default());
+        let mut state = build_state(&[]);
+        // Deploys the single byte 0x2a.
+        let init_code = &[
+            opcodes::PUSH1,
+            0x2a,
+            opcodes::PUSH0,
+            opcodes::MSTORE8,
+            opcodes::PUSH1,
+            0x01,
+            opcodes::PUSH0,
+            opcodes::RETURN,
+        ];
+
+        let result = executor
+            .transact(&tx(TransactTo::Create, init_code), &block(), &mut state)
+            .unwrap();
+        let address = CALLER.create(0);
+        let intrinsic = 53000 + 16 * 8 + 2;
+        assert_eq!(
+            result,
+            ExecutionResult::Success {
+                reason: InstructionResult::Return,
+                gas_used: intrinsic + 16 + 200,
+                gas_refunded: 0,
+                logs: vec![],
+                output: Output::Create(address),
+            }
+        );
+        assert_eq!(state[&CALLER].nonce, 1);
+        assert_eq!(state[&address].code, Bytes::from_static(&[0x2a]));
+    }
+
+    #[test]
+    fn revert_and_halt() {
+        let executor = Executor::new(CfgEnv::default());
+        let reverts = Address::with_last_byte(0xaa);
+        let halts = Address::with_last_byte(0xbb);
+        let mut state = build_state(&[
+            (
+                reverts,
+                Account::with_code(Bytes::from_static(&[
+                    opcodes::PUSH0,
+                    opcodes::PUSH0,
+                    opcodes::REVERT,
+                ])),
+            ),
+            (
+                halts,
+                Account::with_code(Bytes::from_static(&[opcodes::INVALID])),
+            ),
+        ]);
+
+        let mut tx = tx(TransactTo::Call(reverts), &[]);
+        tx.value = U256::from(5);
+        let result = executor.transact(&tx, &block(), &mut state).unwrap();
+        assert_eq!(
+            result,
+            ExecutionResult::Revert {
+                gas_used: 21004,
+                output: Bytes::new(),
+            }
+        );
+        assert!(state[&reverts].balance.is_zero());
+
+        tx.transact_to = TransactTo::Call(halts);
+        tx.nonce = Some(1);
+        let result = executor.transact(&tx, &block(), &mut state).unwrap();
+        assert_eq!(
+            result,
+            ExecutionResult::Halt {
+                reason: InstructionResult::InvalidFEOpcode,
+                gas_used: 100_000,
+            }
+        );
+        // Both still paid for their gas and bumped the nonce.
+        let sender = &state[&CALLER];
+        assert_eq!(sender.nonce, 2);
+        assert_eq!(sender.balance, U256::from(ETHER - 121_004 * 9));
+    }
+}
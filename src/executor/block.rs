@@ -0,0 +1,882 @@
+use std::fmt;
+
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use crate::domain::{
+    block::{self, Block, Header, HeaderError, Withdrawal},
+    constants::BEACON_ROOTS_ADDRESS,
+    env::{CfgEnv, MAX_BLOB_GAS_PER_BLOCK},
+    primitives::{Address, B256},
+    receipt::{self, Bloom, Receipt},
+    spec::SpecId,
+    state::{Account, State},
+    transaction::TransactionError,
+    trie,
+};
+
+use super::{ExecutionResult, Executor, InvalidTransaction};
+
+pub type Result<T> = std::result::Result<T, BlockError>;
+
+const ETHER: u64 = 1_000_000_000_000_000_000;
+const GWEI: u64 = 1_000_000_000;
+/// Ommers further back than this earn nothing and are not allowed in a block.
+pub const MAX_OMMER_DEPTH: u64 = 6;
+
+/// Reasons a block can not be executed, `index` is that of the offending transaction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BlockError {
+    /// The sender could not be recovered from the signature.
+    InvalidSignature {
+        index: usize,
+        error: TransactionError,
+    },
+    InvalidTransaction {
+        index: usize,
+        error: InvalidTransaction,
+    },
+    /// The gas limit of the transaction is more than what is left of the block gas limit.
+    TransactionGasLimitMoreThanAvailableBlockGas {
+        index: usize,
+        gas_limit: u64,
+        available: u64,
+    },
+    BlobGasLimitExceeded {
+        index: usize,
+    },
+    /// The header does not follow from its parent.
+    Header(HeaderError),
+    ParentHashMismatch,
+    NumberMismatch {
+        expected: u64,
+        got: u64,
+    },
+    TimestampNotIncreasing,
+    /// A root or hash in the header does not match the block or its outcome.
+    Mismatch {
+        field: &'static str,
+        expected: B256,
+        got: B256,
+    },
+    GasUsedMismatch {
+        expected: u64,
+        got: u64,
+    },
+    BlobGasUsedMismatch {
+        expected: u64,
+        got: u64,
+    },
+    LogsBloomMismatch,
+    /// Withdrawals in a block without a withdrawals root, or the other way round.
+    WithdrawalsMismatch,
+    /// The ommer is not one to six blocks older than the block.
+    InvalidOmmerDepth {
+        index: usize,
+    },
+    /// Blocks have no ommers since the merge (EIP-3675).
+    OmmersAfterMerge,
+    /// Withdrawals, or a withdrawals root, before Shanghai (EIP-4895).
+    WithdrawalsBeforeShanghai,
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSignature { index, error } => write!(f, "transaction {index}: {error}"),
+            Self::InvalidTransaction { index, error } => {
+                write!(f, "transaction {index}: {error:?}")
+            }
+            Self::TransactionGasLimitMoreThanAvailableBlockGas {
+                index,
+                gas_limit,
+                available,
+            } => write!(
+                f,
+                "transaction {index}: gas limit {gas_limit} more than the {available} left in the block"
+            ),
+            Self::BlobGasLimitExceeded { index } => {
+                write!(f, "transaction {index}: too much blob gas in the block")
+            }
+            Self::Header(error) => write!(f, "invalid header: {error}"),
+            Self::ParentHashMismatch => write!(f, "parent hash is not that of the parent"),
+            Self::NumberMismatch { expected, got } => {
+                write!(f, "block number {got}, expected {expected}")
+            }
+            Self::TimestampNotIncreasing => write!(f, "timestamp not after the parent's"),
+            Self::Mismatch {
+                field,
+                expected,
+                got,
+            } => write!(
+                f,
+                "{field} 0x{}, expected 0x{}",
+                hex::encode(got),
+                hex::encode(expected)
+            ),
+            Self::GasUsedMismatch { expected, got } => {
+                write!(f, "gas used {got}, expected {expected}")
+            }
+            Self::BlobGasUsedMismatch { expected, got } => {
+                write!(f, "blob gas used {got}, expected {expected}")
+            }
+            Self::LogsBloomMismatch => write!(f, "logs bloom does not match the receipts"),
+            Self::WithdrawalsMismatch => write!(f, "withdrawals do not match the header"),
+            Self::InvalidOmmerDepth { index } => {
+                write!(f, "ommer {index}: not 1 to {MAX_OMMER_DEPTH} blocks old")
+            }
+            Self::OmmersAfterMerge => write!(f, "ommers after the merge"),
+            Self::WithdrawalsBeforeShanghai => write!(f, "withdrawals before Shanghai"),
+        }
+    }
+}
+
+impl std::error::Error for BlockError {}
+
+impl From<HeaderError> for BlockError {
+    fn from(error: HeaderError) -> Self {
+        Self::Header(error)
+    }
+}
+
+fn check_hash(field: &'static str, expected: B256, got: B256) -> Result<()> {
+    match expected == got {
+        true => Ok(()),
+        false => Err(BlockError::Mismatch {
+            field,
+            expected,
+            got,
+        }),
+    }
+}
+
+/// The outcome of a block, what its header commits to.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BlockResult {
+    pub receipts: Vec<Receipt>,
+    /// The result of each transaction, in order.
+    pub results: Vec<ExecutionResult>,
+    pub gas_used: u64,
+    pub blob_gas_used: u64,
+    pub logs_bloom: Bloom,
+    pub receipts_root: B256,
+    pub state_root: B256,
+}
+
+/// Executes whole blocks, transaction by transaction, on top of a [`Executor`].
+#[derive(Clone, Debug, Default)]
+pub struct BlockExecutor {
+    pub executor: Executor,
+}
+
+impl BlockExecutor {
+    pub fn new(cfg: CfgEnv) -> Self {
+        Self {
+            executor: Executor::new(cfg),
+        }
+    }
+
+    fn spec(&self) -> SpecId {
+        self.executor.cfg.spec
+    }
+
+    /// Executes the transactions of `block` in order, then pays the block reward and credits
+    /// the withdrawals. On error `state` holds the transactions before the failing one and
+    /// should be discarded.
+    pub fn execute(&self, block: &Block, state: &mut State) -> Result<BlockResult> {
+        let spec = self.spec();
+        let header = &block.header;
+        let block_env = header.block_env();
+        if spec.is_enabled_in(SpecId::Paris) && !block.ommers.is_empty() {
+            return Err(BlockError::OmmersAfterMerge);
+        }
+
+        if spec.is_enabled_in(SpecId::Cancun) {
+            let parent_beacon_block_root = header
+                .parent_beacon_block_root
+                .ok_or(HeaderError::ParentBeaconBlockRootMissing)?;
+            // EIP-4788: a failing call, e.g. before the contract is deployed, is ignored.
+            self.executor.system_call(
+                BEACON_ROOTS_ADDRESS,
+                Bytes::copy_from_slice(&parent_beacon_block_root),
+                &block_env,
+                state,
+            );
+        }
+
+        let mut result = BlockResult::default();
+        for (index, tx) in block.transactions.iter().enumerate() {
+            let available = header.gas_limit - result.gas_used;
+            let gas_limit = tx.transaction.gas_limit();
+            if gas_limit > available {
+                return Err(BlockError::TransactionGasLimitMoreThanAvailableBlockGas {
+                    index,
+                    gas_limit,
+                    available,
+                });
+            }
+            result.blob_gas_used += tx.transaction.blob_gas();
+            if result.blob_gas_used > MAX_BLOB_GAS_PER_BLOCK {
+                return Err(BlockError::BlobGasLimitExceeded { index });
+            }
+
+            let sender = tx
+                .recover_sender(spec)
+                .map_err(|error| BlockError::InvalidSignature { index, error })?;
+            let outcome = self
+                .executor
+                .transact(&tx.transaction.tx_env(sender), &block_env, state)
+                .map_err(|error| BlockError::InvalidTransaction { index, error })?;
+
+            result.gas_used += outcome.gas_used();
+            let logs = match &outcome {
+                ExecutionResult::Success { logs, .. } => logs.clone(),
+                _ => Vec::new(),
+            };
+            let receipt = Receipt {
+                tx_type: tx.transaction.tx_type(),
+                success: outcome.is_success(),
+                // EIP-658: the status replaced the intermediate state root in Byzantium.
+                post_state: (!spec.is_enabled_in(SpecId::Byzantium))
+                    .then(|| trie::state_root(state)),
+                cumulative_gas_used: result.gas_used,
+                logs_bloom: Bloom::from_logs(&logs),
+                logs,
+            };
+            result.logs_bloom.accrue_bloom(&receipt.logs_bloom);
+            result.receipts.push(receipt);
+            result.results.push(outcome);
+        }
+
+        apply_block_reward(spec, header, &block.ommers, state);
+        if let Some(withdrawals) = &block.withdrawals {
+            if spec.is_enabled_in(SpecId::Shanghai) {
+                apply_withdrawals(withdrawals, state);
+            }
+        }
+
+        result.receipts_root = receipt::receipts_root(&result.receipts);
+        result.state_root = trie::state_root(state);
+        Ok(result)
+    }
+
+    /// Checks `block` against `parent` and its own header, executes it and checks the header
+    /// commits to the outcome. On error `state` should be discarded.
+    pub fn import(&self, block: &Block, parent: &Header, state: &mut State) -> Result<BlockResult> {
+        let header = &block.header;
+        if header.parent_hash != parent.hash() {
+            return Err(BlockError::ParentHashMismatch);
+        }
+        if header.number != parent.number + 1 {
+            return Err(BlockError::NumberMismatch {
+                expected: parent.number + 1,
+                got: header.number,
+            });
+        }
+        if header.timestamp <= parent.timestamp {
+            return Err(BlockError::TimestampNotIncreasing);
+        }
+        let spec = self.spec();
+        header.validate_against_parent(parent, spec)?;
+        if spec.is_enabled_in(SpecId::Paris) && !block.ommers.is_empty() {
+            return Err(BlockError::OmmersAfterMerge);
+        }
+        if !spec.is_enabled_in(SpecId::Shanghai)
+            && (block.withdrawals.is_some() || header.withdrawals_root.is_some())
+        {
+            return Err(BlockError::WithdrawalsBeforeShanghai);
+        }
+        validate_body(block)?;
+        for (index, ommer) in block.ommers.iter().enumerate() {
+            if ommer_depth(header, ommer).is_none() {
+                return Err(BlockError::InvalidOmmerDepth { index });
+            }
+        }
+
+        let result = self.execute(block, state)?;
+        if header.gas_used != result.gas_used {
+            return Err(BlockError::GasUsedMismatch {
+                expected: header.gas_used,
+                got: result.gas_used,
+            });
+        }
+        if let Some(expected) = header.blob_gas_used {
+            if expected != result.blob_gas_used {
+                return Err(BlockError::BlobGasUsedMismatch {
+                    expected,
+                    got: result.blob_gas_used,
+                });
+            }
+        }
+        check_hash("receipts root", header.receipts_root, result.receipts_root)?;
+        if header.logs_bloom != result.logs_bloom {
+            return Err(BlockError::LogsBloomMismatch);
+        }
+        check_hash("state root", header.state_root, result.state_root)?;
+        Ok(result)
+    }
+}
+
+/// Checks the header commits to the transactions, ommers and withdrawals of the block.
+pub fn validate_body(block: &Block) -> Result<()> {
+    let header = &block.header;
+    check_hash(
+        "transactions root",
+        header.transactions_root,
+        block::transactions_root(&block.transactions),
+    )?;
+    check_hash(
+        "ommers hash",
+        header.ommers_hash,
+        block::ommers_hash(&block.ommers),
+    )?;
+    match (header.withdrawals_root, &block.withdrawals) {
+        (Some(expected), Some(withdrawals)) => check_hash(
+            "withdrawals root",
+            expected,
+            block::withdrawals_root(withdrawals),
+        ),
+        (None, None) => Ok(()),
+        _ => Err(BlockError::WithdrawalsMismatch),
+    }
+}
+
+/// The reward for mining a block, none since the merge.
+pub fn block_reward(spec: SpecId) -> U256 {
+    let ether = if spec.is_enabled_in(SpecId::Paris) {
+        0
+    } else if spec.is_enabled_in(SpecId::Constantinople) {
+        // EIP-1234
+        2
+    } else if spec.is_enabled_in(SpecId::Byzantium) {
+        // EIP-649
+        3
+    } else {
+        5
+    };
+    U256::from(ether) * U256::from(ETHER)
+}
+
+/// How many blocks `ommer` is older than the block of `header`, if that is within the
+/// [`MAX_OMMER_DEPTH`].
+pub fn ommer_depth(header: &Header, ommer: &Header) -> Option<u64> {
+    header
+        .number
+        .checked_sub(ommer.number)
+        .filter(|depth| (1..=MAX_OMMER_DEPTH).contains(depth))
+}
+
+/// Pays the miner the block reward plus 1/32 of it per ommer, and each ommer miner 1/8 of the
+/// reward for every block it is less than 8 behind. Ommers too old or new for a reward, which
+/// [`BlockExecutor::import`] rejects, get nothing and earn the miner nothing.
+pub fn apply_block_reward(spec: SpecId, header: &Header, ommers: &[Header], state: &mut State) {
+    let reward = block_reward(spec);
+    if reward.is_zero() {
+        return;
+    }
+    let mut credit = |address: Address, amount: U256| {
+        let account = state.entry(address).or_default();
+        account.balance = account.balance.saturating_add(amount);
+    };
+    let mut included = 0u64;
+    for ommer in ommers {
+        if let Some(depth) = ommer_depth(header, ommer) {
+            credit(
+                ommer.beneficiary,
+                reward * U256::from(8 - depth) / U256::from(8),
+            );
+            included += 1;
+        }
+    }
+    credit(
+        header.beneficiary,
+        reward + reward / U256::from(32) * U256::from(included),
+    );
+}
+
+/// Credits each withdrawal, converted from gwei to wei (EIP-4895). A zero withdrawal only
+/// touches the account, which removes it if it is empty (EIP-161).
+pub fn apply_withdrawals(withdrawals: &[Withdrawal], state: &mut State) {
+    for withdrawal in withdrawals {
+        if withdrawal.amount == 0 {
+            if state
+                .get(&withdrawal.address)
+                .is_some_and(Account::is_empty)
+            {
+                state.remove(&withdrawal.address);
+            }
+            continue;
+        }
+        let account = state.entry(withdrawal.address).or_default();
+        let amount = U256::from(withdrawal.amount) * U256::from(GWEI);
+        account.balance = account.balance.saturating_add(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::SigningKey;
+
+    use crate::{
+        domain::{
+            env::TransactTo,
+            state::Log,
+            transaction::{Signature, SignedTransaction, Transaction, TxEip1559, TxLegacy},
+        },
+        interpreter::opcodes,
+    };
+
+    use super::*;
+
+    const SENDER: &str = "9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f";
+    const COINBASE: Address = Address([0xcb; 20]);
+    const LOGGER: Address = Address([0x10; 20]);
+
+    fn sign(transaction: Transaction) -> SignedTransaction {
+        let key = SigningKey::from_slice(&[0x46; 32]).unwrap();
+        let (signature, recid) = key
+            .sign_prehash_recoverable(&transaction.signature_hash())
+            .unwrap();
+        let signature = Signature {
+            r: U256::from_be_slice(&signature.r().to_bytes()),
+            s: U256::from_be_slice(&signature.s().to_bytes()),
+            odd_y_parity: recid.is_y_odd(),
+        };
+        SignedTransaction::new(transaction, signature)
+    }
+
+    fn build_state() -> State {
+        let mut state = State::new();
+        state.insert(
+            SENDER.parse().unwrap(),
+            Account::with_balance(U256::from(ETHER)),
+        );
+        // LOG1(0, 0, 0xaa)
+        let code = [
+            opcodes::PUSH1,
+            0xaa,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::LOG1,
+        ];
+        state.insert(LOGGER, Account::with_code(Bytes::copy_from_slice(&code)));
+        state
+    }
+
+    fn block(spec: SpecId, transactions: Vec<SignedTransaction>) -> Block {
+        Block {
+            header: Header {
+                number: 10,
+                beneficiary: COINBASE,
+                gas_limit: 100_000,
+                base_fee_per_gas: spec.is_enabled_in(SpecId::London).then_some(U256::from(7)),
+                parent_beacon_block_root: spec.is_enabled_in(SpecId::Cancun).then_some([0; 32]),
+                ..Default::default()
+            },
+            transactions,
+            ommers: vec![],
+            withdrawals: None,
+        }
+    }
+
+    fn transactions() -> Vec<SignedTransaction> {
+        vec![
+            sign(Transaction::DynamicFee(TxEip1559 {
+                chain_id: 1,
+                nonce: 0,
+                max_fee_per_gas: U256::from(10),
+                max_priority_fee_per_gas: U256::from(1),
+                gas_limit: 50_000,
+                to: TransactTo::Call(LOGGER),
+                ..Default::default()
+            })),
+            sign(Transaction::Legacy(TxLegacy {
+                chain_id: Some(1),
+                nonce: 1,
+                gas_price: U256::from(10),
+                gas_limit: 21_000,
+                to: TransactTo::Call(Address::with_last_byte(0xaa)),
+                value: U256::from(1),
+                ..Default::default()
+            })),
+        ]
+    }
+
+    #[test]
+    fn receipts() {
+        let mut state = build_state();
+        let executor = BlockExecutor::new(CfgEnv::default());
+        let result = executor
+            .execute(&block(SpecId::Cancun, transactions()), &mut state)
+            .unwrap();
+
+        let [logged, transfer] = &result.receipts[..] else {
+            panic!("expected two receipts");
+        };
+        assert_eq!(logged.tx_type, 2);
+        assert!(logged.success && transfer.success);
+        assert_eq!(
+            logged.logs,
+            [Log {
+                address: LOGGER,
+                topics: vec![U256::from(0xaa)],
+                data: Bytes::new(),
+            }]
+        );
+        assert!(logged.logs_bloom.contains(LOGGER.as_slice()));
+        assert_eq!(transfer.logs_bloom, Bloom::default());
+        assert_eq!(
+            transfer.cumulative_gas_used,
+            logged.cumulative_gas_used + 21_000
+        );
+        assert_eq!(result.gas_used, transfer.cumulative_gas_used);
+        assert_eq!(result.logs_bloom, logged.logs_bloom);
+        assert_eq!(
+            result.receipts_root,
+            receipt::receipts_root(&result.receipts)
+        );
+        assert_eq!(result.state_root, trie::state_root(&state));
+        assert_eq!(state[&Address::with_last_byte(0xaa)].balance, U256::from(1));
+        // No reward after the merge, only the priority fees.
+        assert_eq!(
+            state[&COINBASE].balance,
+            U256::from(logged.cumulative_gas_used + 21_000 * 3)
+        );
+    }
+
+    #[test]
+    fn block_rewards() {
+        assert_eq!(block_reward(SpecId::Frontier), U256::from(5 * ETHER));
+        assert_eq!(block_reward(SpecId::Byzantium), U256::from(3 * ETHER));
+        assert_eq!(block_reward(SpecId::London), U256::from(2 * ETHER));
+        assert_eq!(block_reward(SpecId::Paris), U256::ZERO);
+
+        let cfg = CfgEnv {
+            spec: SpecId::Berlin,
+            ..Default::default()
+        };
+        let mut block = block(SpecId::Berlin, vec![]);
+        block.ommers.push(Header {
+            number: 9,
+            beneficiary: Address::with_last_byte(0xbb),
+            ..Default::default()
+        });
+        let mut state = State::new();
+        let result = BlockExecutor::new(cfg).execute(&block, &mut state).unwrap();
+        assert_eq!(
+            state[&COINBASE].balance,
+            U256::from(2 * ETHER + 2 * ETHER / 32)
+        );
+        assert_eq!(
+            state[&Address::with_last_byte(0xbb)].balance,
+            U256::from(2 * ETHER / 8 * 7)
+        );
+        assert_eq!(result.receipts_root, trie::EMPTY_ROOT);
+        assert_eq!(result.state_root, trie::state_root(&state));
+
+        // Ommers that are not older than the block, or too old, earn nothing.
+        let mut state = State::new();
+        let ommer = |number| Header {
+            number,
+            beneficiary: Address::with_last_byte(0xcc),
+            ..Default::default()
+        };
+        apply_block_reward(
+            SpecId::Berlin,
+            &block.header,
+            &[ommer(11), ommer(3)],
+            &mut state,
+        );
+        assert!(!state.contains_key(&Address::with_last_byte(0xcc)));
+        assert_eq!(state[&COINBASE].balance, U256::from(2 * ETHER));
+
+        let cfg = CfgEnv {
+            spec: SpecId::Paris,
+            ..Default::default()
+        };
+        assert_eq!(
+            BlockExecutor::new(cfg).execute(&block, &mut State::new()),
+            Err(BlockError::OmmersAfterMerge)
+        );
+    }
+
+    #[test]
+    fn pre_byzantium_receipts_hold_state_root() {
+        let cfg = CfgEnv {
+            spec: SpecId::Homestead,
+            ..Default::default()
+        };
+        let transactions = transactions().split_off(1);
+        let mut transaction = transactions[0].transaction.clone();
+        let Transaction::Legacy(tx) = &mut transaction else {
+            unreachable!()
+        };
+        tx.chain_id = None;
+        tx.nonce = 0;
+        let mut state = build_state();
+        let result = BlockExecutor::new(cfg)
+            .execute(
+                &block(SpecId::Homestead, vec![sign(transaction)]),
+                &mut state,
+            )
+            .unwrap();
+        let post_state = result.receipts[0].post_state.unwrap();
+        // The block reward is paid after the last receipt.
+        assert_ne!(post_state, result.state_root);
+        assert_eq!(result.state_root, trie::state_root(&state));
+    }
+
+    #[test]
+    fn import() {
+        let parent = Header {
+            number: 9,
+            timestamp: 12,
+            gas_limit: 100_000,
+            gas_used: 50_000,
+            base_fee_per_gas: Some(U256::from(7)),
+            withdrawals_root: Some(trie::EMPTY_ROOT),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            ..Default::default()
+        };
+        let mut block = block(SpecId::Cancun, transactions());
+        block.withdrawals = Some(vec![]);
+        let header = &mut block.header;
+        header.parent_hash = parent.hash();
+        header.timestamp = 24;
+        header.transactions_root = block::transactions_root(&block.transactions);
+        header.withdrawals_root = Some(trie::EMPTY_ROOT);
+        header.blob_gas_used = Some(0);
+        header.excess_blob_gas = Some(0);
+
+        // Commit to the outcome of a dry run.
+        let executor = BlockExecutor::new(CfgEnv::default());
+        let result = executor.execute(&block, &mut build_state()).unwrap();
+        let header = &mut block.header;
+        header.gas_used = result.gas_used;
+        header.receipts_root = result.receipts_root;
+        header.logs_bloom = result.logs_bloom;
+        header.state_root = result.state_root;
+        assert_eq!(
+            executor.import(&block, &parent, &mut build_state()),
+            Ok(result)
+        );
+
+        let import = |block: &Block| executor.import(block, &parent, &mut build_state());
+        let mut invalid = block.clone();
+        invalid.header.parent_hash = [0; 32];
+        assert_eq!(import(&invalid), Err(BlockError::ParentHashMismatch));
+
+        let mut invalid = block.clone();
+        invalid.header.base_fee_per_gas = Some(U256::from(8));
+        assert!(matches!(
+            import(&invalid),
+            Err(BlockError::Header(HeaderError::BaseFeeMismatch { .. }))
+        ));
+
+        let mut invalid = block.clone();
+        invalid.transactions.pop();
+        assert!(matches!(
+            import(&invalid),
+            Err(BlockError::Mismatch {
+                field: "transactions root",
+                ..
+            })
+        ));
+
+        let mut invalid = block.clone();
+        invalid.withdrawals = None;
+        assert_eq!(import(&invalid), Err(BlockError::WithdrawalsMismatch));
+
+        let mut invalid = block.clone();
+        invalid.ommers.push(Header {
+            number: 9,
+            ..Default::default()
+        });
+        invalid.header.ommers_hash = block::ommers_hash(&invalid.ommers);
+        assert_eq!(import(&invalid), Err(BlockError::OmmersAfterMerge));
+
+        let mut invalid = block.clone();
+        invalid.header.state_root = [0; 32];
+        assert_eq!(
+            import(&invalid).unwrap_err().to_string(),
+            format!(
+                "state root 0x{}, expected 0x{}",
+                hex::encode(block.header.state_root),
+                hex::encode([0; 32])
+            )
+        );
+
+        let mut invalid = block;
+        invalid.header.gas_used += 1;
+        assert!(matches!(
+            import(&invalid),
+            Err(BlockError::GasUsedMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn import_before_the_merge() {
+        let parent = Header {
+            number: 9,
+            timestamp: 12,
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+        let executor = BlockExecutor::new(CfgEnv {
+            spec: SpecId::Berlin,
+            ..Default::default()
+        });
+        let mut block = block(SpecId::Berlin, vec![]);
+        block.header.parent_hash = parent.hash();
+        block.header.timestamp = 24;
+        block.ommers.push(Header {
+            number: 8,
+            ..Default::default()
+        });
+        block.header.ommers_hash = block::ommers_hash(&block.ommers);
+        let result = executor.execute(&block, &mut State::new()).unwrap();
+        block.header.receipts_root = result.receipts_root;
+        block.header.state_root = result.state_root;
+        assert_eq!(
+            executor.import(&block, &parent, &mut State::new()),
+            Ok(result)
+        );
+
+        let import = |block: &Block| executor.import(block, &parent, &mut State::new());
+        // A ommer of the same height as the block.
+        let mut invalid = block.clone();
+        invalid.ommers[0].number = 10;
+        invalid.header.ommers_hash = block::ommers_hash(&invalid.ommers);
+        assert_eq!(
+            import(&invalid),
+            Err(BlockError::InvalidOmmerDepth { index: 0 })
+        );
+
+        let mut invalid = block.clone();
+        invalid.header.withdrawals_root = Some(trie::EMPTY_ROOT);
+        assert_eq!(import(&invalid), Err(BlockError::WithdrawalsBeforeShanghai));
+        invalid.withdrawals = Some(vec![]);
+        assert_eq!(import(&invalid), Err(BlockError::WithdrawalsBeforeShanghai));
+    }
+
+    #[test]
+    fn invalid_blocks() {
+        let executor = BlockExecutor::new(CfgEnv::default());
+        let mut block = block(SpecId::Cancun, transactions());
+        // The first transaction uses 21757 gas, leaving less than the 21000 of the second.
+        block.header.gas_limit = 21_757 + 20_999;
+        block.transactions[0] = sign(Transaction::DynamicFee(TxEip1559 {
+            gas_limit: 21_757,
+            ..match &block.transactions[0].transaction {
+                Transaction::DynamicFee(tx) => tx.clone(),
+                _ => unreachable!(),
+            }
+        }));
+        assert_eq!(
+            executor.execute(&block, &mut build_state()),
+            Err(BlockError::TransactionGasLimitMoreThanAvailableBlockGas {
+                index: 1,
+                gas_limit: 21_000,
+                available: 20_999,
+            })
+        );
+
+        let mut block = self::block(SpecId::Cancun, transactions());
+        block.transactions.swap(0, 1);
+        assert!(matches!(
+            executor.execute(&block, &mut build_state()),
+            Err(BlockError::InvalidTransaction {
+                index: 0,
+                error: InvalidTransaction::NonceTooHigh { tx: 1, state: 0 }
+            })
+        ));
+
+        let mut block = self::block(SpecId::Cancun, transactions());
+        block.transactions[1].signature.s = U256::ZERO;
+        assert!(matches!(
+            executor.execute(&block, &mut build_state()),
+            Err(BlockError::InvalidSignature { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn withdrawals() {
+        let cfg = CfgEnv {
+            spec: SpecId::Shanghai,
+            ..Default::default()
+        };
+        let empty = Address::with_last_byte(0xee);
+        let mut state = State::new();
+        state.insert(empty, Account::default());
+
+        let mut block = block(SpecId::Shanghai, vec![]);
+        let withdrawal = |address, amount| Withdrawal {
+            address,
+            amount,
+            ..Default::default()
+        };
+        block.withdrawals = Some(vec![
+            withdrawal(COINBASE, 5),
+            withdrawal(COINBASE, 1),
+            withdrawal(Address::with_last_byte(0xaa), 0),
+            withdrawal(empty, 0),
+        ]);
+        let result = BlockExecutor::new(cfg).execute(&block, &mut state).unwrap();
+        assert_eq!(state.len(), 1);
+        assert_eq!(state[&COINBASE].balance, U256::from(6 * GWEI));
+        assert_eq!(result.state_root, trie::state_root(&state));
+
+        // Ignored before Shanghai.
+        let cfg = CfgEnv {
+            spec: SpecId::Paris,
+            ..Default::default()
+        };
+        let mut state = State::new();
+        BlockExecutor::new(cfg).execute(&block, &mut state).unwrap();
+        assert!(state.is_empty());
+    }
+
+    #[test]
+    fn beacon_root() {
+        // The runtime code deployed by EIP-4788.
+        let code = hex::decode(
+            "3373fffffffffffffffffffffffffffffffffffffffe14604d57602036146024575f5ffd5b5f35801560495\
+             762001fff810690815414603c575f5ffd5b62001fff01545f5260205ff35b5f5ffd5b62001fff42064281\
+             555f359062001fff015500",
+        )
+        .unwrap();
+        let mut state = State::new();
+        state.insert(BEACON_ROOTS_ADDRESS, Account::with_code(code.into()));
+
+        let mut block = block(SpecId::Cancun, vec![]);
+        block.header.timestamp = 8191 + 12;
+        block.header.parent_beacon_block_root = Some([7; 32]);
+        let executor = BlockExecutor::new(CfgEnv::default());
+        let result = executor.execute(&block, &mut state).unwrap();
+
+        // The timestamp and root go in a ring buffer of 8191 slots each.
+        let storage = &state[&BEACON_ROOTS_ADDRESS].storage;
+        assert_eq!(storage[&U256::from(12)], U256::from(8191 + 12));
+        assert_eq!(
+            storage[&U256::from(8191 + 12)],
+            U256::from_be_bytes([7; 32])
+        );
+        // Nothing is paid for and the system address is not left behind.
+        assert_eq!(result.gas_used, 0);
+        assert_eq!(state.len(), 1);
+
+        // Without the contract the call does nothing.
+        let mut state = State::new();
+        executor.execute(&block, &mut state).unwrap();
+        assert!(state.is_empty());
+
+        block.header.parent_beacon_block_root = None;
+        assert_eq!(
+            executor.execute(&block, &mut state),
+            Err(BlockError::Header(
+                HeaderError::ParentBeaconBlockRootMissing
+            ))
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Runners for the JSON fixtures of the ethereum/tests repository.
+
+pub mod blockchain;
+pub mod eof;
+pub mod state;
+pub mod vm;
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use bytes::Bytes;
+use hashbrown::HashMap;
+use ruint::aliases::U256;
+use serde::{de, de::DeserializeOwned, Deserialize, Deserializer};
+
+use crate::domain::{
+    primitives::{self, keccak256, Address, B256},
+    state::{Account, State},
+};
+
+pub type Result<T> = std::result::Result<T, FixtureError>;
+
+#[derive(Debug)]
+pub enum FixtureError {
+    Io(io::Error),
+    Json(serde_json::Error),
+}
+
+impl From<io::Error> for FixtureError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for FixtureError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{error}"),
+            Self::Json(error) => write!(f, "invalid fixture: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for FixtureError {}
+
+/// The outcome of one test, or of one case of it, e.g. under one fork.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TestResult<E> {
+    pub name: String,
+    pub result: std::result::Result<(), E>,
+}
+
+impl<E> TestResult<E> {
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for TestResult<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.name)?;
+        match &self.result {
+            Ok(()) => write!(f, "ok"),
+            Err(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+/// Reads a fixture file, a JSON object of tests keyed by name.
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<BTreeMap<String, T>> {
+    let json = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// `path` itself if it is a file, otherwise every `.json` file below it, sorted.
+pub fn json_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(json_files(&path)?);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// BLOCKHASH in the fixtures is the keccak-256 of the block number in decimal.
+pub fn block_hashes(number: u64) -> HashMap<u64, B256> {
+    (number.saturating_sub(256)..number)
+        .map(|number| (number, keccak256(number.to_string())))
+        .collect()
+}
+
+/// An account of a pre or post state.
+#[derive(Clone, Debug, Deserialize)]
+pub struct AccountFixture {
+    pub balance: U256,
+    #[serde(deserialize_with = "deserialize_bytes")]
+    pub code: Bytes,
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub nonce: u64,
+    #[serde(default)]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+impl AccountFixture {
+    pub fn to_account(&self) -> Account {
+        let mut account = Account::with_code(self.code.clone());
+        account.balance = self.balance;
+        account.nonce = self.nonce;
+        account.storage = self
+            .storage
+            .iter()
+            .filter(|(_, value)| !value.is_zero())
+            .map(|(key, value)| (*key, *value))
+            .collect();
+        account
+    }
+}
+
+pub fn build_state(accounts: &BTreeMap<Address, AccountFixture>) -> State {
+    accounts
+        .iter()
+        .map(|(address, account)| (*address, account.to_account()))
+        .collect()
+}
+
+/// The first account of `state` or `post` that differs from the other or is missing from it.
+/// Zero slots in `state` are ignored, the fixtures leave them out.
+pub fn state_mismatch(state: &State, post: &BTreeMap<Address, AccountFixture>) -> Option<Address> {
+    let differs = |address: &Address, expected: &AccountFixture| {
+        let Some(account) = state.get(address) else {
+            return true;
+        };
+        let mut account = account.clone();
+        account.storage.retain(|_, value| !value.is_zero());
+        account != expected.to_account()
+    };
+    post.iter()
+        .find(|(address, expected)| differs(address, expected))
+        .map(|(address, _)| *address)
+        .or_else(|| {
+            state
+                .keys()
+                .find(|address| !post.contains_key(*address))
+                .copied()
+        })
+}
+
+fn parse_hex<E: de::Error>(s: &str) -> std::result::Result<Bytes, E> {
+    primitives::parse_hex(s).map_err(de::Error::custom)
+}
+
+/// A `0x` prefixed hex string.
+pub fn deserialize_bytes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Bytes, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    parse_hex(&s)
+}
+
+pub fn deserialize_opt_bytes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Bytes>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| parse_hex(&s))
+        .transpose()
+}
+
+pub fn deserialize_bytes_vec<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<Bytes>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|s| parse_hex(s))
+        .collect()
+}
+
+pub fn deserialize_b256<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<B256, D::Error> {
+    U256::deserialize(deserializer).map(|value| value.to_be_bytes())
+}
+
+pub fn deserialize_opt_b256<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<B256>, D::Error> {
+    Option::<U256>::deserialize(deserializer).map(|value| value.map(|value| value.to_be_bytes()))
+}
+
+/// A hex string or number, the fixtures use both.
+pub fn deserialize_u64<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<u64, D::Error> {
+    let value = U256::deserialize(deserializer)?;
+    value.try_into().map_err(de::Error::custom)
+}
+
+pub fn deserialize_opt_u64<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<u64>, D::Error> {
+    Option::<U256>::deserialize(deserializer)?
+        .map(|value| value.try_into().map_err(de::Error::custom))
+        .transpose()
+}
+
+/// The fixtures of `suite` vendored in `tests/fixtures`.
+#[cfg(test)]
+pub fn vendored(suite: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(suite)
+}
+
+#[cfg(test)]
+pub fn assert_all_ok<E: fmt::Display>(results: &[TestResult<E>]) {
+    assert!(!results.is_empty());
+    for result in results {
+        assert!(result.is_ok(), "{result}");
+    }
+}
+
+/// Runs `suite` of the ethereum/tests checkout in `ETHEREUM_TESTS` and prints the failures.
+/// Fails unless each of them is a known gap.
+#[cfg(test)]
+pub fn run_suite<E: fmt::Display>(
+    suite: &str,
+    run: impl FnOnce(&Path) -> Result<Vec<TestResult<E>>>,
+    is_known_gap: impl Fn(&E) -> bool,
+) {
+    let root = std::env::var("ETHEREUM_TESTS")
+        .expect("ETHEREUM_TESTS must point at a checkout of ethereum/tests");
+    let results = run(&Path::new(&root).join(suite)).unwrap();
+    let failures: Vec<_> = results.iter().filter(|result| !result.is_ok()).collect();
+    for failure in &failures {
+        println!("{failure}");
+    }
+    println!(
+        "{} of {} passed",
+        results.len() - failures.len(),
+        results.len()
+    );
+    let unexpected = failures
+        .iter()
+        .filter(|failure| !failure.result.as_ref().is_err_and(&is_known_gap))
+        .count();
+    assert_eq!(unexpected, 0, "{unexpected} unexpected failures");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accounts() {
+        let json = r#"{
+            "0x1000000000000000000000000000000000000000": {
+                "balance": "0x0de0b6b3a7640000",
+                "code": "0x6001",
+                "nonce": "0x01",
+                "storage": { "0x01": "0x02", "0x02": "0x00" }
+            }
+        }"#;
+        let accounts: BTreeMap<Address, AccountFixture> = serde_json::from_str(json).unwrap();
+        let state = build_state(&accounts);
+        let address: Address = "0x1000000000000000000000000000000000000000"
+            .parse()
+            .unwrap();
+        let account = &state[&address];
+        assert_eq!(account.balance, U256::from(1_000_000_000_000_000_000u64));
+        assert_eq!(account.code[..], [0x60, 0x01]);
+        assert_eq!(account.nonce, 1);
+        // Zero slots are left out, as they would be in the trie.
+        assert_eq!(account.storage.len(), 1);
+        assert_eq!(account.storage[&U256::from(1)], U256::from(2));
+
+        let error = serde_json::from_str::<AccountFixture>(
+            r#"{ "balance": "0x0", "code": "0x6", "nonce": "0x0" }"#,
+        );
+        assert!(error.is_err());
+    }
+}
@@ -0,0 +1,334 @@
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use bytes::Bytes;
+use serde::Deserialize;
+
+use crate::{
+    domain::{
+        block::{Block, Header},
+        env::CfgEnv,
+        primitives::{Address, B256},
+        rlp::{self, RlpError},
+        spec::SpecId,
+        state::State,
+        trie,
+    },
+    executor::block::{BlockError, BlockExecutor},
+};
+
+use super::{
+    build_state, deserialize_b256, deserialize_bytes, deserialize_opt_b256, AccountFixture,
+    TestResult,
+};
+
+/// A test of the `BlockchainTests` format: blocks imported one by one on top of the genesis
+/// block, the invalid ones expected to be rejected.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockchainTest {
+    pub blocks: Vec<BlockFixture>,
+    #[serde(rename = "genesisRLP", deserialize_with = "deserialize_bytes")]
+    pub genesis_rlp: Bytes,
+    /// The hash of the head of the chain after the import.
+    #[serde(deserialize_with = "deserialize_b256")]
+    pub lastblockhash: B256,
+    pub network: String,
+    pub pre: BTreeMap<Address, AccountFixture>,
+    /// Either the post state or, for large ones, only its root.
+    pub post_state: Option<BTreeMap<Address, AccountFixture>>,
+    #[serde(default, deserialize_with = "deserialize_opt_b256")]
+    pub post_state_hash: Option<B256>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockFixture {
+    #[serde(deserialize_with = "deserialize_bytes")]
+    pub rlp: Bytes,
+    /// Set if the block is invalid, the chain then stays as it was.
+    pub expect_exception: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BlockchainTestError {
+    InvalidGenesis(RlpError),
+    GenesisStateRootMismatch {
+        expected: B256,
+        got: B256,
+    },
+    /// `index` is that of the block in the fixture.
+    InvalidRlp {
+        index: usize,
+        error: RlpError,
+    },
+    BlockRejected {
+        index: usize,
+        error: BlockError,
+    },
+    /// The block was expected to be rejected.
+    MissingException {
+        index: usize,
+        exception: String,
+    },
+    LastBlockHashMismatch {
+        expected: B256,
+        got: B256,
+    },
+    StateRootMismatch {
+        expected: B256,
+        got: B256,
+    },
+    AccountMismatch(Address),
+}
+
+impl fmt::Display for BlockchainTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidGenesis(error) => write!(f, "invalid genesis rlp: {error}"),
+            Self::GenesisStateRootMismatch { expected, got } => write!(
+                f,
+                "genesis state root 0x{}, expected 0x{}",
+                hex::encode(got),
+                hex::encode(expected)
+            ),
+            Self::InvalidRlp { index, error } => write!(f, "block {index}: invalid rlp: {error}"),
+            Self::BlockRejected { index, error } => write!(f, "block {index}: {error}"),
+            Self::MissingException { index, exception } => {
+                write!(f, "block {index}: expected exception {exception}")
+            }
+            Self::LastBlockHashMismatch { expected, got } => write!(
+                f,
+                "last block hash 0x{}, expected 0x{}",
+                hex::encode(got),
+                hex::encode(expected)
+            ),
+            Self::StateRootMismatch { expected, got } => write!(
+                f,
+                "state root 0x{}, expected 0x{}",
+                hex::encode(got),
+                hex::encode(expected)
+            ),
+            Self::AccountMismatch(address) => write!(f, "account {address:?} differs"),
+        }
+    }
+}
+
+impl std::error::Error for BlockchainTestError {}
+
+/// Decodes and imports the block at `index` on top of `parent`, returning it and the state
+/// after it.
+fn import(
+    executor: &BlockExecutor,
+    index: usize,
+    raw: &[u8],
+    parent: &Header,
+    state: &State,
+) -> Result<(Block, State), BlockchainTestError> {
+    let block = rlp::decode_exact::<Block>(raw)
+        .map_err(|error| BlockchainTestError::InvalidRlp { index, error })?;
+    let mut state = state.clone();
+    executor
+        .import(&block, parent, &mut state)
+        .map_err(|error| BlockchainTestError::BlockRejected { index, error })?;
+    Ok((block, state))
+}
+
+impl BlockchainTest {
+    /// `None` if the network is unknown to this crate, e.g. one that changes fork mid chain, or
+    /// if the test has side chains.
+    pub fn run(&self, name: &str) -> Option<TestResult<BlockchainTestError>> {
+        let spec = self.network.parse().ok()?;
+        if self.has_side_chains() {
+            return None;
+        }
+        Some(TestResult {
+            name: format!("{name} {}", self.network),
+            result: self.run_chain(spec),
+        })
+    }
+
+    /// Whether a valid block is built on another block than the valid one before it, as in the
+    /// fork choice tests. Blocks are only imported onto the head here, there are no reorgs.
+    pub fn has_side_chains(&self) -> bool {
+        let Ok(genesis) = rlp::decode_exact::<Block>(&self.genesis_rlp) else {
+            return false;
+        };
+        let mut head = genesis.hash();
+        for fixture in &self.blocks {
+            if fixture.expect_exception.is_some() {
+                continue;
+            }
+            let Ok(block) = rlp::decode_exact::<Block>(&fixture.rlp) else {
+                return false;
+            };
+            if block.header.parent_hash != head {
+                return true;
+            }
+            head = block.hash();
+        }
+        false
+    }
+
+    pub fn run_chain(&self, spec: SpecId) -> Result<(), BlockchainTestError> {
+        let genesis = rlp::decode_exact::<Block>(&self.genesis_rlp)
+            .map_err(BlockchainTestError::InvalidGenesis)?;
+        let mut state = build_state(&self.pre);
+        let state_root = trie::state_root(&state);
+        if state_root != genesis.header.state_root {
+            return Err(BlockchainTestError::GenesisStateRootMismatch {
+                expected: genesis.header.state_root,
+                got: state_root,
+            });
+        }
+
+        let mut executor = BlockExecutor::new(CfgEnv { chain_id: 1, spec });
+        let mut head = genesis.header;
+        executor
+            .executor
+            .block_hashes
+            .insert(head.number, head.hash());
+        for (index, fixture) in self.blocks.iter().enumerate() {
+            match (
+                import(&executor, index, &fixture.rlp, &head, &state),
+                &fixture.expect_exception,
+            ) {
+                (Ok(_), Some(exception)) => {
+                    return Err(BlockchainTestError::MissingException {
+                        index,
+                        exception: exception.clone(),
+                    })
+                }
+                (Ok((block, next_state)), None) => {
+                    executor
+                        .executor
+                        .block_hashes
+                        .insert(block.header.number, block.hash());
+                    head = block.header;
+                    state = next_state;
+                }
+                (Err(error), None) => return Err(error),
+                (Err(_), Some(_)) => {}
+            }
+        }
+
+        if head.hash() != self.lastblockhash {
+            return Err(BlockchainTestError::LastBlockHashMismatch {
+                expected: self.lastblockhash,
+                got: head.hash(),
+            });
+        }
+        if let Some(post) = &self.post_state {
+            if let Some(address) = super::state_mismatch(&state, post) {
+                return Err(BlockchainTestError::AccountMismatch(address));
+            }
+        }
+        if let Some(expected) = self.post_state_hash {
+            let got = trie::state_root(&state);
+            if got != expected {
+                return Err(BlockchainTestError::StateRootMismatch { expected, got });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs every blockchain test in the file or directory at `path`, tests of unknown networks are
+/// skipped.
+pub fn run_path(path: &Path) -> super::Result<Vec<TestResult<BlockchainTestError>>> {
+    let mut results = Vec::new();
+    for file in super::json_files(path)? {
+        for (name, test) in super::load::<BlockchainTest>(&file)? {
+            results.extend(test.run(&name));
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        domain::block::HeaderError,
+        fixtures::{assert_all_ok, run_suite, vendored},
+    };
+
+    use super::*;
+
+    fn add11_chain() -> BlockchainTest {
+        let file = vendored("BlockchainTests").join("bcExample/add11Chain.json");
+        let mut tests = super::super::load::<BlockchainTest>(&file).unwrap();
+        tests.remove("add11Chain").unwrap()
+    }
+
+    #[test]
+    fn vendored_fixtures() {
+        assert_all_ok(&run_path(&vendored("BlockchainTests")).unwrap());
+    }
+
+    #[test]
+    fn reports_mismatches() {
+        let mut test = add11_chain();
+        assert!(!test.has_side_chains());
+        test.blocks[1].expect_exception = None;
+        assert!(test.has_side_chains());
+        assert_eq!(test.run("add11Chain"), None);
+        assert_eq!(
+            test.run_chain(SpecId::Shanghai),
+            Err(BlockchainTestError::BlockRejected {
+                index: 1,
+                error: BlockError::ParentHashMismatch
+            })
+        );
+
+        let mut test = add11_chain();
+        test.blocks[0].expect_exception = Some("BlockException.INVALID_STATE_ROOT".to_string());
+        assert!(matches!(
+            test.run_chain(SpecId::Shanghai),
+            Err(BlockchainTestError::MissingException { index: 0, .. })
+        ));
+
+        let mut test = add11_chain();
+        test.blocks[0].rlp = test.blocks[0].rlp.slice(1..);
+        let result = test.run_chain(SpecId::Shanghai);
+        assert!(matches!(
+            result,
+            Err(BlockchainTestError::InvalidRlp { index: 0, .. })
+        ));
+
+        // Shanghai headers lack the blob gas fields of Cancun.
+        let test = add11_chain();
+        assert!(matches!(
+            test.run_chain(SpecId::Cancun),
+            Err(BlockchainTestError::BlockRejected {
+                index: 0,
+                error: BlockError::Header(HeaderError::BlobGasMissing)
+            })
+        ));
+
+        let mut test = add11_chain();
+        test.post_state = None;
+        test.post_state_hash = Some([0; 32]);
+        assert!(matches!(
+            test.run_chain(SpecId::Shanghai),
+            Err(BlockchainTestError::StateRootMismatch { .. })
+        ));
+
+        let mut test = add11_chain();
+        test.lastblockhash = Header::default().hash();
+        assert!(matches!(
+            test.run_chain(SpecId::Shanghai),
+            Err(BlockchainTestError::LastBlockHashMismatch { .. })
+        ));
+
+        let mut test = add11_chain();
+        test.network = "ShanghaiToCancunAtTime15k".to_string();
+        assert_eq!(test.run("add11Chain"), None);
+    }
+
+    /// Runs the suite of a ethereum/tests checkout, e.g.
+    /// `ETHEREUM_TESTS=../tests cargo test -- --ignored blockchain_tests`.
+    #[test]
+    #[ignore]
+    fn blockchain_tests() {
+        run_suite("BlockchainTests", run_path, |_| false);
+    }
+}
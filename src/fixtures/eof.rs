@@ -0,0 +1,205 @@
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use bytes::Bytes;
+use serde::Deserialize;
+
+use crate::domain::eof::{EofContainer, EofError};
+
+use super::{deserialize_bytes, TestResult};
+
+/// A test of the `EOFTests` format: containers and whether each fork accepts them. Only the
+/// container format is validated, so vectors that are invalid because of their code fail.
+/// Initcode vectors are skipped, they only differ from runtime ones in the rules of their code.
+#[derive(Clone, Debug, Deserialize)]
+pub struct EofTest {
+    pub vectors: BTreeMap<String, EofVector>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EofVector {
+    #[serde(deserialize_with = "deserialize_bytes")]
+    pub code: Bytes,
+    /// Runtime if missing.
+    #[serde(default)]
+    pub container_kind: ContainerKind,
+    /// The expected outcomes, keyed by fork name.
+    pub results: BTreeMap<String, EofExpectation>,
+}
+
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ContainerKind {
+    /// Run by a creation, ends in RETURNCONTRACT.
+    Initcode,
+    /// Deployed code, ends in STOP, RETURN or REVERT.
+    #[default]
+    Runtime,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct EofExpectation {
+    pub result: bool,
+    pub exception: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EofTestError {
+    /// The container was expected to be rejected with the exception.
+    MissingException(String),
+    UnexpectedException(EofError),
+}
+
+impl fmt::Display for EofTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingException(exception) => write!(f, "expected exception {exception}"),
+            Self::UnexpectedException(error) => write!(f, "unexpected exception: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for EofTestError {}
+
+impl EofVector {
+    pub fn check(&self, expected: &EofExpectation) -> Result<(), EofTestError> {
+        match EofContainer::decode(&self.code) {
+            Ok(_) if !expected.result => Err(EofTestError::MissingException(
+                expected.exception.clone().unwrap_or_default(),
+            )),
+            Err(error) if expected.result => Err(EofTestError::UnexpectedException(error)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl EofTest {
+    /// Checks every runtime vector against the expectation of every fork. None of the forks
+    /// this crate knows has EOF, so the container is validated the same way for all of them.
+    pub fn run(&self, name: &str) -> Vec<TestResult<EofTestError>> {
+        let mut results = Vec::new();
+        for (vector_name, vector) in &self.vectors {
+            if vector.container_kind != ContainerKind::Runtime {
+                continue;
+            }
+            for (fork, expected) in &vector.results {
+                results.push(TestResult {
+                    name: format!("{name} {vector_name} {fork}"),
+                    result: vector.check(expected),
+                });
+            }
+        }
+        results
+    }
+}
+
+/// Runs every EOF test in the file or directory at `path`.
+pub fn run_path(path: &Path) -> super::Result<Vec<TestResult<EofTestError>>> {
+    let mut results = Vec::new();
+    for file in super::json_files(path)? {
+        for (name, test) in super::load::<EofTest>(&file)? {
+            results.extend(test.run(&name));
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{assert_all_ok, run_suite, vendored};
+
+    use super::*;
+
+    #[test]
+    fn vendored_fixtures() {
+        assert_all_ok(&run_path(&vendored("EOFTests")).unwrap());
+    }
+
+    #[test]
+    fn skips_initcode() {
+        let file = vendored("EOFTests").join("EIP3540/container_format.json");
+        let mut tests = super::super::load::<EofTest>(&file).unwrap();
+        let test = tests.get_mut("container_format").unwrap();
+        assert_eq!(
+            test.vectors["truncated_body"].container_kind,
+            ContainerKind::Runtime
+        );
+        assert_eq!(
+            test.vectors["minimal"].container_kind,
+            ContainerKind::Runtime
+        );
+        let vector = test.vectors.get_mut("truncated_body").unwrap();
+        vector.container_kind = ContainerKind::Initcode;
+        let results = test.run("container_format");
+        assert_eq!(results.len(), 5);
+        assert!(results
+            .iter()
+            .all(|result| !result.name.contains("truncated_body")));
+
+        let error = serde_json::from_str::<EofVector>(
+            r#"{ "code": "0xef00", "containerKind": "DEPLOYED", "results": {} }"#,
+        );
+        assert!(error.is_err());
+    }
+
+    #[test]
+    fn reports_mismatches() {
+        let file = vendored("EOFTests").join("EIP3540/container_format.json");
+        let mut tests = super::super::load::<EofTest>(&file).unwrap();
+        let test = tests.get_mut("container_format").unwrap();
+        for vector in test.vectors.values_mut() {
+            for expected in vector.results.values_mut() {
+                expected.result = !expected.result;
+                expected.exception = Some("EOF_Exception".to_string());
+            }
+        }
+        let results = test.run("container_format");
+        assert!(results.iter().all(|result| !result.is_ok()));
+        assert_eq!(
+            results[0].result,
+            Err(EofTestError::MissingException("EOF_Exception".to_string()))
+        );
+        assert_eq!(
+            results[0].to_string(),
+            "container_format minimal Prague: expected exception EOF_Exception"
+        );
+        assert_eq!(
+            results[1].result,
+            Err(EofTestError::UnexpectedException(EofError::InvalidMagic))
+        );
+    }
+
+    /// The exceptions of invalid code rather than a invalid container. The code sections are not
+    /// validated, so vectors expecting these are accepted.
+    const CODE_EXCEPTIONS: &[&str] = &[
+        "EOF_UndefinedInstruction",
+        "EOF_TruncatedImmediate",
+        "EOF_InvalidJumpDestination",
+        "EOF_InvalidCodeSectionIndex",
+        "EOF_InvalidContainerSectionIndex",
+        "EOF_InvalidDataloadnIndex",
+        "EOF_InvalidCodeTermination",
+        "EOF_InvalidNonReturningFlag",
+        "EOF_InvalidMaxStackHeight",
+        "EOF_ConflictingStackHeight",
+        "EOF_StackUnderflow",
+        "EOF_StackOverflow",
+        "EOF_UnreachableCode",
+        "EOF_UnreachableCodeSections",
+        "EOF_JumpfDestinationIncompatibleOutputs",
+        "EOF_CallfToNonReturningFunction",
+        "EOF_IncompatibleContainerKind",
+        "EOF_OrphanSubContainer",
+    ];
+
+    /// Runs the suite of a ethereum/tests checkout, e.g.
+    /// `ETHEREUM_TESTS=../tests cargo test -- --ignored eof_tests`.
+    #[test]
+    #[ignore]
+    fn eof_tests() {
+        run_suite("EOFTests", run_path, |error| {
+            matches!(error, EofTestError::MissingException(exception)
+                if CODE_EXCEPTIONS.contains(&exception.as_str()))
+        });
+    }
+}
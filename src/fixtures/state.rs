@@ -0,0 +1,377 @@
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use bytes::Bytes;
+use k256::ecdsa::SigningKey;
+use ruint::aliases::U256;
+use serde::{Deserialize, Deserializer};
+
+use crate::{
+    domain::{
+        env::{BlockEnv, CfgEnv, TransactTo, TxEnv},
+        primitives::{keccak256, Address, B256},
+        rlp,
+        spec::SpecId,
+        state::AccessListItem,
+        trie,
+    },
+    executor::{ExecutionResult, Executor, InvalidTransaction},
+};
+
+use super::{
+    block_hashes, build_state, deserialize_b256, deserialize_bytes, deserialize_bytes_vec,
+    deserialize_opt_b256, deserialize_opt_u64, deserialize_u64, AccountFixture, TestResult,
+};
+
+/// A test of the `GeneralStateTests` format: one transaction, with several choices of data, gas
+/// limit and value, executed on top of `pre` under each fork in `post`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct StateTest {
+    pub env: StateEnv,
+    pub pre: BTreeMap<Address, AccountFixture>,
+    pub transaction: TransactionFixture,
+    /// The expected outcomes, keyed by fork name.
+    pub post: BTreeMap<String, Vec<PostState>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateEnv {
+    pub current_coinbase: Address,
+    #[serde(default)]
+    pub current_difficulty: U256,
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub current_gas_limit: u64,
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub current_number: u64,
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub current_timestamp: u64,
+    pub current_base_fee: Option<U256>,
+    #[serde(default, deserialize_with = "deserialize_opt_b256")]
+    pub current_random: Option<B256>,
+    #[serde(default, deserialize_with = "deserialize_opt_u64")]
+    pub current_excess_blob_gas: Option<u64>,
+}
+
+impl StateEnv {
+    pub fn block_env(&self) -> BlockEnv {
+        BlockEnv {
+            number: self.current_number,
+            coinbase: self.current_coinbase,
+            timestamp: self.current_timestamp,
+            gas_limit: self.current_gas_limit,
+            basefee: self.current_base_fee.unwrap_or_default(),
+            difficulty: self.current_difficulty,
+            prevrandao: self.current_random,
+            excess_blob_gas: self.current_excess_blob_gas,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListItemFixture {
+    pub address: Address,
+    pub storage_keys: Vec<U256>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionFixture {
+    #[serde(deserialize_with = "deserialize_bytes_vec")]
+    pub data: Vec<Bytes>,
+    pub gas_limit: Vec<U256>,
+    pub value: Vec<U256>,
+    /// One access list per entry of `data`, only for EIP-2930 and later transactions.
+    #[serde(default)]
+    pub access_lists: Option<Vec<Option<Vec<AccessListItemFixture>>>>,
+    pub gas_price: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub nonce: u64,
+    #[serde(deserialize_with = "deserialize_b256")]
+    pub secret_key: B256,
+    pub sender: Option<Address>,
+    /// Empty for contract creations.
+    #[serde(deserialize_with = "deserialize_to")]
+    pub to: TransactTo,
+    pub max_fee_per_blob_gas: Option<U256>,
+    #[serde(default)]
+    pub blob_versioned_hashes: Option<Vec<U256>>,
+}
+
+fn deserialize_to<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TransactTo, D::Error> {
+    let to = String::deserialize(deserializer)?;
+    if to.is_empty() {
+        return Ok(TransactTo::Create);
+    }
+    to.parse()
+        .map(TransactTo::Call)
+        .map_err(serde::de::Error::custom)
+}
+
+impl TransactionFixture {
+    /// The transaction picked out by `indexes`. `None` if the sender can not be derived.
+    pub fn tx_env(&self, indexes: &Indexes) -> Option<TxEnv> {
+        let caller = match self.sender {
+            Some(sender) => sender,
+            None => secret_key_address(&self.secret_key)?,
+        };
+        let access_list = self
+            .access_lists
+            .as_ref()
+            .and_then(|access_lists| access_lists.get(indexes.data)?.as_ref())
+            .map(|access_list| {
+                access_list
+                    .iter()
+                    .map(|item| AccessListItem {
+                        address: item.address,
+                        storage_keys: item.storage_keys.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(TxEnv {
+            caller,
+            gas_limit: (*self.gas_limit.get(indexes.gas)?)
+                .try_into()
+                .unwrap_or(u64::MAX),
+            gas_price: self.gas_price.or(self.max_fee_per_gas).unwrap_or_default(),
+            gas_priority_fee: self.max_priority_fee_per_gas,
+            transact_to: self.to,
+            value: *self.value.get(indexes.value)?,
+            data: self.data.get(indexes.data)?.clone(),
+            nonce: Some(self.nonce),
+            chain_id: None,
+            access_list,
+            blob_hashes: self
+                .blob_versioned_hashes
+                .iter()
+                .flatten()
+                .map(|hash| hash.to_be_bytes())
+                .collect(),
+            max_fee_per_blob_gas: self.max_fee_per_blob_gas,
+        })
+    }
+}
+
+/// The address of the public key of `secret_key`.
+pub fn secret_key_address(secret_key: &B256) -> Option<Address> {
+    let key = SigningKey::from_slice(secret_key).ok()?;
+    let point = key.verifying_key().to_encoded_point(false);
+    Some(Address::from_slice(
+        &keccak256(&point.as_bytes()[1..])[12..],
+    ))
+}
+
+#[derive(Copy, Clone, Debug, Default, Deserialize)]
+pub struct Indexes {
+    pub data: usize,
+    pub gas: usize,
+    pub value: usize,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostState {
+    /// The state root after the transaction.
+    #[serde(deserialize_with = "deserialize_b256")]
+    pub hash: B256,
+    /// keccak-256 of the RLP list of the logs.
+    #[serde(deserialize_with = "deserialize_b256")]
+    pub logs: B256,
+    pub indexes: Indexes,
+    #[serde(default, deserialize_with = "deserialize_bytes")]
+    pub txbytes: Bytes,
+    /// Set if the transaction is invalid, the state then stays as it was.
+    pub expect_exception: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StateTestError {
+    /// The indexes point past the transaction fields, or the secret key is invalid.
+    InvalidTransactionFixture,
+    UnexpectedException(InvalidTransaction),
+    /// The transaction was expected to be rejected.
+    MissingException(String),
+    StateRootMismatch {
+        expected: B256,
+        got: B256,
+    },
+    LogsHashMismatch {
+        expected: B256,
+        got: B256,
+    },
+}
+
+impl fmt::Display for StateTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidTransactionFixture => write!(f, "invalid transaction fixture"),
+            Self::UnexpectedException(error) => write!(f, "unexpected exception {error:?}"),
+            Self::MissingException(exception) => write!(f, "expected exception {exception}"),
+            Self::StateRootMismatch { expected, got } => write!(
+                f,
+                "state root 0x{}, expected 0x{}",
+                hex::encode(got),
+                hex::encode(expected)
+            ),
+            Self::LogsHashMismatch { expected, got } => write!(
+                f,
+                "logs hash 0x{}, expected 0x{}",
+                hex::encode(got),
+                hex::encode(expected)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StateTestError {}
+
+/// keccak-256 of the RLP list of `logs`.
+pub fn logs_hash(result: &ExecutionResult) -> B256 {
+    let logs = match result {
+        ExecutionResult::Success { logs, .. } => &logs[..],
+        _ => &[],
+    };
+    keccak256(rlp::encode(logs))
+}
+
+impl StateTest {
+    /// Runs every expected outcome of every fork this crate knows, the other forks are skipped.
+    /// The results are named after the fork and the position of the outcome in its list.
+    pub fn run(&self, name: &str) -> Vec<TestResult<StateTestError>> {
+        let mut results = Vec::new();
+        for (fork, posts) in &self.post {
+            let Ok(spec) = fork.parse::<SpecId>() else {
+                continue;
+            };
+            for (index, post) in posts.iter().enumerate() {
+                results.push(TestResult {
+                    name: format!("{name} {fork}[{index}]"),
+                    result: self.run_case(spec, post),
+                });
+            }
+        }
+        results
+    }
+
+    pub fn run_case(&self, spec: SpecId, post: &PostState) -> Result<(), StateTestError> {
+        let tx = self
+            .transaction
+            .tx_env(&post.indexes)
+            .ok_or(StateTestError::InvalidTransactionFixture)?;
+        let mut executor = Executor::new(CfgEnv { chain_id: 1, spec });
+        executor.block_hashes = block_hashes(self.env.current_number);
+
+        let mut state = build_state(&self.pre);
+        let logs = match executor.transact(&tx, &self.env.block_env(), &mut state) {
+            Ok(_) if post.expect_exception.is_some() => {
+                return Err(StateTestError::MissingException(
+                    post.expect_exception.clone().unwrap_or_default(),
+                ))
+            }
+            Ok(result) => logs_hash(&result),
+            Err(error) if post.expect_exception.is_none() => {
+                return Err(StateTestError::UnexpectedException(error))
+            }
+            Err(_) => keccak256([rlp::EMPTY_LIST]),
+        };
+
+        let state_root = trie::state_root(&state);
+        if state_root != post.hash {
+            return Err(StateTestError::StateRootMismatch {
+                expected: post.hash,
+                got: state_root,
+            });
+        }
+        if logs != post.logs {
+            return Err(StateTestError::LogsHashMismatch {
+                expected: post.logs,
+                got: logs,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Runs every state test in the file or directory at `path`.
+pub fn run_path(path: &Path) -> super::Result<Vec<TestResult<StateTestError>>> {
+    let mut results = Vec::new();
+    for file in super::json_files(path)? {
+        for (name, test) in super::load::<StateTest>(&file)? {
+            results.extend(test.run(&name));
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{assert_all_ok, run_suite, vendored};
+
+    use super::*;
+
+    #[test]
+    fn secret_key() {
+        // The key most fixtures send from.
+        let secret_key = U256::from_str_radix(
+            "45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8",
+            16,
+        )
+        .unwrap();
+        assert_eq!(
+            secret_key_address(&secret_key.to_be_bytes()),
+            "0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b".parse().ok()
+        );
+        assert_eq!(secret_key_address(&[0; 32]), None);
+    }
+
+    #[test]
+    fn vendored_fixtures() {
+        assert_all_ok(&run_path(&vendored("GeneralStateTests")).unwrap());
+    }
+
+    #[test]
+    fn reports_mismatches() {
+        let file = vendored("GeneralStateTests").join("stExample/add11.json");
+        let mut tests = super::super::load::<StateTest>(&file).unwrap();
+        let test = tests.get_mut("add11").unwrap();
+        let post = &mut test.post.get_mut("Cancun").unwrap()[0];
+        post.hash = [0; 32];
+        let results = test.run("add11");
+        let result = results
+            .iter()
+            .find(|result| result.name == "add11 Cancun[0]")
+            .unwrap();
+        assert!(matches!(
+            result.result,
+            Err(StateTestError::StateRootMismatch { .. })
+        ));
+        assert!(result
+            .to_string()
+            .starts_with("add11 Cancun[0]: state root 0x"));
+
+        let post = &mut test.post.get_mut("Cancun").unwrap()[0];
+        post.expect_exception = Some("TransactionException.INTRINSIC_GAS_TOO_LOW".to_string());
+        let results = test.run("add11");
+        let result = results
+            .iter()
+            .find(|result| result.name == "add11 Cancun[0]")
+            .unwrap();
+        assert_eq!(
+            result.result,
+            Err(StateTestError::MissingException(
+                "TransactionException.INTRINSIC_GAS_TOO_LOW".to_string()
+            ))
+        );
+    }
+
+    /// Runs the suite of a ethereum/tests checkout, e.g.
+    /// `ETHEREUM_TESTS=../tests cargo test -- --ignored general_state_tests`.
+    #[test]
+    #[ignore]
+    fn general_state_tests() {
+        run_suite("GeneralStateTests", run_path, |_| false);
+    }
+}
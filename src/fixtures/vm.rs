@@ -0,0 +1,246 @@
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use bytes::Bytes;
+use ruint::aliases::U256;
+use serde::Deserialize;
+
+use crate::{
+    domain::{
+        env::{CfgEnv, Env, TxEnv},
+        primitives::{keccak256, Address, B256},
+        rlp,
+        spec::SpecId,
+    },
+    evm::Evm,
+    interpreter::{gas::Gas, InstructionResult, Interpreter},
+};
+
+use super::{
+    block_hashes, build_state, deserialize_bytes, deserialize_opt_b256, deserialize_opt_bytes,
+    deserialize_opt_u64, deserialize_u64, state::StateEnv, AccountFixture, TestResult,
+};
+
+/// A test of the `VMTests` format: bytecode run by a single interpreter on top of `pre`. Calls and
+/// creations are executed by the host rather than only recorded as in the original fillers.
+#[derive(Clone, Debug, Deserialize)]
+pub struct VmTest {
+    pub env: StateEnv,
+    pub exec: ExecFixture,
+    pub pre: BTreeMap<Address, AccountFixture>,
+    /// Missing if the code is expected to halt exceptionally.
+    pub post: Option<BTreeMap<Address, AccountFixture>>,
+    /// The gas left after execution.
+    #[serde(default, deserialize_with = "deserialize_opt_u64")]
+    pub gas: Option<u64>,
+    /// keccak-256 of the RLP list of the logs.
+    #[serde(default, deserialize_with = "deserialize_opt_b256")]
+    pub logs: Option<B256>,
+    #[serde(default, deserialize_with = "deserialize_opt_bytes")]
+    pub out: Option<Bytes>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecFixture {
+    pub address: Address,
+    pub caller: Address,
+    #[serde(deserialize_with = "deserialize_bytes")]
+    pub code: Bytes,
+    #[serde(deserialize_with = "deserialize_bytes")]
+    pub data: Bytes,
+    #[serde(deserialize_with = "deserialize_u64")]
+    pub gas: u64,
+    pub gas_price: U256,
+    pub origin: Address,
+    pub value: U256,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VmTestError {
+    /// The code was expected to halt exceptionally.
+    MissingException(InstructionResult),
+    UnexpectedException(InstructionResult),
+    GasMismatch {
+        expected: u64,
+        got: u64,
+    },
+    OutputMismatch {
+        expected: Bytes,
+        got: Bytes,
+    },
+    LogsHashMismatch {
+        expected: B256,
+        got: B256,
+    },
+    /// The account differs from the post state, or is missing from either side.
+    AccountMismatch(Address),
+}
+
+impl fmt::Display for VmTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingException(result) => write!(f, "expected exception, got {result:?}"),
+            Self::UnexpectedException(result) => write!(f, "unexpected exception {result:?}"),
+            Self::GasMismatch { expected, got } => write!(f, "gas left {got}, expected {expected}"),
+            Self::OutputMismatch { expected, got } => write!(
+                f,
+                "output 0x{}, expected 0x{}",
+                hex::encode(got),
+                hex::encode(expected)
+            ),
+            Self::LogsHashMismatch { expected, got } => write!(
+                f,
+                "logs hash 0x{}, expected 0x{}",
+                hex::encode(got),
+                hex::encode(expected)
+            ),
+            Self::AccountMismatch(address) => write!(f, "account {address:?} differs"),
+        }
+    }
+}
+
+impl std::error::Error for VmTestError {}
+
+impl VmTest {
+    pub fn run(&self, spec: SpecId) -> Result<(), VmTestError> {
+        let env = Env {
+            cfg: CfgEnv { chain_id: 1, spec },
+            block: self.env.block_env(),
+            tx: TxEnv {
+                caller: self.exec.origin,
+                gas_limit: self.exec.gas,
+                gas_price: self.exec.gas_price,
+                ..Default::default()
+            },
+        };
+        let mut evm = Evm::new(env, build_state(&self.pre));
+        evm.block_hashes = block_hashes(self.env.current_number);
+
+        let mut interpreter = Interpreter::new(self.exec.code.clone());
+        interpreter.gas = Gas::new(self.exec.gas);
+        interpreter.spec = spec;
+        interpreter.address = self.exec.address;
+        interpreter.caller = self.exec.caller;
+        interpreter.call_value = self.exec.value;
+        interpreter.input = self.exec.data.clone();
+        let result = interpreter.run_with_host(&mut evm);
+
+        let Some(post) = &self.post else {
+            return match result.is_error() {
+                true => Ok(()),
+                false => Err(VmTestError::MissingException(result)),
+            };
+        };
+        if result.is_error() {
+            return Err(VmTestError::UnexpectedException(result));
+        }
+
+        if let Some(expected) = self.gas {
+            let got = interpreter.gas.remaining();
+            if got != expected {
+                return Err(VmTestError::GasMismatch { expected, got });
+            }
+        }
+        if let Some(expected) = &self.out {
+            if interpreter.output != expected {
+                return Err(VmTestError::OutputMismatch {
+                    expected: expected.clone(),
+                    got: interpreter.output.clone(),
+                });
+            }
+        }
+        let logs = keccak256(rlp::encode(&evm.journaled_state.finalize()[..]));
+        if let Some(expected) = self.logs {
+            if logs != expected {
+                return Err(VmTestError::LogsHashMismatch {
+                    expected,
+                    got: logs,
+                });
+            }
+        }
+
+        match super::state_mismatch(&evm.journaled_state.into_state(), post) {
+            Some(address) => Err(VmTestError::AccountMismatch(address)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Runs every VM test in the file or directory at `path` under `spec`.
+pub fn run_path(path: &Path, spec: SpecId) -> super::Result<Vec<TestResult<VmTestError>>> {
+    let mut results = Vec::new();
+    for file in super::json_files(path)? {
+        for (name, test) in super::load::<VmTest>(&file)? {
+            results.push(TestResult {
+                result: test.run(spec),
+                name,
+            });
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{assert_all_ok, run_suite, vendored};
+
+    use super::*;
+
+    #[test]
+    fn vendored_fixtures() {
+        assert_all_ok(&run_path(&vendored("VMTests"), SpecId::Berlin).unwrap());
+    }
+
+    #[test]
+    fn reports_mismatches() {
+        let file = vendored("VMTests").join("vmArithmeticTest/add.json");
+        let tests = super::super::load::<VmTest>(&file).unwrap();
+        let test = &tests["add"];
+
+        let mut test = test.clone();
+        test.gas = Some(77887);
+        assert_eq!(
+            test.run(SpecId::Berlin),
+            Err(VmTestError::GasMismatch {
+                expected: 77887,
+                got: 77888
+            })
+        );
+
+        test.gas = None;
+        test.post
+            .as_mut()
+            .unwrap()
+            .values_mut()
+            .for_each(|account| {
+                account.storage.insert(U256::ZERO, U256::from(4));
+            });
+        assert!(matches!(
+            test.run(SpecId::Berlin),
+            Err(VmTestError::AccountMismatch(_))
+        ));
+
+        let test = &tests["addStackUnderflow"];
+        let mut test = test.clone();
+        test.post = Some(test.pre.clone());
+        assert_eq!(
+            test.run(SpecId::Berlin),
+            Err(VmTestError::UnexpectedException(
+                InstructionResult::StackError(crate::interpreter::stack::StackError::Underflow)
+            ))
+        );
+    }
+
+    /// Runs the legacy suite of a ethereum/tests checkout, e.g.
+    /// `ETHEREUM_TESTS=../tests cargo test -- --ignored vm_tests`. It was filled for
+    /// Constantinople.
+    #[test]
+    #[ignore]
+    fn vm_tests() {
+        run_suite(
+            "LegacyTests/Constantinople/VMTests",
+            |path| run_path(path, SpecId::Constantinople),
+            |_| false,
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! Hooks into execution, for tracers, debuggers and the like.
+
+use ruint::aliases::U256;
+
+use crate::{
+    domain::{env::TxEnv, journal::JournaledState, primitives::Address, state::Log},
+    executor::ExecutionResult,
+    interpreter::{
+        host::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
+        Interpreter,
+    },
+};
+
+/// Called by [`Evm`](crate::evm::Evm) around every instruction and frame it runs. Every hook
+/// does nothing by default, and the evm is generic over its inspector, so the ones that are not
+/// implemented cost nothing.
+pub trait Inspector {
+    /// Before the instruction at `interpreter.pc` runs. Setting `interpreter.instruction_result`
+    /// halts the frame instead.
+    #[inline]
+    fn step(&mut self, interpreter: &mut Interpreter, state: &mut JournaledState) {
+        let _ = (interpreter, state);
+    }
+
+    /// After the instruction ran, `interpreter.instruction_result` tells if it halted the frame.
+    #[inline]
+    fn step_end(&mut self, interpreter: &mut Interpreter, state: &mut JournaledState) {
+        let _ = (interpreter, state);
+    }
+
+    /// Before a call frame is entered. Returning a outcome skips the call and uses it instead.
+    #[inline]
+    fn call(&mut self, inputs: &mut CallInputs, state: &mut JournaledState) -> Option<CallOutcome> {
+        let _ = (inputs, state);
+        None
+    }
+
+    #[inline]
+    fn call_end(
+        &mut self,
+        inputs: &CallInputs,
+        outcome: &mut CallOutcome,
+        state: &mut JournaledState,
+    ) {
+        let _ = (inputs, outcome, state);
+    }
+
+    /// Before a create frame is entered. Returning a outcome skips it and uses it instead.
+    #[inline]
+    fn create(
+        &mut self,
+        inputs: &mut CreateInputs,
+        state: &mut JournaledState,
+    ) -> Option<CreateOutcome> {
+        let _ = (inputs, state);
+        None
+    }
+
+    #[inline]
+    fn create_end(
+        &mut self,
+        inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+        state: &mut JournaledState,
+    ) {
+        let _ = (inputs, outcome, state);
+    }
+
+    /// A log emitted by the current frame, it is dropped again if the frame reverts.
+    #[inline]
+    fn log(&mut self, log: &Log) {
+        let _ = log;
+    }
+
+    /// `address` sends its `value` to `target` and selfdestructs.
+    #[inline]
+    fn selfdestruct(&mut self, address: Address, target: Address, value: U256) {
+        let _ = (address, target, value);
+    }
+
+    /// After a transaction ran with
+    /// [`Executor::transact_with_inspector`](crate::executor::Executor::transact_with_inspector),
+    /// refunds paid out.
+    #[inline]
+    fn transaction_end(&mut self, tx: &TxEnv, result: &ExecutionResult) {
+        let _ = (tx, result);
+    }
+}
+
+/// The inspector of a evm that is not inspected.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NoopInspector;
+
+impl Inspector for NoopInspector {}
+
+impl<I: Inspector + ?Sized> Inspector for &mut I {
+    fn step(&mut self, interpreter: &mut Interpreter, state: &mut JournaledState) {
+        (**self).step(interpreter, state)
+    }
+
+    fn step_end(&mut self, interpreter: &mut Interpreter, state: &mut JournaledState) {
+        (**self).step_end(interpreter, state)
+    }
+
+    fn call(&mut self, inputs: &mut CallInputs, state: &mut JournaledState) -> Option<CallOutcome> {
+        (**self).call(inputs, state)
+    }
+
+    fn call_end(
+        &mut self,
+        inputs: &CallInputs,
+        outcome: &mut CallOutcome,
+        state: &mut JournaledState,
+    ) {
+        (**self).call_end(inputs, outcome, state)
+    }
+
+    fn create(
+        &mut self,
+        inputs: &mut CreateInputs,
+        state: &mut JournaledState,
+    ) -> Option<CreateOutcome> {
+        (**self).create(inputs, state)
+    }
+
+    fn create_end(
+        &mut self,
+        inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+        state: &mut JournaledState,
+    ) {
+        (**self).create_end(inputs, outcome, state)
+    }
+
+    fn log(&mut self, log: &Log) {
+        (**self).log(log)
+    }
+
+    fn selfdestruct(&mut self, address: Address, target: Address, value: U256) {
+        (**self).selfdestruct(address, target, value)
+    }
+
+    fn transaction_end(&mut self, tx: &TxEnv, result: &ExecutionResult) {
+        (**self).transaction_end(tx, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use crate::{
+        domain::{
+            env::Env,
+            state::{Account, State},
+        },
+        evm::Evm,
+        interpreter::{gas::Gas, host::Host, opcodes, InstructionResult},
+    };
+
+    use super::*;
+
+    const CALLER: Address = Address([0xca; 20]);
+    const A: Address = Address([0xaa; 20]);
+    const B: Address = Address([
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xbb,
+    ]);
+
+    #[derive(Debug, Default)]
+    struct Recorder {
+        steps: usize,
+        step_ends: usize,
+        events: Vec<String>,
+        /// Skips calls to B, answering them with a revert.
+        skip_b: bool,
+    }
+
+    impl Inspector for Recorder {
+        fn step(&mut self, _interpreter: &mut Interpreter, _state: &mut JournaledState) {
+            self.steps += 1;
+        }
+
+        fn step_end(&mut self, _interpreter: &mut Interpreter, _state: &mut JournaledState) {
+            self.step_ends += 1;
+        }
+
+        fn call(
+            &mut self,
+            inputs: &mut CallInputs,
+            state: &mut JournaledState,
+        ) -> Option<CallOutcome> {
+            self.events
+                .push(format!("call {} {}", inputs.target_address, state.depth));
+            (self.skip_b && inputs.target_address == B).then(|| CallOutcome {
+                result: InstructionResult::Revert,
+                gas: Gas::new(inputs.gas_limit),
+                output: Bytes::new(),
+            })
+        }
+
+        fn call_end(
+            &mut self,
+            inputs: &CallInputs,
+            outcome: &mut CallOutcome,
+            _state: &mut JournaledState,
+        ) {
+            self.events.push(format!(
+                "call_end {} {:?}",
+                inputs.target_address, outcome.result
+            ));
+        }
+
+        fn create(
+            &mut self,
+            _inputs: &mut CreateInputs,
+            state: &mut JournaledState,
+        ) -> Option<CreateOutcome> {
+            self.events.push(format!("create {}", state.depth));
+            None
+        }
+
+        fn create_end(
+            &mut self,
+            _inputs: &CreateInputs,
+            outcome: &mut CreateOutcome,
+            _state: &mut JournaledState,
+        ) {
+            self.events.push(format!("create_end {:?}", outcome.result));
+        }
+
+        fn log(&mut self, log: &Log) {
+            self.events.push(format!("log {}", log.address));
+        }
+
+        fn selfdestruct(&mut self, address: Address, target: Address, value: U256) {
+            self.events
+                .push(format!("selfdestruct {address} {target} {value}"));
+        }
+    }
+
+    fn build_evm(inspector: Recorder) -> Evm<Recorder> {
+        // LOG0, CALL B, CREATE with empty init code.
+        let a = Bytes::from_static(&[
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::LOG0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH1,
+            0xbb,
+            opcodes::GAS,
+            opcodes::CALL,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::CREATE,
+            opcodes::STOP,
+        ]);
+        // SELFDESTRUCT to 0xcc.
+        let b = Bytes::from_static(&[opcodes::PUSH1, 0xcc, opcodes::SELFDESTRUCT]);
+        let mut state = State::new();
+        state.insert(A, Account::with_code(a));
+        let mut b = Account::with_code(b);
+        b.balance = U256::from(3);
+        state.insert(B, b);
+        Evm::with_inspector(Env::default(), state, inspector)
+    }
+
+    fn call_a(evm: &mut Evm<Recorder>) -> CallOutcome {
+        evm.call(CallInputs {
+            gas_limit: 1_000_000,
+            target_address: A,
+            code_address: A,
+            caller: CALLER,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn hooks() {
+        let mut evm = build_evm(Recorder::default());
+        assert_eq!(call_a(&mut evm).result, InstructionResult::Stop);
+
+        let a = A.to_string();
+        let b = B.to_string();
+        let cc = Address::with_last_byte(0xcc);
+        assert_eq!(
+            evm.inspector.events,
+            [
+                format!("call {a} 0"),
+                format!("log {a}"),
+                format!("call {b} 1"),
+                format!("selfdestruct {b} {cc} 3"),
+                format!("call_end {b} SelfDestruct"),
+                "create 1".to_string(),
+                "create_end Return".to_string(),
+                format!("call_end {a} Stop"),
+            ]
+        );
+        // 16 in A, 2 in B and running off the end of the empty init code.
+        assert_eq!(evm.inspector.steps, 19);
+        assert_eq!(evm.inspector.step_ends, 19);
+    }
+
+    #[test]
+    fn skips_calls() {
+        let mut evm = build_evm(Recorder {
+            skip_b: true,
+            ..Default::default()
+        });
+        assert_eq!(call_a(&mut evm).result, InstructionResult::Stop);
+        assert_eq!(evm.inspector.events[2], format!("call {B} 1"));
+        assert_eq!(evm.inspector.events[3], format!("call_end {B} Revert"));
+        assert_eq!(evm.inspector.steps, 17);
+        assert_eq!(evm.journaled_state.balance(&B), U256::from(3));
+    }
+
+    #[test]
+    fn mutates_interpreter() {
+        /// Halts every frame before its first CALL.
+        struct Halt;
+
+        impl Inspector for Halt {
+            fn step(&mut self, interpreter: &mut Interpreter, _state: &mut JournaledState) {
+                if interpreter.bytecode.bytes_slice()[interpreter.pc] == opcodes::CALL {
+                    interpreter.instruction_result = InstructionResult::Revert;
+                }
+            }
+        }
+
+        let evm = build_evm(Recorder::default());
+        let mut evm = Evm::with_inspector(Env::default(), evm.journaled_state.state, Halt);
+        let mut interpreter = Interpreter::new(evm.journaled_state.code(&A));
+        assert_eq!(evm.run_frame(&mut interpreter), InstructionResult::Revert);
+        assert_eq!(interpreter.pc, 11);
+        assert_eq!(interpreter.stack.len(), 7);
+    }
+}
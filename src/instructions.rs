@@ -1,4 +1,100 @@
+/// Charges `$gas` or halts the frame with `OutOfGas`.
+macro_rules! gas {
+    ($interpreter:expr, $gas:expr) => {
+        if !$interpreter.gas.record_cost($gas) {
+            $interpreter.instruction_result = $crate::interpreter::InstructionResult::OutOfGas;
+            return;
+        }
+    };
+}
+
+/// Unwraps a stack operation or halts the frame with the stack error.
+macro_rules! try_stack {
+    ($interpreter:expr, $result:expr) => {
+        match $result {
+            Ok(value) => value,
+            Err(error) => {
+                $interpreter.instruction_result = error.into();
+                return;
+            }
+        }
+    };
+}
+
+/// Halts the frame if it is executing within a STATICCALL.
+macro_rules! require_non_staticcall {
+    ($interpreter:expr) => {
+        if $interpreter.is_static {
+            $interpreter.instruction_result =
+                $crate::interpreter::InstructionResult::StateChangeDuringStaticCall;
+            return;
+        }
+    };
+}
+
+/// Converts a stack word to a usize, anything that large can never be paid for so it is out of
+/// gas.
+macro_rules! as_usize_or_fail {
+    ($interpreter:expr, $value:expr) => {
+        match usize::try_from($value) {
+            Ok(value) if value <= u32::MAX as usize => value,
+            _ => {
+                $interpreter.instruction_result = $crate::interpreter::InstructionResult::OutOfGas;
+                return;
+            }
+        }
+    };
+}
+
+/// Offsets into calldata or code past their end read as zero, so they saturate instead.
+macro_rules! as_usize_saturated {
+    ($value:expr) => {
+        usize::try_from($value).unwrap_or(usize::MAX)
+    };
+}
+
+/// Converts a `(offset, len)` pair from the stack into a memory range, expanding memory and
+/// charging for it. A empty range never touches memory whatever its offset and comes back as
+/// `(0, 0)`.
+macro_rules! memory_range {
+    ($interpreter:expr, $offset:expr, $len:expr) => {{
+        let len = as_usize_or_fail!($interpreter, $len);
+        if len == 0 {
+            (0usize, 0usize)
+        } else {
+            let offset = as_usize_or_fail!($interpreter, $offset);
+            if !$interpreter.resize_memory(offset + len) {
+                $interpreter.instruction_result = $crate::interpreter::InstructionResult::OutOfGas;
+                return;
+            }
+            (offset, len)
+        }
+    }};
+}
+
+/// Charges the per word cost of a copy instruction and expands memory for it.
+macro_rules! copy_memory_range {
+    ($interpreter:expr, $memory_offset:expr, $len:expr) => {{
+        let len = as_usize_or_fail!($interpreter, $len);
+        gas!(
+            $interpreter,
+            $crate::interpreter::gas::copy_cost(len as u64)
+        );
+        memory_range!(
+            $interpreter,
+            $memory_offset,
+            ruint::aliases::U256::from(len)
+        )
+    }};
+}
+
 pub mod arithmetic;
 pub mod bitwise;
 pub mod comparison;
+pub mod contract;
+pub mod control;
+pub mod host;
+pub mod host_env;
+pub mod memory;
 pub mod stack;
+pub mod system;
@@ -1,29 +1,29 @@
 use ruint::aliases::U256;
 
-use crate::interpreter::Interpreter;
+use crate::interpreter::{gas, host::Host, Interpreter};
 
-pub fn add(interpreter: &mut Interpreter) {
+pub fn add(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => *r2 = r1.wrapping_add(*r2),
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn mul(interpreter: &mut Interpreter) {
+pub fn mul(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => *r2 = r1.wrapping_mul(*r2),
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn sub(interpreter: &mut Interpreter) {
+pub fn sub(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => *r2 = r1.wrapping_sub(*r2),
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn div(interpreter: &mut Interpreter) {
+pub fn div(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => {
             if *r2 != U256::ZERO {
@@ -34,7 +34,7 @@ pub fn div(interpreter: &mut Interpreter) {
     }
 }
 
-pub fn rem(interpreter: &mut Interpreter) {
+pub fn rem(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => {
             if *r2 != U256::ZERO {
@@ -45,30 +45,93 @@ pub fn rem(interpreter: &mut Interpreter) {
     }
 }
 
-pub fn smod(interpreter: &mut Interpreter) {
+/// Words are two's complement for the signed instructions.
+pub fn is_negative(value: &U256) -> bool {
+    value.bit(255)
+}
+
+/// The magnitude of a signed word, the minimum value stays the same as its magnitude does not
+/// fit.
+pub fn abs(value: U256) -> U256 {
+    if is_negative(&value) {
+        value.wrapping_neg()
+    } else {
+        value
+    }
+}
+
+pub fn sdiv(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    match interpreter.stack.pop_top() {
+        Ok((r1, r2)) => {
+            if r2.is_zero() {
+                return;
+            }
+            let quotient = abs(r1) / abs(*r2);
+            *r2 = if is_negative(&r1) != is_negative(r2) {
+                quotient.wrapping_neg()
+            } else {
+                quotient
+            };
+        }
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
+/// The result takes the sign of the dividend.
+pub fn smod(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    match interpreter.stack.pop_top() {
+        Ok((r1, r2)) => {
+            if r2.is_zero() {
+                return;
+            }
+            let remainder = abs(r1) % abs(*r2);
+            *r2 = if is_negative(&r1) {
+                remainder.wrapping_neg()
+            } else {
+                remainder
+            };
+        }
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
+/// Extends the sign of the `r1`th byte, counting from the least significant one.
+pub fn signextend(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
-        Ok((_, _)) => todo!(),
+        Ok((r1, r2)) => {
+            if r1 < U256::from(31) {
+                let bit = r1.to::<usize>() * 8 + 7;
+                let mask = (U256::from(1) << bit) - U256::from(1);
+                *r2 = if r2.bit(bit) { *r2 | !mask } else { *r2 & mask };
+            }
+        }
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn addmod(interpreter: &mut Interpreter) {
+pub fn addmod(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop2_top() {
         Ok((r1, r2, r3)) => *r3 = r1.add_mod(r2, *r3),
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn mulmod(interpreter: &mut Interpreter) {
+pub fn mulmod(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop2_top() {
         Ok((r1, r2, r3)) => *r3 = r1.mul_mod(r2, *r3),
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn exp(interpreter: &mut Interpreter) {
+pub fn exp(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
-        Ok((r1, r2)) => *r2 = r1.pow(*r2),
+        Ok((r1, r2)) => {
+            gas!(
+                interpreter,
+                gas::exp_cost(interpreter.spec, r2.byte_len() as u64)
+            );
+            *r2 = r1.pow(*r2);
+        }
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
@@ -77,7 +140,7 @@ pub fn exp(interpreter: &mut Interpreter) {
 mod tests {
     use bytes::Bytes;
 
-    use crate::interpreter::{opcodes, InstructionResult};
+    use crate::interpreter::{gas::Gas, opcodes, InstructionResult};
 
     use super::*;
 
@@ -146,6 +209,51 @@ mod tests {
         assert_eq!(evm.stack.pop().unwrap(), U256::from(2));
     }
 
+    #[test]
+    fn signed_div_mod() {
+        let neg = |value: u64| U256::ZERO.wrapping_sub(U256::from(value));
+        let mut evm = build_evm(&[opcodes::SDIV], &[neg(10), U256::from(3)]);
+        evm.run();
+        assert_eq!(evm.stack.pop().unwrap(), neg(3));
+
+        evm = build_evm(&[opcodes::SDIV], &[neg(10), neg(5)]);
+        evm.run();
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(2));
+
+        // The one overflowing case, MIN / -1 wraps back to MIN.
+        let min = U256::from(1) << 255;
+        evm = build_evm(&[opcodes::SDIV], &[min, neg(1)]);
+        evm.run();
+        assert_eq!(evm.stack.pop().unwrap(), min);
+
+        evm = build_evm(&[opcodes::SMOD], &[neg(10), U256::from(3)]);
+        evm.run();
+        assert_eq!(evm.stack.pop().unwrap(), neg(1));
+
+        evm = build_evm(&[opcodes::SMOD], &[U256::from(10), neg(3)]);
+        evm.run();
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(1));
+
+        evm = build_evm(&[opcodes::SDIV], &[U256::from(10), U256::ZERO]);
+        evm.run();
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+    }
+
+    #[test]
+    fn signextend() {
+        let mut evm = build_evm(&[opcodes::SIGNEXTEND], &[U256::ZERO, U256::from(0xff)]);
+        evm.run();
+        assert_eq!(evm.stack.pop().unwrap(), U256::MAX);
+
+        evm = build_evm(&[opcodes::SIGNEXTEND], &[U256::ZERO, U256::from(0x17f)]);
+        evm.run();
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(0x7f));
+
+        evm = build_evm(&[opcodes::SIGNEXTEND], &[U256::from(31), U256::from(0xff)]);
+        evm.run();
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(0xff));
+    }
+
     #[test]
     fn add_mod() {
         let mut evm = build_evm(
@@ -196,4 +304,16 @@ mod tests {
         assert_eq!(instr_res, InstructionResult::Stop);
         assert_eq!(evm.stack.pop().unwrap(), U256::from(1));
     }
+
+    #[test]
+    fn exp_gas() {
+        let mut evm = build_evm(&[opcodes::EXP], &[U256::from(2), U256::from(0x100)]);
+        evm.run();
+        assert_eq!(evm.gas.spent(), 10 + 2 * 50);
+
+        evm = build_evm(&[opcodes::EXP], &[U256::from(2), U256::MAX]);
+        evm.gas = Gas::new(10 + 31 * 50);
+        let instr_res = evm.run();
+        assert_eq!(instr_res, InstructionResult::OutOfGas);
+    }
 }
@@ -1,50 +1,81 @@
-use core::panic;
-
 use ruint::aliases::U256;
 
-use crate::interpreter::{stack::StackError, Interpreter};
+use crate::interpreter::{host::Host, Interpreter};
 
-pub fn and(interpreter: &mut Interpreter) {
+pub fn and(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => *r2 = r1 & *r2,
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn or(interpreter: &mut Interpreter) {
+pub fn or(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => *r2 = r1 | *r2,
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn xor(interpreter: &mut Interpreter) {
+pub fn xor(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => *r2 = r1 ^ *r2,
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn not(interpreter: &mut Interpreter) {
+pub fn not(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.top() {
         Ok(r1) => *r1 = !(*r1),
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn shl(interpreter: &mut Interpreter) {
+pub fn byte(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    match interpreter.stack.pop_top() {
+        Ok((r1, r2)) => {
+            // The index counts from the most significant byte.
+            *r2 = match usize::try_from(r1) {
+                Ok(index) if index < 32 => U256::from(r2.byte(31 - index)),
+                _ => U256::ZERO,
+            }
+        }
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
+pub fn shl(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => {
-            if let Some(res) = r2.checked_shl(r1.to()) {
-                *r2 = res;
-            } else {
-                *r2 = U256::ZERO
+            // Bits shifted out are dropped, shifting by 256 or more leaves nothing.
+            *r2 = match usize::try_from(r1) {
+                Ok(shift) if shift < 256 => r2.wrapping_shl(shift),
+                _ => U256::ZERO,
             }
         }
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
+pub fn shr(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    match interpreter.stack.pop_top() {
+        Ok((r1, r2)) => {
+            *r2 = match usize::try_from(r1) {
+                Ok(shift) if shift < 256 => r2.wrapping_shr(shift),
+                _ => U256::ZERO,
+            }
+        }
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
+/// Shift right that fills with the sign bit.
+pub fn sar(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    match interpreter.stack.pop_top() {
+        Ok((r1, r2)) => *r2 = r2.arithmetic_shr(usize::try_from(r1).unwrap_or(usize::MAX)),
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use bytes::Bytes;
@@ -110,5 +141,36 @@ mod tests {
 
         evm = build_evm(&[opcodes::SHL], &[U256::from(256), U256::MAX]);
         assert_eq!(evm.stack.pop().unwrap(), U256::from(0));
+
+        // The top bit is shifted out rather than the whole result zeroed.
+        evm = build_evm(&[opcodes::SHL], &[U256::from(1), U256::MAX]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::MAX - U256::from(1));
+    }
+
+    #[test]
+    fn byte() {
+        let value = U256::from(0xabcd);
+        let mut evm = build_evm(&[opcodes::BYTE], &[U256::from(31), value]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(0xcd));
+        evm = build_evm(&[opcodes::BYTE], &[U256::from(30), value]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(0xab));
+        evm = build_evm(&[opcodes::BYTE], &[U256::from(32), value]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+    }
+
+    #[test]
+    fn shr_sar() {
+        let mut evm = build_evm(&[opcodes::SHR], &[U256::from(4), U256::from(0xf0)]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(0xf));
+        evm = build_evm(&[opcodes::SHR], &[U256::MAX, U256::MAX]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+
+        let minus_16 = U256::ZERO.wrapping_sub(U256::from(16));
+        evm = build_evm(&[opcodes::SAR], &[U256::from(4), minus_16]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::MAX);
+        evm = build_evm(&[opcodes::SAR], &[U256::from(300), minus_16]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::MAX);
+        evm = build_evm(&[opcodes::SAR], &[U256::from(300), U256::from(16)]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
     }
 }
@@ -1,29 +1,57 @@
+use std::cmp::Ordering;
+
 use ruint::aliases::U256;
 
-use crate::interpreter::Interpreter;
+use crate::interpreter::{host::Host, Interpreter};
+
+use super::arithmetic::is_negative;
+
+/// Compares two's complement words, negatives sort below positives and among themselves the
+/// unsigned order holds.
+pub fn signed_cmp(a: &U256, b: &U256) -> Ordering {
+    match (is_negative(a), is_negative(b)) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        _ => a.cmp(b),
+    }
+}
 
-pub fn lt(interpreter: &mut Interpreter) {
+pub fn lt(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => *r2 = U256::from(r1.lt(r2)),
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn gt(interpreter: &mut Interpreter) {
+pub fn gt(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => *r2 = U256::from(r1.gt(r2)),
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn eq(interpreter: &mut Interpreter) {
+pub fn slt(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    match interpreter.stack.pop_top() {
+        Ok((r1, r2)) => *r2 = U256::from(signed_cmp(&r1, r2) == Ordering::Less),
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
+pub fn sgt(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    match interpreter.stack.pop_top() {
+        Ok((r1, r2)) => *r2 = U256::from(signed_cmp(&r1, r2) == Ordering::Greater),
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
+pub fn eq(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.pop_top() {
         Ok((r1, r2)) => *r2 = U256::from(r1.eq(r2)),
         Err(result) => interpreter.instruction_result = result.into(),
     }
 }
 
-pub fn iszero(interpreter: &mut Interpreter) {
+pub fn iszero(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     match interpreter.stack.top() {
         Ok(r1) => *r1 = U256::from(r1.is_zero()),
         Err(result) => interpreter.instruction_result = result.into(),
@@ -65,6 +93,17 @@ mod tests {
         assert_eq!(evm.stack.pop().unwrap(), U256::from(0));
     }
 
+    #[test]
+    fn signed() {
+        let minus_one = U256::MAX;
+        let mut evm = build_evm(&[opcodes::SLT], &[minus_one, U256::from(1)]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(1));
+        evm = build_evm(&[opcodes::SGT], &[minus_one, U256::from(1)]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(0));
+        evm = build_evm(&[opcodes::SGT], &[minus_one, minus_one - U256::from(1)]);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(1));
+    }
+
     #[test]
     fn eq() {
         let mut evm = build_evm(&[opcodes::EQ], &[U256::from(3), U256::from(3)]);
@@ -0,0 +1,493 @@
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use crate::{
+    domain::{constants::MAX_INITCODE_SIZE, primitives::Address, spec::SpecId},
+    interpreter::{
+        gas,
+        host::{CallInputs, CallKind, CreateInputs, CreateScheme, Host},
+        InstructionResult, Interpreter,
+    },
+};
+
+/// EIP-150: all but one 64th of the remaining gas can be passed on, before it all of it.
+fn max_forwarded_gas(spec: SpecId, remaining: u64) -> u64 {
+    if spec.is_enabled_in(SpecId::TangerineWhistle) {
+        remaining - remaining / 64
+    } else {
+        remaining
+    }
+}
+
+fn call_inner(interpreter: &mut Interpreter, host: &mut dyn Host, kind: CallKind) {
+    let [gas_limit, to] = try_stack!(interpreter, interpreter.stack.popn::<2>());
+    let value = match kind {
+        CallKind::Call | CallKind::CallCode => try_stack!(interpreter, interpreter.stack.pop()),
+        CallKind::DelegateCall | CallKind::StaticCall => U256::ZERO,
+    };
+    let [in_offset, in_len, out_offset, out_len] =
+        try_stack!(interpreter, interpreter.stack.popn::<4>());
+    let to = Address::from_word(to);
+    if kind == CallKind::Call && interpreter.is_static && !value.is_zero() {
+        interpreter.instruction_result = InstructionResult::StateChangeDuringStaticCall;
+        return;
+    }
+
+    let (in_offset, in_len) = memory_range!(interpreter, in_offset, in_len);
+    let (out_offset, out_len) = memory_range!(interpreter, out_offset, out_len);
+    let input = Bytes::copy_from_slice(interpreter.memory.slice(in_offset, in_len));
+
+    let account = host.load_account(to);
+    let mut cost = gas::call_cost(interpreter.spec, account.is_cold);
+    let transfers_value = !value.is_zero();
+    if transfers_value {
+        cost += gas::CALLVALUE;
+    }
+    // EIP-161: only sending value brings a account into existence.
+    if kind == CallKind::Call
+        && account.data
+        && (transfers_value || !interpreter.spec.is_enabled_in(SpecId::SpuriousDragon))
+    {
+        cost += gas::NEWACCOUNT;
+    }
+    gas!(interpreter, cost);
+
+    // Before EIP-150 asking for more than is left is out of gas.
+    let requested = u64::try_from(gas_limit).unwrap_or(u64::MAX);
+    let gas_limit = if interpreter.spec.is_enabled_in(SpecId::TangerineWhistle) {
+        requested.min(max_forwarded_gas(
+            interpreter.spec,
+            interpreter.gas.remaining(),
+        ))
+    } else {
+        requested
+    };
+    gas!(interpreter, gas_limit);
+    let gas_limit = if transfers_value {
+        gas_limit + gas::CALL_STIPEND
+    } else {
+        gas_limit
+    };
+
+    let inputs = match kind {
+        CallKind::Call => CallInputs {
+            kind,
+            input,
+            gas_limit,
+            target_address: to,
+            code_address: to,
+            caller: interpreter.address,
+            value,
+            transfers_value: true,
+            is_static: interpreter.is_static,
+        },
+        // Runs the code of `to` against the own account, the value goes to itself.
+        CallKind::CallCode => CallInputs {
+            kind,
+            input,
+            gas_limit,
+            target_address: interpreter.address,
+            code_address: to,
+            caller: interpreter.address,
+            value,
+            transfers_value: true,
+            is_static: interpreter.is_static,
+        },
+        // EIP-7: keeps the caller and value of the current frame.
+        CallKind::DelegateCall => CallInputs {
+            kind,
+            input,
+            gas_limit,
+            target_address: interpreter.address,
+            code_address: to,
+            caller: interpreter.caller,
+            value: interpreter.call_value,
+            transfers_value: false,
+            is_static: interpreter.is_static,
+        },
+        // EIP-214
+        CallKind::StaticCall => CallInputs {
+            kind,
+            input,
+            gas_limit,
+            target_address: to,
+            code_address: to,
+            caller: interpreter.address,
+            value: U256::ZERO,
+            transfers_value: false,
+            is_static: true,
+        },
+    };
+
+    let outcome = host.call(inputs);
+    let success = outcome.result.is_ok();
+    if success || outcome.result.is_revert() || outcome.result.is_aborted() {
+        interpreter.gas.erase_cost(outcome.gas.remaining());
+    }
+    if success {
+        interpreter.gas.record_refund(outcome.gas.refunded());
+    }
+    let len = out_len.min(outcome.output.len());
+    interpreter.memory.set(out_offset, &outcome.output[..len]);
+    interpreter.return_data = outcome.output;
+    try_stack!(interpreter, interpreter.stack.push(U256::from(success)));
+}
+
+pub fn call(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    call_inner(interpreter, host, CallKind::Call);
+}
+
+pub fn call_code(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    call_inner(interpreter, host, CallKind::CallCode);
+}
+
+pub fn delegate_call(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    call_inner(interpreter, host, CallKind::DelegateCall);
+}
+
+pub fn static_call(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    call_inner(interpreter, host, CallKind::StaticCall);
+}
+
+/// CREATE and, with `IS_CREATE2`, CREATE2 (EIP-1014).
+pub fn create<const IS_CREATE2: bool>(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    require_non_staticcall!(interpreter);
+    let [value, offset, len] = try_stack!(interpreter, interpreter.stack.popn::<3>());
+    let scheme = if IS_CREATE2 {
+        let salt = try_stack!(interpreter, interpreter.stack.pop());
+        CreateScheme::Create2 { salt }
+    } else {
+        CreateScheme::Create
+    };
+
+    let len = as_usize_or_fail!(interpreter, len);
+    if interpreter.spec.is_enabled_in(SpecId::Shanghai) {
+        if len > MAX_INITCODE_SIZE {
+            interpreter.instruction_result = InstructionResult::CreateInitCodeSizeLimit;
+            return;
+        }
+        gas!(interpreter, gas::initcode_cost(len as u64));
+    }
+    if IS_CREATE2 {
+        // The init code is hashed for the address.
+        gas!(interpreter, gas::keccak256_cost(len as u64));
+    }
+    let (offset, len) = memory_range!(interpreter, offset, U256::from(len));
+    let init_code = Bytes::copy_from_slice(interpreter.memory.slice(offset, len));
+
+    let gas_limit = max_forwarded_gas(interpreter.spec, interpreter.gas.remaining());
+    gas!(interpreter, gas_limit);
+
+    let outcome = host.create(CreateInputs {
+        caller: interpreter.address,
+        scheme,
+        value,
+        init_code,
+        gas_limit,
+    });
+    let success = outcome.result.is_ok();
+    if success || outcome.result.is_revert() || outcome.result.is_aborted() {
+        interpreter.gas.erase_cost(outcome.gas.remaining());
+    }
+    if success {
+        interpreter.gas.record_refund(outcome.gas.refunded());
+    }
+    // Only a revert leaves return data behind.
+    interpreter.return_data = if outcome.result.is_revert() {
+        outcome.output
+    } else {
+        Bytes::new()
+    };
+    let address = outcome.address.map(Address::into_word).unwrap_or_default();
+    try_stack!(interpreter, interpreter.stack.push(address));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        domain::{
+            constants::CALL_DEPTH_LIMIT,
+            primitives::{keccak256, KECCAK_EMPTY},
+            state::{Account, State},
+        },
+        evm::Evm,
+        interpreter::{gas::Gas, opcodes},
+    };
+
+    use super::*;
+
+    const CALLER: Address = Address([0xaa; 20]);
+
+    fn build_host(callee_code: &'static [u8]) -> Evm {
+        let mut state = State::new();
+        state.insert(CALLER, Account::with_balance(U256::from(100)));
+        state.insert(
+            Address::with_last_byte(0xbb),
+            Account::with_code(Bytes::from_static(callee_code)),
+        );
+        Evm::new(Default::default(), state)
+    }
+
+    fn run_evm(bytes: &'static [u8], host: &mut Evm) -> Interpreter {
+        let mut evm = Interpreter::new(Bytes::from_static(bytes));
+        evm.address = CALLER;
+        evm.gas = Gas::new(100_000);
+        assert_eq!(evm.run_with_host(host), InstructionResult::Stop);
+        evm
+    }
+
+    #[test]
+    fn call_copies_output() {
+        // Returns its caller and value.
+        let mut host = build_host(&[
+            opcodes::CALLER,
+            opcodes::PUSH0,
+            opcodes::MSTORE,
+            opcodes::CALLVALUE,
+            opcodes::PUSH1,
+            0x20,
+            opcodes::MSTORE,
+            opcodes::PUSH1,
+            0x40,
+            opcodes::PUSH0,
+            opcodes::RETURN,
+        ]);
+        let mut evm = run_evm(
+            &[
+                opcodes::PUSH1,
+                0x40,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH1,
+                0x03,
+                opcodes::PUSH1,
+                0xbb,
+                opcodes::GAS,
+                opcodes::CALL,
+            ],
+            &mut host,
+        );
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(1));
+        assert_eq!(
+            U256::from_be_slice(evm.memory.slice(0, 32)),
+            CALLER.into_word()
+        );
+        assert_eq!(U256::from_be_slice(evm.memory.slice(32, 32)), U256::from(3));
+        assert_eq!(evm.return_data.len(), 64);
+        let balance = host.journaled_state.balance(&Address::with_last_byte(0xbb));
+        assert_eq!(balance, U256::from(3));
+        // The pushes and GAS, a cold account, the value, memory for the output and what the
+        // callee spent, the stipend came back unused.
+        let callee = 2 + 2 + 3 + 3 + 2 + 3 + 3 + 3 + 3 + 2;
+        assert_eq!(
+            evm.gas.spent(),
+            3 * 3 + 2 * 3 + 2 + 2600 + 9000 + 6 + callee - 2300
+        );
+    }
+
+    #[test]
+    fn delegate_call_runs_in_own_storage() {
+        // Stores 1 at slot 0.
+        let mut host = build_host(&[opcodes::PUSH1, 0x01, opcodes::PUSH0, opcodes::SSTORE]);
+        let code = &[
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH1,
+            0xbb,
+            opcodes::GAS,
+            opcodes::DELEGATECALL,
+        ];
+        let mut evm = run_evm(code, &mut host);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(1));
+        assert_eq!(
+            host.journaled_state.sload(&CALLER, U256::ZERO),
+            U256::from(1)
+        );
+
+        // The same store is not allowed in a static call.
+        let mut host = build_host(&[opcodes::PUSH1, 0x01, opcodes::PUSH0, opcodes::SSTORE]);
+        let mut evm = run_evm(
+            &[
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH1,
+                0xbb,
+                opcodes::GAS,
+                opcodes::STATICCALL,
+            ],
+            &mut host,
+        );
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+        assert!(host.journaled_state.sload(&CALLER, U256::ZERO).is_zero());
+    }
+
+    fn run_in(spec: SpecId, code: &'static [u8], host: &mut Evm) -> Interpreter {
+        host.env.cfg.spec = spec;
+        let mut evm = Interpreter::new(Bytes::from_static(code));
+        evm.address = CALLER;
+        evm.gas = Gas::new(100_000);
+        evm.spec = spec;
+        evm.run_with_host(host);
+        evm
+    }
+
+    #[test]
+    fn aborted_calls_return_gas() {
+        // CALLER has 100 wei, sending 101 fails before the callee runs.
+        let mut host = build_host(&[opcodes::STOP]);
+        let mut evm = run_in(
+            SpecId::Cancun,
+            &[
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH1,
+                101,
+                opcodes::PUSH1,
+                0xbb,
+                opcodes::PUSH2,
+                0x27,
+                0x10,
+                opcodes::CALL,
+            ],
+            &mut host,
+        );
+        assert_eq!(evm.instruction_result, InstructionResult::Stop);
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+        // The forwarded gas and the stipend come back, the access and value costs do not.
+        assert_eq!(evm.gas.spent(), 17 + 2600 + 9000 - 2300);
+
+        // Past the depth limit neither a call nor a create runs.
+        let mut host = build_host(&[opcodes::STOP]);
+        host.journaled_state.depth = CALL_DEPTH_LIMIT + 1;
+        let mut evm = run_in(
+            SpecId::Cancun,
+            &[
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH1,
+                0xbb,
+                opcodes::PUSH2,
+                0x27,
+                0x10,
+                opcodes::CALL,
+            ],
+            &mut host,
+        );
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+        assert_eq!(evm.gas.spent(), 16 + 2600);
+
+        let mut evm = run_in(
+            SpecId::Cancun,
+            &[
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::CREATE,
+            ],
+            &mut host,
+        );
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+        assert_eq!(evm.gas.spent(), 6 + gas::CREATE);
+        assert_eq!(host.journaled_state.nonce(&CALLER), 0);
+    }
+
+    #[test]
+    fn older_forks() {
+        // Before EIP-150 a call gets all it asks for, or runs out of gas. No PUSH0 yet.
+        let code = &[
+            opcodes::PUSH1,
+            0,
+            opcodes::PUSH1,
+            0,
+            opcodes::PUSH1,
+            0,
+            opcodes::PUSH1,
+            0,
+            opcodes::PUSH1,
+            0,
+            opcodes::PUSH1,
+            0xbb,
+            opcodes::PUSH3,
+            0x03,
+            0x0d,
+            0x40,
+            opcodes::CALL,
+        ];
+        let mut evm = run_in(SpecId::Homestead, code, &mut build_host(&[]));
+        assert_eq!(evm.instruction_result, InstructionResult::OutOfGas);
+        evm = run_in(SpecId::TangerineWhistle, code, &mut build_host(&[]));
+        assert_eq!(evm.instruction_result, InstructionResult::Stop);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(1));
+
+        // Before EIP-161 calling a account that does not exist costs a new one, value or not.
+        let code = &[
+            opcodes::PUSH1,
+            0,
+            opcodes::PUSH1,
+            0,
+            opcodes::PUSH1,
+            0,
+            opcodes::PUSH1,
+            0,
+            opcodes::PUSH1,
+            0,
+            opcodes::PUSH1,
+            0xcc,
+            opcodes::PUSH1,
+            0,
+            opcodes::CALL,
+        ];
+        evm = run_in(SpecId::TangerineWhistle, code, &mut build_host(&[]));
+        assert_eq!(evm.gas.spent(), 21 + 700 + gas::NEWACCOUNT);
+        evm = run_in(SpecId::SpuriousDragon, code, &mut build_host(&[]));
+        assert_eq!(evm.gas.spent(), 21 + 700);
+    }
+
+    #[test]
+    fn create2_address() {
+        let mut host = build_host(&[]);
+        let mut evm = run_evm(
+            &[
+                opcodes::PUSH1,
+                0x2a,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::CREATE2,
+            ],
+            &mut host,
+        );
+        let salt = U256::from(0x2a).to_be_bytes();
+        let address = CALLER.create2(salt, keccak256([]));
+        assert_eq!(evm.stack.pop().unwrap(), address.into_word());
+        assert_eq!(host.journaled_state.nonce(&address), 1);
+        assert_eq!(
+            host.journaled_state.account(&address).unwrap().code_hash,
+            KECCAK_EMPTY
+        );
+
+        // Deploying to the same address again collides and pushes zero.
+        evm = run_evm(
+            &[
+                opcodes::PUSH1,
+                0x2a,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::CREATE2,
+            ],
+            &mut host,
+        );
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+    }
+}
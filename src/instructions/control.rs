@@ -0,0 +1,109 @@
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use crate::interpreter::{host::Host, InstructionResult, Interpreter};
+
+pub fn stop(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    interpreter.instruction_result = InstructionResult::Stop;
+}
+
+fn jump_to(interpreter: &mut Interpreter, dest: U256) {
+    match usize::try_from(dest) {
+        Ok(dest) if interpreter.is_valid_jump(dest) => interpreter.pc = dest,
+        _ => interpreter.instruction_result = InstructionResult::InvalidJump,
+    }
+}
+
+pub fn jump(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let dest = try_stack!(interpreter, interpreter.stack.pop());
+    jump_to(interpreter, dest);
+}
+
+pub fn jumpi(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let (dest, condition) = try_stack!(interpreter, interpreter.stack.pop2());
+    if !condition.is_zero() {
+        jump_to(interpreter, dest);
+    }
+}
+
+pub fn jumpdest(_interpreter: &mut Interpreter, _host: &mut dyn Host) {}
+
+pub fn pc(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    // The pc has already moved past this instruction.
+    let pc = U256::from(interpreter.pc - 1);
+    try_stack!(interpreter, interpreter.stack.push(pc));
+}
+
+fn return_inner(interpreter: &mut Interpreter, result: InstructionResult) {
+    let (offset, len) = try_stack!(interpreter, interpreter.stack.pop2());
+    let (offset, len) = memory_range!(interpreter, offset, len);
+    interpreter.output = Bytes::copy_from_slice(interpreter.memory.slice(offset, len));
+    interpreter.instruction_result = result;
+}
+
+pub fn ret(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    return_inner(interpreter, InstructionResult::Return);
+}
+
+pub fn revert(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    return_inner(interpreter, InstructionResult::Revert);
+}
+
+pub fn invalid(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    interpreter.instruction_result = InstructionResult::InvalidFEOpcode;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bytecode;
+
+    use super::*;
+
+    #[test]
+    fn jumps() {
+        let mut evm = Interpreter::new(bytecode!(
+            PUSH target JUMP INVALID
+            target: JUMPDEST PC
+        ));
+        assert_eq!(evm.run(), InstructionResult::Stop);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(5));
+
+        // The JUMPDEST byte at 4 is the data of the second PUSH1.
+        evm = Interpreter::new(bytecode!(PUSH1 0x04 JUMP PUSH1 0x5b));
+        assert_eq!(evm.run(), InstructionResult::InvalidJump);
+    }
+
+    #[test]
+    fn jumpi() {
+        let mut evm = Interpreter::new(bytecode!(
+            PUSH0 PUSH skip JUMPI
+            PUSH1 0x01 INVALID
+            skip: JUMPDEST
+        ));
+        assert_eq!(evm.run(), InstructionResult::InvalidFEOpcode);
+
+        evm = Interpreter::new(bytecode!(
+            PUSH1 0x01 PUSH skip JUMPI
+            INVALID
+            skip: JUMPDEST
+        ));
+        assert_eq!(evm.run(), InstructionResult::Stop);
+    }
+
+    #[test]
+    fn return_revert() {
+        let mut evm = Interpreter::new(bytecode!(
+            PUSH1 0x2a PUSH0 MSTORE
+            PUSH1 0x20 PUSH0 RETURN
+        ));
+        assert_eq!(evm.run(), InstructionResult::Return);
+        assert_eq!(evm.output.len(), 32);
+        assert_eq!(evm.output[31], 0x2a);
+
+        evm = Interpreter::new(bytecode!(PUSH0 PUSH1 0xff REVERT));
+        assert_eq!(evm.run(), InstructionResult::Revert);
+        // A empty range does not touch memory, whatever the offset.
+        assert!(evm.output.is_empty());
+        assert_eq!(evm.memory.len(), 0);
+    }
+}
@@ -0,0 +1,443 @@
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use crate::{
+    domain::{primitives::Address, spec::SpecId, state::Log},
+    interpreter::{gas, host::Host, InstructionResult, Interpreter},
+};
+
+pub fn balance(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    match interpreter.stack.top() {
+        Ok(r1) => {
+            let balance = host.balance(Address::from_word(*r1));
+            gas!(
+                interpreter,
+                gas::balance_cost(interpreter.spec, balance.is_cold)
+            );
+            *r1 = balance.data;
+        }
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
+pub fn extcodesize(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    match interpreter.stack.top() {
+        Ok(r1) => {
+            let code = host.code(Address::from_word(*r1));
+            gas!(
+                interpreter,
+                gas::extcode_cost(interpreter.spec, code.is_cold)
+            );
+            *r1 = U256::from(code.data.len());
+        }
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
+pub fn extcodehash(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    match interpreter.stack.top() {
+        Ok(r1) => {
+            let code_hash = host.code_hash(Address::from_word(*r1));
+            gas!(
+                interpreter,
+                gas::extcodehash_cost(interpreter.spec, code_hash.is_cold)
+            );
+            *r1 = U256::from_be_bytes(code_hash.data);
+        }
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
+pub fn extcodecopy(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let [address, memory_offset, code_offset, len] =
+        try_stack!(interpreter, interpreter.stack.popn::<4>());
+    let code = host.code(Address::from_word(address));
+    gas!(
+        interpreter,
+        gas::extcode_cost(interpreter.spec, code.is_cold)
+    );
+    let (memory_offset, len) = copy_memory_range!(interpreter, memory_offset, len);
+    interpreter.memory.set_data(
+        memory_offset,
+        as_usize_saturated!(code_offset),
+        len,
+        &code.data,
+    );
+}
+
+/// Only the 256 most recent blocks are available, anything else is zero.
+pub fn blockhash(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let number = try_stack!(interpreter, interpreter.stack.top());
+    let current = host.env().block.number;
+    *number = match u64::try_from(*number) {
+        Ok(n) if n < current && current - n <= 256 => U256::from_be_bytes(host.block_hash(n)),
+        _ => U256::ZERO,
+    };
+}
+
+/// EIP-1884
+pub fn selfbalance(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let balance = host.balance(interpreter.address).data;
+    try_stack!(interpreter, interpreter.stack.push(balance));
+}
+
+pub fn sload(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    match interpreter.stack.top() {
+        Ok(r1) => {
+            let value = host.sload(interpreter.address, *r1);
+            gas!(
+                interpreter,
+                gas::sload_cost(interpreter.spec, value.is_cold)
+            );
+            *r1 = value.data;
+        }
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
+pub fn sstore(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    require_non_staticcall!(interpreter);
+    // EIP-2200: SSTORE is not allowed to eat into the call stipend.
+    if interpreter.spec.is_enabled_in(SpecId::Istanbul)
+        && interpreter.gas.remaining() <= gas::CALL_STIPEND
+    {
+        interpreter.instruction_result = InstructionResult::OutOfGas;
+        return;
+    }
+    match interpreter.stack.pop2() {
+        Ok((key, value)) => {
+            let result = host.sstore(interpreter.address, key, value);
+            gas!(
+                interpreter,
+                gas::sstore_cost(interpreter.spec, &result.data, result.is_cold)
+            );
+            interpreter
+                .gas
+                .record_refund(gas::sstore_refund(interpreter.spec, &result.data));
+        }
+        Err(result) => interpreter.instruction_result = result.into(),
+    }
+}
+
+/// EIP-1153
+pub fn tload(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let key = try_stack!(interpreter, interpreter.stack.top());
+    *key = host.tload(interpreter.address, *key);
+}
+
+pub fn tstore(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    require_non_staticcall!(interpreter);
+    let (key, value) = try_stack!(interpreter, interpreter.stack.pop2());
+    host.tstore(interpreter.address, key, value);
+}
+
+pub fn log<const N: usize>(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    require_non_staticcall!(interpreter);
+    let (offset, len) = try_stack!(interpreter, interpreter.stack.pop2());
+    let len = as_usize_or_fail!(interpreter, len);
+    gas!(interpreter, gas::log_cost(len as u64));
+    let (offset, len) = memory_range!(interpreter, offset, U256::from(len));
+    let topics = try_stack!(interpreter, interpreter.stack.popn::<N>());
+    host.log(Log {
+        address: interpreter.address,
+        topics: topics.to_vec(),
+        data: Bytes::copy_from_slice(interpreter.memory.slice(offset, len)),
+    });
+}
+
+pub fn selfdestruct(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    require_non_staticcall!(interpreter);
+    let target = Address::from_word(try_stack!(interpreter, interpreter.stack.pop()));
+    let result = host.selfdestruct(interpreter.address, target);
+
+    // EIP-150 priced SELFDESTRUCT, it was free before.
+    let mut cost = 0;
+    if interpreter.spec.is_enabled_in(SpecId::TangerineWhistle) {
+        cost += gas::SELFDESTRUCT;
+    }
+    if result.is_cold && interpreter.spec.is_enabled_in(SpecId::Berlin) {
+        cost += gas::COLD_ACCOUNT_ACCESS_COST;
+    }
+    // EIP-161: only sending value brings the target into existence.
+    if interpreter.spec.is_enabled_in(SpecId::TangerineWhistle)
+        && result.data.target_is_empty
+        && (result.data.had_value || !interpreter.spec.is_enabled_in(SpecId::SpuriousDragon))
+    {
+        cost += gas::NEWACCOUNT;
+    }
+    gas!(interpreter, cost);
+    // EIP-3529 removed the refund in London.
+    if !interpreter.spec.is_enabled_in(SpecId::London) && !result.data.previously_destroyed {
+        interpreter.gas.record_refund(gas::SELFDESTRUCT_REFUND);
+    }
+    interpreter.instruction_result = InstructionResult::SelfDestruct;
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use crate::{
+        domain::{
+            primitives::KECCAK_EMPTY,
+            state::{Account, State},
+        },
+        evm::Evm,
+        interpreter::{gas::Gas, opcodes},
+    };
+
+    use super::*;
+
+    fn run_evm(bytes: &[u8], to_push: &[U256], host: &mut Evm) -> Interpreter {
+        let code = Bytes::copy_from_slice(bytes);
+        let mut evm = Interpreter::new(code);
+        evm.address = Address::with_last_byte(0xc);
+        evm.gas = Gas::new(100_000);
+        for num in to_push.iter().rev() {
+            evm.stack.push(*num).unwrap();
+        }
+        evm.run_with_host(host);
+        evm
+    }
+
+    fn build_host() -> Evm {
+        let mut state = State::new();
+        state.insert(
+            Address::with_last_byte(0xa),
+            Account::with_balance(U256::from(42)),
+        );
+        state.insert(
+            Address::with_last_byte(0xc),
+            Account::with_balance(U256::from(7)),
+        );
+        Evm::new(Default::default(), state)
+    }
+
+    #[test]
+    fn balance_cold_then_warm() {
+        let mut host = build_host();
+        let address = Address::with_last_byte(0xa).into_word();
+        let mut evm = run_evm(
+            &[
+                opcodes::BALANCE,
+                opcodes::POP,
+                opcodes::PUSH1,
+                0x0a,
+                opcodes::BALANCE,
+            ],
+            &[address],
+            &mut host,
+        );
+        assert_eq!(evm.instruction_result, InstructionResult::Stop);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(42));
+        assert_eq!(evm.gas.spent(), 2600 + 2 + 3 + 100);
+    }
+
+    #[test]
+    fn extcode() {
+        let mut host = build_host();
+        let funded = Address::with_last_byte(0xa).into_word();
+        let missing = Address::with_last_byte(0xb).into_word();
+        let mut evm = run_evm(&[opcodes::EXTCODEHASH], &[funded], &mut host);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from_be_bytes(KECCAK_EMPTY));
+
+        evm = run_evm(&[opcodes::EXTCODEHASH], &[missing], &mut host);
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+
+        evm = run_evm(&[opcodes::EXTCODESIZE], &[funded], &mut host);
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+        assert_eq!(evm.gas.spent(), 100);
+    }
+
+    #[test]
+    fn sload_sstore() {
+        let mut host = build_host();
+        let mut evm = run_evm(
+            &[
+                opcodes::PUSH1,
+                0x05,
+                opcodes::PUSH1,
+                0x01,
+                opcodes::SSTORE,
+                opcodes::PUSH1,
+                0x01,
+                opcodes::SLOAD,
+            ],
+            &[],
+            &mut host,
+        );
+        assert_eq!(evm.instruction_result, InstructionResult::Stop);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(5));
+        // cold set of a fresh slot then a warm read
+        assert_eq!(evm.gas.spent(), 3 + 3 + 22100 + 3 + 100);
+
+        // Clearing the slot again within the transaction refunds most of the set.
+        evm = run_evm(
+            &[opcodes::PUSH0, opcodes::PUSH1, 0x01, opcodes::SSTORE],
+            &[],
+            &mut host,
+        );
+        assert_eq!(evm.gas.spent(), 2 + 3 + 100);
+        assert_eq!(evm.gas.refunded(), 19900);
+    }
+
+    #[test]
+    fn older_forks() {
+        // BALANCE of 0xa, SSTORE 1 := 5 and SLOAD 1, none of it warm or cold before Berlin.
+        let code = [
+            opcodes::PUSH1,
+            0x0a,
+            opcodes::BALANCE,
+            opcodes::PUSH1,
+            0x05,
+            opcodes::PUSH1,
+            0x01,
+            opcodes::SSTORE,
+            opcodes::PUSH1,
+            0x01,
+            opcodes::SLOAD,
+        ];
+        for (spec, cost) in [
+            (SpecId::Frontier, 20 + 20000 + 50),
+            (SpecId::Petersburg, 400 + 20000 + 200),
+            (SpecId::Istanbul, 700 + 20000 + 800),
+            (SpecId::Berlin, 2600 + 22100 + 100),
+        ] {
+            let mut host = build_host();
+            let mut evm = Interpreter::new(Bytes::copy_from_slice(&code));
+            evm.address = Address::with_last_byte(0xc);
+            evm.gas = Gas::new(100_000);
+            evm.spec = spec;
+            assert_eq!(evm.run_with_host(&mut host), InstructionResult::Stop);
+            assert_eq!(evm.gas.spent(), 4 * 3 + cost, "{spec:?}");
+        }
+
+        // The stipend only guards SSTORE from Istanbul on.
+        let mut host = build_host();
+        let mut evm = Interpreter::new(Bytes::from_static(&[opcodes::SSTORE]));
+        evm.gas = Gas::new(5000);
+        evm.spec = SpecId::Petersburg;
+        evm.stack.push(U256::ZERO).unwrap();
+        evm.stack.push(U256::ZERO).unwrap();
+        assert_eq!(evm.run_with_host(&mut host), InstructionResult::Stop);
+        assert_eq!(evm.gas.remaining(), 0);
+    }
+
+    #[test]
+    fn sstore_stipend() {
+        let mut host = build_host();
+        let mut evm = Interpreter::new(Bytes::from_static(&[opcodes::SSTORE]));
+        evm.gas = Gas::new(2300);
+        evm.stack.push(U256::ZERO).unwrap();
+        evm.stack.push(U256::ZERO).unwrap();
+        assert_eq!(evm.run_with_host(&mut host), InstructionResult::OutOfGas);
+    }
+
+    #[test]
+    fn log() {
+        let mut host = build_host();
+        let evm = run_evm(
+            &[
+                opcodes::PUSH1,
+                0x01,
+                opcodes::PUSH1,
+                0x20,
+                opcodes::PUSH0,
+                opcodes::LOG1,
+            ],
+            &[],
+            &mut host,
+        );
+        assert_eq!(evm.instruction_result, InstructionResult::Stop);
+        assert_eq!(evm.gas.spent(), 2 * 3 + 2 + 750 + 8 * 32 + 3);
+        let logs = &host.journaled_state.logs;
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].address, Address::with_last_byte(0xc));
+        assert_eq!(logs[0].topics, vec![U256::from(1)]);
+        assert_eq!(logs[0].data.len(), 32);
+
+        let mut evm = Interpreter::new(Bytes::from_static(&[opcodes::LOG0]));
+        evm.is_static = true;
+        evm.stack.push(U256::ZERO).unwrap();
+        evm.stack.push(U256::ZERO).unwrap();
+        assert_eq!(
+            evm.run_with_host(&mut host),
+            InstructionResult::StateChangeDuringStaticCall
+        );
+    }
+
+    #[test]
+    fn transient_storage() {
+        let mut host = build_host();
+        let mut evm = run_evm(
+            &[
+                opcodes::PUSH1,
+                0x05,
+                opcodes::PUSH1,
+                0x01,
+                opcodes::TSTORE,
+                opcodes::PUSH1,
+                0x01,
+                opcodes::TLOAD,
+            ],
+            &[],
+            &mut host,
+        );
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(5));
+        assert_eq!(evm.gas.spent(), 3 * 3 + 100 * 2);
+        // Nothing is written to the persistent storage.
+        assert_eq!(
+            host.journaled_state
+                .sload(&Address::with_last_byte(0xc), U256::from(1)),
+            U256::ZERO
+        );
+    }
+
+    #[test]
+    fn selfbalance_and_blockhash() {
+        let mut host = build_host();
+        host.env.block.number = 300;
+        host.block_hashes.insert(299, [9; 32]);
+        let mut evm = run_evm(
+            &[
+                opcodes::SELFBALANCE,
+                opcodes::PUSH2,
+                0x01,
+                0x2b,
+                opcodes::BLOCKHASH,
+                opcodes::PUSH1,
+                0x2b,
+                opcodes::BLOCKHASH,
+                opcodes::PUSH2,
+                0x01,
+                0x2c,
+                opcodes::BLOCKHASH,
+            ],
+            &[],
+            &mut host,
+        );
+        // The current block is not available, nor anything older than 256 blocks.
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from_be_bytes([9; 32]));
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(7));
+    }
+
+    #[test]
+    fn selfdestruct() {
+        let mut host = build_host();
+        let beneficiary = Address::with_last_byte(0xb);
+        let evm = run_evm(
+            &[opcodes::SELFDESTRUCT],
+            &[beneficiary.into_word()],
+            &mut host,
+        );
+        assert_eq!(evm.instruction_result, InstructionResult::SelfDestruct);
+        // Cold beneficiary that does not exist yet.
+        assert_eq!(evm.gas.spent(), 5000 + 2600 + 25000);
+        assert_eq!(evm.gas.refunded(), 0);
+        assert_eq!(host.journaled_state.balance(&beneficiary), U256::from(7));
+        // Not created in this transaction, so Cancun keeps the account (EIP-6780).
+        assert!(host.journaled_state.destroyed.is_empty());
+    }
+}
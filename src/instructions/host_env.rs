@@ -0,0 +1,168 @@
+use ruint::aliases::U256;
+
+use crate::{
+    domain::spec::SpecId,
+    interpreter::{host::Host, Interpreter},
+};
+
+pub fn origin(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let origin = host.env().tx.caller.into_word();
+    try_stack!(interpreter, interpreter.stack.push(origin));
+}
+
+pub fn gasprice(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let price = host.env().effective_gas_price();
+    try_stack!(interpreter, interpreter.stack.push(price));
+}
+
+pub fn coinbase(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let coinbase = host.env().block.coinbase.into_word();
+    try_stack!(interpreter, interpreter.stack.push(coinbase));
+}
+
+pub fn timestamp(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let timestamp = U256::from(host.env().block.timestamp);
+    try_stack!(interpreter, interpreter.stack.push(timestamp));
+}
+
+pub fn number(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let number = U256::from(host.env().block.number);
+    try_stack!(interpreter, interpreter.stack.push(number));
+}
+
+/// DIFFICULTY before the merge, PREVRANDAO after (EIP-4399).
+pub fn prevrandao(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let block = &host.env().block;
+    let value = if interpreter.spec.is_enabled_in(SpecId::Paris) {
+        U256::from_be_bytes(block.prevrandao.unwrap_or_default())
+    } else {
+        block.difficulty
+    };
+    try_stack!(interpreter, interpreter.stack.push(value));
+}
+
+pub fn gaslimit(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let gas_limit = U256::from(host.env().block.gas_limit);
+    try_stack!(interpreter, interpreter.stack.push(gas_limit));
+}
+
+/// EIP-1344
+pub fn chainid(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let chain_id = U256::from(host.env().cfg.chain_id);
+    try_stack!(interpreter, interpreter.stack.push(chain_id));
+}
+
+/// EIP-3198
+pub fn basefee(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let basefee = host.env().block.basefee;
+    try_stack!(interpreter, interpreter.stack.push(basefee));
+}
+
+/// EIP-4844, zero for indices past the blobs of the transaction.
+pub fn blobhash(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let index = try_stack!(interpreter, interpreter.stack.top());
+    let blob_hashes = &host.env().tx.blob_hashes;
+    *index = match usize::try_from(*index) {
+        Ok(i) if i < blob_hashes.len() => U256::from_be_bytes(blob_hashes[i]),
+        _ => U256::ZERO,
+    };
+}
+
+/// EIP-7516
+pub fn blobbasefee(interpreter: &mut Interpreter, host: &mut dyn Host) {
+    let price = U256::from(host.env().block.blob_gasprice().unwrap_or_default());
+    try_stack!(interpreter, interpreter.stack.push(price));
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use crate::{
+        domain::primitives::Address,
+        evm::Evm,
+        interpreter::{opcodes, InstructionResult},
+    };
+
+    use super::*;
+
+    fn run_evm(bytes: &'static [u8], spec: SpecId, host: &mut Evm) -> Interpreter {
+        let mut evm = Interpreter::new(Bytes::from_static(bytes));
+        evm.spec = spec;
+        assert_eq!(evm.run_with_host(host), InstructionResult::Stop);
+        evm
+    }
+
+    #[test]
+    fn block_and_tx() {
+        let mut host = Evm::default();
+        host.env.cfg.chain_id = 5;
+        host.env.block.number = 100;
+        host.env.block.coinbase = Address::with_last_byte(0xcb);
+        host.env.block.basefee = U256::from(7);
+        host.env.tx.caller = Address::with_last_byte(0xca);
+        host.env.tx.gas_price = U256::from(10);
+        host.env.tx.gas_priority_fee = Some(U256::from(1));
+
+        let mut evm = run_evm(
+            &[
+                opcodes::CHAINID,
+                opcodes::NUMBER,
+                opcodes::COINBASE,
+                opcodes::ORIGIN,
+                opcodes::GASPRICE,
+                opcodes::BASEFEE,
+            ],
+            SpecId::Cancun,
+            &mut host,
+        );
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(7));
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(8));
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(0xca));
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(0xcb));
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(100));
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(5));
+    }
+
+    #[test]
+    fn prevrandao_replaces_difficulty() {
+        let mut host = Evm::default();
+        host.env.block.difficulty = U256::from(131072);
+        host.env.block.prevrandao = Some([1; 32]);
+
+        let mut evm = run_evm(&[opcodes::PREVRANDAO], SpecId::London, &mut host);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(131072));
+        evm = run_evm(&[opcodes::PREVRANDAO], SpecId::Paris, &mut host);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from_be_bytes([1; 32]));
+    }
+
+    #[test]
+    fn blobs() {
+        let mut host = Evm::default();
+        host.env.tx.blob_hashes = vec![[2; 32]];
+        host.env.block.excess_blob_gas = Some(0);
+
+        let mut evm = run_evm(
+            &[
+                opcodes::PUSH0,
+                opcodes::BLOBHASH,
+                opcodes::PUSH1,
+                0x01,
+                opcodes::BLOBHASH,
+                opcodes::BLOBBASEFEE,
+            ],
+            SpecId::Cancun,
+            &mut host,
+        );
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(1));
+        assert_eq!(evm.stack.pop().unwrap(), U256::ZERO);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from_be_bytes([2; 32]));
+
+        let mut evm = Interpreter::new(Bytes::from_static(&[opcodes::BLOBHASH]));
+        evm.spec = SpecId::Shanghai;
+        assert_eq!(
+            evm.run_with_host(&mut host),
+            InstructionResult::OpcodeNotFound
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use ruint::aliases::U256;
+
+use crate::interpreter::{gas, host::Host, InstructionResult, Interpreter};
+
+pub fn mload(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let offset = try_stack!(interpreter, interpreter.stack.pop());
+    let (offset, _) = memory_range!(interpreter, offset, U256::from(32));
+    let value = U256::from_be_slice(interpreter.memory.slice(offset, 32));
+    try_stack!(interpreter, interpreter.stack.push(value));
+}
+
+pub fn mstore(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let (offset, value) = try_stack!(interpreter, interpreter.stack.pop2());
+    let (offset, _) = memory_range!(interpreter, offset, U256::from(32));
+    interpreter.memory.set(offset, &value.to_be_bytes::<32>());
+}
+
+pub fn mstore8(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let (offset, value) = try_stack!(interpreter, interpreter.stack.pop2());
+    let (offset, _) = memory_range!(interpreter, offset, U256::from(1));
+    interpreter.memory.set(offset, &[value.byte(0)]);
+}
+
+pub fn msize(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let len = U256::from(interpreter.memory.len());
+    try_stack!(interpreter, interpreter.stack.push(len));
+}
+
+/// EIP-5656
+pub fn mcopy(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let [dst, src, len] = try_stack!(interpreter, interpreter.stack.popn::<3>());
+    let len = as_usize_or_fail!(interpreter, len);
+    gas!(interpreter, gas::copy_cost(len as u64));
+    if len == 0 {
+        return;
+    }
+    let dst = as_usize_or_fail!(interpreter, dst);
+    let src = as_usize_or_fail!(interpreter, src);
+    if !interpreter.resize_memory(dst.max(src) + len) {
+        interpreter.instruction_result = InstructionResult::OutOfGas;
+        return;
+    }
+    interpreter.memory.copy(dst, src, len);
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use crate::interpreter::opcodes;
+
+    use super::*;
+
+    fn build_evm(bytes: &'static [u8]) -> Interpreter {
+        Interpreter::new(Bytes::from_static(bytes))
+    }
+
+    #[test]
+    fn mstore_mload() {
+        let mut evm = build_evm(&[
+            opcodes::PUSH1,
+            0xff,
+            opcodes::PUSH1,
+            0x01,
+            opcodes::MSTORE8,
+            opcodes::PUSH0,
+            opcodes::MLOAD,
+            opcodes::MSIZE,
+        ]);
+        assert_eq!(evm.run(), InstructionResult::Stop);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(32));
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(0xff) << 240);
+        // One word of memory, charged once.
+        assert_eq!(evm.gas.spent(), 3 + 3 + 3 + 3 + 2 + 3 + 2);
+    }
+
+    #[test]
+    fn expansion_gas() {
+        let mut evm = build_evm(&[opcodes::PUSH1, 0x20, opcodes::MLOAD]);
+        evm.run();
+        assert_eq!(evm.memory.len(), 64);
+        assert_eq!(evm.gas.spent(), 3 + 3 + gas::memory_gas(2));
+
+        // A offset that could never be paid for.
+        let mut evm = build_evm(&[opcodes::MLOAD]);
+        evm.stack.push(U256::MAX).unwrap();
+        assert_eq!(evm.run(), InstructionResult::OutOfGas);
+    }
+
+    #[test]
+    fn mcopy() {
+        let mut evm = build_evm(&[
+            opcodes::PUSH1,
+            0x2a,
+            opcodes::PUSH0,
+            opcodes::MSTORE,
+            opcodes::PUSH1,
+            0x20,
+            opcodes::PUSH0,
+            opcodes::PUSH1,
+            0x01,
+            opcodes::MCOPY,
+            opcodes::PUSH1,
+            0x01,
+            opcodes::MLOAD,
+        ]);
+        assert_eq!(evm.run(), InstructionResult::Stop);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(0x2a));
+        assert_eq!(evm.memory.len(), 64);
+    }
+}
@@ -1,20 +1,20 @@
 use ruint::aliases::U256;
 
-use crate::interpreter::Interpreter;
+use crate::interpreter::{host::Host, Interpreter};
 
-pub fn push0(interpreter: &mut Interpreter) {
+pub fn push0(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     if let Err(result) = interpreter.stack.push(U256::ZERO) {
         interpreter.instruction_result = result.into();
     }
 }
 
-pub fn pop(interpreter: &mut Interpreter) {
+pub fn pop(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     if let Err(result) = interpreter.stack.pop() {
         interpreter.instruction_result = result.into();
     }
 }
 
-pub fn push<const N: usize>(interpreter: &mut Interpreter) {
+pub fn push<const N: usize>(interpreter: &mut Interpreter, _host: &mut dyn Host) {
     const { assert!(matches!(N, 1..=32)) }; // Now THAT is a gamer move.
 
     let mut value = [0u8; 32];
@@ -32,6 +32,18 @@ pub fn push<const N: usize>(interpreter: &mut Interpreter) {
     interpreter.pc += N;
 }
 
+pub fn dup<const N: usize>(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    if let Err(result) = interpreter.stack.dup(N) {
+        interpreter.instruction_result = result.into();
+    }
+}
+
+pub fn swap<const N: usize>(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    if let Err(result) = interpreter.stack.swap(N) {
+        interpreter.instruction_result = result.into();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use bytes::Bytes;
@@ -104,4 +116,24 @@ mod tests {
         assert_eq!(evm.stack.len(), 1);
         assert_eq!(evm.stack.pop().unwrap(), U256::from(1));
     }
+
+    #[test]
+    fn dup_swap() {
+        let mut evm = build_evm(
+            &[opcodes::DUP3, opcodes::SWAP2],
+            &[U256::from(1), U256::from(2), U256::from(3)],
+        );
+        assert_eq!(evm.run(), InstructionResult::Stop);
+        let items: Vec<_> = (0..4).map(|_| evm.stack.pop().unwrap()).collect();
+        assert_eq!(
+            items,
+            [U256::from(2), U256::from(1), U256::from(3), U256::from(3)]
+        );
+
+        evm = build_evm(&[opcodes::SWAP1], &[U256::from(1)]);
+        assert_eq!(
+            evm.run(),
+            InstructionResult::StackError(StackError::Underflow)
+        );
+    }
 }
@@ -0,0 +1,211 @@
+use ruint::aliases::U256;
+
+use crate::{
+    domain::primitives,
+    interpreter::{gas, host::Host, InstructionResult, Interpreter},
+};
+
+pub fn keccak256(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let (offset, len) = try_stack!(interpreter, interpreter.stack.pop2());
+    let len = as_usize_or_fail!(interpreter, len);
+    gas!(interpreter, gas::keccak256_cost(len as u64));
+    let (offset, len) = memory_range!(interpreter, offset, U256::from(len));
+    let hash = primitives::keccak256(interpreter.memory.slice(offset, len));
+    try_stack!(
+        interpreter,
+        interpreter.stack.push(U256::from_be_bytes(hash))
+    );
+}
+
+pub fn address(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let address = interpreter.address.into_word();
+    try_stack!(interpreter, interpreter.stack.push(address));
+}
+
+pub fn caller(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let caller = interpreter.caller.into_word();
+    try_stack!(interpreter, interpreter.stack.push(caller));
+}
+
+pub fn callvalue(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let value = interpreter.call_value;
+    try_stack!(interpreter, interpreter.stack.push(value));
+}
+
+/// Reads a word of calldata, zero padded past the end.
+pub fn calldataload(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let offset = try_stack!(interpreter, interpreter.stack.top());
+    let mut word = [0u8; 32];
+    if let Ok(offset) = usize::try_from(*offset) {
+        if offset < interpreter.input.len() {
+            let len = (interpreter.input.len() - offset).min(32);
+            word[..len].copy_from_slice(&interpreter.input[offset..offset + len]);
+        }
+    }
+    *offset = U256::from_be_bytes(word);
+}
+
+pub fn calldatasize(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let len = U256::from(interpreter.input.len());
+    try_stack!(interpreter, interpreter.stack.push(len));
+}
+
+pub fn calldatacopy(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let [memory_offset, data_offset, len] = try_stack!(interpreter, interpreter.stack.popn::<3>());
+    let (memory_offset, len) = copy_memory_range!(interpreter, memory_offset, len);
+    interpreter.memory.set_data(
+        memory_offset,
+        as_usize_saturated!(data_offset),
+        len,
+        &interpreter.input,
+    );
+}
+
+pub fn codesize(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let len = U256::from(interpreter.bytecode.len());
+    try_stack!(interpreter, interpreter.stack.push(len));
+}
+
+pub fn codecopy(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let [memory_offset, code_offset, len] = try_stack!(interpreter, interpreter.stack.popn::<3>());
+    let (memory_offset, len) = copy_memory_range!(interpreter, memory_offset, len);
+    interpreter.memory.set_data(
+        memory_offset,
+        as_usize_saturated!(code_offset),
+        len,
+        interpreter.bytecode.bytes_slice(),
+    );
+}
+
+/// EIP-211
+pub fn returndatasize(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let len = U256::from(interpreter.return_data.len());
+    try_stack!(interpreter, interpreter.stack.push(len));
+}
+
+/// Unlike the other copies, reading past the end of the return data is a error.
+pub fn returndatacopy(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let [memory_offset, data_offset, len] = try_stack!(interpreter, interpreter.stack.popn::<3>());
+    let end = data_offset.saturating_add(len);
+    if end > U256::from(interpreter.return_data.len()) {
+        interpreter.instruction_result = InstructionResult::ReturnDataOutOfBounds;
+        return;
+    }
+    let (memory_offset, len) = copy_memory_range!(interpreter, memory_offset, len);
+    interpreter.memory.set_data(
+        memory_offset,
+        data_offset.to::<usize>(),
+        len,
+        &interpreter.return_data,
+    );
+}
+
+pub fn gas(interpreter: &mut Interpreter, _host: &mut dyn Host) {
+    let remaining = U256::from(interpreter.gas.remaining());
+    try_stack!(interpreter, interpreter.stack.push(remaining));
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use crate::{domain::primitives::KECCAK_EMPTY, interpreter::opcodes};
+
+    use super::*;
+
+    fn build_evm(bytes: &'static [u8], input: &'static [u8]) -> Interpreter {
+        let mut evm = Interpreter::new(Bytes::from_static(bytes));
+        evm.input = Bytes::from_static(input);
+        evm
+    }
+
+    #[test]
+    fn keccak256_empty() {
+        let mut evm = build_evm(
+            &[opcodes::PUSH0, opcodes::PUSH1, 0xff, opcodes::KECCAK256],
+            &[],
+        );
+        assert_eq!(evm.run(), InstructionResult::Stop);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from_be_bytes(KECCAK_EMPTY));
+        assert_eq!(evm.gas.spent(), 2 + 3 + 30);
+    }
+
+    #[test]
+    fn calldata() {
+        let mut evm = build_evm(
+            &[
+                opcodes::CALLDATASIZE,
+                opcodes::PUSH1,
+                0x01,
+                opcodes::CALLDATALOAD,
+            ],
+            &[0xaa, 0xbb, 0xcc],
+        );
+        assert_eq!(evm.run(), InstructionResult::Stop);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(0xbbcc) << 240);
+        assert_eq!(evm.stack.pop().unwrap(), U256::from(3));
+
+        // Copies two bytes from offset 2, the second one past the end of the calldata.
+        evm = build_evm(
+            &[
+                opcodes::PUSH1,
+                0x02,
+                opcodes::PUSH1,
+                0x02,
+                opcodes::PUSH0,
+                opcodes::CALLDATACOPY,
+            ],
+            &[0xaa, 0xbb, 0xcc],
+        );
+        assert_eq!(evm.run(), InstructionResult::Stop);
+        assert_eq!(evm.memory.slice(0, 3), &[0xcc, 0, 0]);
+        assert_eq!(evm.gas.spent(), 3 + 3 + 2 + 3 + 3 + 3);
+    }
+
+    #[test]
+    fn codecopy() {
+        let mut evm = build_evm(
+            &[
+                opcodes::PUSH1,
+                0x20,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::CODECOPY,
+            ],
+            &[],
+        );
+        assert_eq!(evm.run(), InstructionResult::Stop);
+        assert_eq!(evm.memory.slice(0, 6), &[0x60, 0x20, 0x5f, 0x5f, 0x39, 0]);
+    }
+
+    #[test]
+    fn returndata_out_of_bounds() {
+        let mut evm = build_evm(
+            &[
+                opcodes::PUSH1,
+                0x02,
+                opcodes::PUSH0,
+                opcodes::PUSH0,
+                opcodes::RETURNDATACOPY,
+            ],
+            &[],
+        );
+        evm.return_data = Bytes::from_static(&[1, 2]);
+        assert_eq!(evm.run(), InstructionResult::Stop);
+        assert_eq!(evm.memory.slice(0, 2), &[1, 2]);
+
+        evm = build_evm(
+            &[
+                opcodes::PUSH1,
+                0x01,
+                opcodes::PUSH1,
+                0x02,
+                opcodes::PUSH0,
+                opcodes::RETURNDATACOPY,
+            ],
+            &[],
+        );
+        evm.return_data = Bytes::from_static(&[1, 2]);
+        assert_eq!(evm.run(), InstructionResult::ReturnDataOutOfBounds);
+    }
+}
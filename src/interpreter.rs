@@ -1,10 +1,21 @@
+pub mod gas;
+pub mod host;
+pub mod memory;
 pub mod opcodes;
 pub mod stack;
 
 use bytes::Bytes;
+use gas::Gas;
+use host::Host;
+use memory::Memory;
+use ruint::aliases::U256;
 use stack::{Stack, StackError};
 
-use crate::{domain::bytecode::Bytecode, interpreter::opcodes::Opcode};
+use crate::{
+    domain::{bytecode::Bytecode, primitives::Address, spec::SpecId},
+    evm::Evm,
+    interpreter::opcodes::Opcode,
+};
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
 pub enum InstructionResult {
@@ -12,8 +23,55 @@ pub enum InstructionResult {
     #[default]
     Continue,
     Stop,
+    Return,
+    SelfDestruct,
 
+    Revert,
+
+    OutOfGas,
     StackError(StackError),
+    /// The designated INVALID opcode, 0xFE.
+    InvalidFEOpcode,
+    /// Not a opcode, or not one yet in the current fork.
+    OpcodeNotFound,
+    InvalidJump,
+    StateChangeDuringStaticCall,
+    ReturnDataOutOfBounds,
+    CallTooDeep,
+    OutOfFunds,
+    NonceOverflow,
+    CreateCollision,
+    /// EIP-170
+    CreateContractSizeLimit,
+    /// EIP-3541
+    CreateContractStartingWithEF,
+    /// EIP-3860
+    CreateInitCodeSizeLimit,
+    PrecompileError,
+}
+
+impl InstructionResult {
+    /// The frame halted normally and its changes are kept.
+    pub fn is_ok(self) -> bool {
+        matches!(self, Self::Stop | Self::Return | Self::SelfDestruct)
+    }
+
+    pub fn is_revert(self) -> bool {
+        self == Self::Revert
+    }
+
+    /// Exceptional halt, the frame is reverted and all of its gas consumed.
+    pub fn is_error(self) -> bool {
+        !self.is_ok() && !self.is_revert() && self != Self::Continue
+    }
+
+    /// A call or create that failed before any code ran, all the gas it was given is left.
+    pub fn is_aborted(self) -> bool {
+        matches!(
+            self,
+            Self::CallTooDeep | Self::OutOfFunds | Self::NonceOverflow
+        )
+    }
 }
 
 // pub type Result<T> = std::result::Result<T, InterpreterError>;
@@ -22,37 +80,97 @@ pub enum InstructionResult {
 pub struct Interpreter {
     /// Bytecode that instruction result will point to
     pub bytecode: Bytecode,
+    /// `jumpdests[pc]` is true if `pc` is a JUMPDEST and not part of PUSH data.
+    jumpdests: Vec<bool>,
     pub stack: Stack,
+    pub memory: Memory,
     pub pc: usize,
+    pub gas: Gas,
+    pub spec: SpecId,
+    /// Address of the account whose code is executing, storage reads and writes go here.
+    pub address: Address,
+    pub caller: Address,
+    pub call_value: U256,
+    /// The calldata of the frame.
+    pub input: Bytes,
+    /// No state changes allowed (STATICCALL).
+    pub is_static: bool,
+    /// Output of the last sub call, read by RETURNDATASIZE and RETURNDATACOPY.
+    pub return_data: Bytes,
+    /// Set by RETURN and REVERT.
+    pub output: Bytes,
     pub instruction_result: InstructionResult,
 }
 
 impl Interpreter {
     pub fn new(code: Bytes) -> Self {
+        let bytecode = Bytecode::new_legacy(code);
         Self {
-            bytecode: Bytecode::new_legacy(code),
+            jumpdests: bytecode.jumpdests(),
+            bytecode,
             stack: Stack::new(),
+            memory: Memory::new(),
             pc: 0,
+            gas: Gas::new(u64::MAX),
+            spec: SpecId::default(),
+            address: Address::ZERO,
+            caller: Address::ZERO,
+            call_value: U256::ZERO,
+            input: Bytes::new(),
+            is_static: false,
+            return_data: Bytes::new(),
+            output: Bytes::new(),
             instruction_result: InstructionResult::Continue,
         }
     }
 
-    pub fn step(&mut self) {
+    pub fn is_valid_jump(&self, dest: usize) -> bool {
+        self.jumpdests.get(dest).copied().unwrap_or(false)
+    }
+
+    /// Grows memory to hold `new_len` bytes and charges the expansion, returns false if out of
+    /// gas.
+    pub fn resize_memory(&mut self, new_len: usize) -> bool {
+        if new_len <= self.memory.len() {
+            return true;
+        }
+        let current = gas::memory_gas(memory::num_words(self.memory.len() as u64));
+        let new = gas::memory_gas(memory::num_words(new_len as u64));
+        if !self.gas.record_cost(new - current) {
+            return false;
+        }
+        self.memory.resize(new_len);
+        true
+    }
+
+    pub fn step(&mut self, host: &mut dyn Host) {
         if self.pc >= self.bytecode.len() {
             self.instruction_result = InstructionResult::Stop;
         } else {
             let opcode = self.bytecode.bytes_slice()[self.pc];
             self.pc += 1;
             match Opcode::new(opcode) {
-                Some(op) => op.context().instruction(self),
-                None => todo!(),
+                Some(op) if self.spec.is_enabled_in(op.introduced_in()) => {
+                    let context = op.context();
+                    if !self.gas.record_cost(context.static_gas()) {
+                        self.instruction_result = InstructionResult::OutOfGas;
+                        return;
+                    }
+                    context.instruction(self, host)
+                }
+                _ => self.instruction_result = InstructionResult::OpcodeNotFound,
             }
         }
     }
 
+    /// Runs against a empty world state.
     pub fn run(&mut self) -> InstructionResult {
+        self.run_with_host(&mut Evm::default())
+    }
+
+    pub fn run_with_host(&mut self, host: &mut dyn Host) -> InstructionResult {
         while self.instruction_result == InstructionResult::Continue {
-            self.step();
+            self.step(host);
         }
 
         self.instruction_result
@@ -66,7 +184,7 @@ mod tests {
 
     use crate::interpreter::{opcodes, InstructionResult};
 
-    use super::Interpreter;
+    use super::{Gas, Interpreter};
 
     fn build_evm(bytes: &'static [u8]) -> Interpreter {
         let code = Bytes::from_static(bytes);
@@ -110,4 +228,14 @@ mod tests {
         assert_eq!(evm.stack.len(), 1);
         assert_eq!(evm.stack.pop().unwrap(), U256::from(2));
     }
+
+    #[test]
+    fn out_of_gas() {
+        let mut evm = build_evm(&[opcodes::PUSH0, opcodes::PUSH1, 0x01, opcodes::ADD]);
+        evm.gas = Gas::new(7);
+        let instr_res = evm.run();
+        assert_eq!(instr_res, InstructionResult::OutOfGas);
+        assert_eq!(evm.gas.spent(), 5);
+        assert_eq!(evm.pc, 4);
+    }
 }
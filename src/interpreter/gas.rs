@@ -0,0 +1,503 @@
+use crate::domain::{spec::SpecId, state::AccessListItem};
+
+use super::{host::SStoreResult, memory::num_words};
+
+pub const ZERO: u64 = 0;
+pub const BASE: u64 = 2;
+pub const VERYLOW: u64 = 3;
+pub const LOW: u64 = 5;
+pub const MID: u64 = 8;
+pub const HIGH: u64 = 10;
+pub const JUMPDEST: u64 = 1;
+pub const EXP: u64 = 10;
+pub const SELFDESTRUCT: u64 = 5000;
+pub const CREATE: u64 = 32000;
+pub const MEMORY: u64 = 3;
+pub const COPY: u64 = 3;
+pub const KECCAK256: u64 = 30;
+pub const KECCAK256WORD: u64 = 6;
+pub const BLOCKHASH: u64 = 20;
+pub const LOG: u64 = 375;
+pub const LOGDATA: u64 = 8;
+pub const LOGTOPIC: u64 = 375;
+pub const CALLVALUE: u64 = 9000;
+pub const NEWACCOUNT: u64 = 25000;
+pub const CODEDEPOSIT: u64 = 200;
+/// EIP-3860: Limit and meter initcode
+pub const INITCODE_WORD_COST: u64 = 2;
+/// Refunded once per account before London removed it (EIP-3529).
+pub const SELFDESTRUCT_REFUND: i64 = 24000;
+
+// Account and storage access before EIP-2929, as repriced by EIP-150 and EIP-1884.
+pub const BALANCE_FRONTIER: u64 = 20;
+pub const BALANCE_TANGERINE: u64 = 400;
+pub const BALANCE_ISTANBUL: u64 = 700;
+pub const EXTCODE_FRONTIER: u64 = 20;
+pub const EXTCODE_TANGERINE: u64 = 700;
+pub const EXTCODEHASH_CONSTANTINOPLE: u64 = 400;
+pub const EXTCODEHASH_ISTANBUL: u64 = 700;
+pub const CALL_FRONTIER: u64 = 40;
+pub const CALL_TANGERINE: u64 = 700;
+pub const SLOAD_FRONTIER: u64 = 50;
+pub const SLOAD_TANGERINE: u64 = 200;
+pub const SLOAD_ISTANBUL: u64 = 800;
+
+// Per exponent byte of EXP, repriced by EIP-160.
+pub const EXP_BYTE_FRONTIER: u64 = 10;
+pub const EXP_BYTE_SPURIOUS_DRAGON: u64 = 50;
+
+// EIP-2929: Gas cost increases for state access opcodes
+pub const COLD_SLOAD_COST: u64 = 2100;
+pub const COLD_ACCOUNT_ACCESS_COST: u64 = 2600;
+pub const WARM_STORAGE_READ_COST: u64 = 100;
+
+// SSTORE, net metered by EIP-1283 in Constantinople and EIP-2200 from Istanbul on.
+pub const SSTORE_SET: u64 = 20000;
+/// Lowered by the cold SLOAD cost in Berlin (EIP-2929).
+pub const SSTORE_RESET: u64 = 5000;
+pub const SSTORE_CLEARS: i64 = 15000;
+/// EIP-3529: Reduction in refunds
+pub const SSTORE_CLEARS_SCHEDULE: i64 = 4800;
+/// SSTORE fails if less than this is left (EIP-2200).
+pub const CALL_STIPEND: u64 = 2300;
+/// EIP-1153 TLOAD and TSTORE are priced as a warm storage read.
+pub const TRANSIENT: u64 = WARM_STORAGE_READ_COST;
+
+// Intrinsic gas, paid by every transaction before any code runs.
+pub const TRANSACTION: u64 = 21000;
+pub const TRANSACTION_CREATE: u64 = 32000;
+pub const TRANSACTION_ZERO_DATA: u64 = 4;
+pub const TRANSACTION_NON_ZERO_DATA_FRONTIER: u64 = 68;
+/// EIP-2028: Transaction data gas cost reduction
+pub const TRANSACTION_NON_ZERO_DATA: u64 = 16;
+pub const ACCESS_LIST_ADDRESS: u64 = 2400;
+pub const ACCESS_LIST_STORAGE_KEY: u64 = 1900;
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Gas {
+    limit: u64,
+    remaining: u64,
+    /// Can go negative within a frame, e.g. when a cleared slot is set again.
+    refunded: i64,
+}
+
+impl Gas {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            remaining: limit,
+            refunded: 0,
+        }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    pub fn spent(&self) -> u64 {
+        self.limit - self.remaining
+    }
+
+    pub fn refunded(&self) -> i64 {
+        self.refunded
+    }
+
+    /// Returns false, without charging anything, if there is not enough gas left.
+    #[inline]
+    pub fn record_cost(&mut self, cost: u64) -> bool {
+        match self.remaining.checked_sub(cost) {
+            Some(remaining) => {
+                self.remaining = remaining;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn record_refund(&mut self, refund: i64) {
+        self.refunded += refund;
+    }
+
+    /// Gives back gas that was charged but not used, e.g. what a sub call left over.
+    pub fn erase_cost(&mut self, returned: u64) {
+        self.remaining += returned;
+    }
+
+    /// Consumes everything that is left, what a exceptional halt does.
+    pub fn spend_all(&mut self) {
+        self.remaining = 0;
+    }
+}
+
+/// The total cost of `words` of memory, the expansion cost is the difference of two of these.
+pub const fn memory_gas(words: u64) -> u64 {
+    MEMORY
+        .saturating_mul(words)
+        .saturating_add(words.saturating_mul(words) / 512)
+}
+
+/// Per word cost of the copy instructions, the static part is charged up front.
+pub const fn copy_cost(len: u64) -> u64 {
+    COPY.saturating_mul(num_words(len))
+}
+
+pub const fn keccak256_cost(len: u64) -> u64 {
+    KECCAK256WORD.saturating_mul(num_words(len))
+}
+
+/// The dynamic part of LOG, the topics are in the static cost.
+pub const fn log_cost(len: u64) -> u64 {
+    LOGDATA.saturating_mul(len)
+}
+
+pub const fn initcode_cost(len: u64) -> u64 {
+    INITCODE_WORD_COST.saturating_mul(num_words(len))
+}
+
+/// The gas a transaction pays up front for its calldata, kind and access list.
+pub fn intrinsic_gas(
+    spec: SpecId,
+    data: &[u8],
+    is_create: bool,
+    access_list: &[AccessListItem],
+) -> u64 {
+    let zero_bytes = data.iter().filter(|byte| **byte == 0).count() as u64;
+    let non_zero_bytes = data.len() as u64 - zero_bytes;
+    let non_zero_cost = if spec.is_enabled_in(SpecId::Istanbul) {
+        TRANSACTION_NON_ZERO_DATA
+    } else {
+        TRANSACTION_NON_ZERO_DATA_FRONTIER
+    };
+
+    let mut gas = TRANSACTION + zero_bytes * TRANSACTION_ZERO_DATA + non_zero_bytes * non_zero_cost;
+    if is_create {
+        // EIP-2: Homestead made contract creation more expensive.
+        if spec.is_enabled_in(SpecId::Homestead) {
+            gas += TRANSACTION_CREATE;
+        }
+        if spec.is_enabled_in(SpecId::Shanghai) {
+            gas += initcode_cost(data.len() as u64);
+        }
+    }
+    for item in access_list {
+        gas += ACCESS_LIST_ADDRESS + item.storage_keys.len() as u64 * ACCESS_LIST_STORAGE_KEY;
+    }
+    gas
+}
+
+/// EIP-2929 account access, for BALANCE, EXTCODESIZE, EXTCODEHASH, EXTCODECOPY and the CALL
+/// family from Berlin on.
+pub const fn account_access_cost(is_cold: bool) -> u64 {
+    if is_cold {
+        COLD_ACCOUNT_ACCESS_COST
+    } else {
+        WARM_STORAGE_READ_COST
+    }
+}
+
+pub fn balance_cost(spec: SpecId, is_cold: bool) -> u64 {
+    if spec.is_enabled_in(SpecId::Berlin) {
+        account_access_cost(is_cold)
+    } else if spec.is_enabled_in(SpecId::Istanbul) {
+        BALANCE_ISTANBUL
+    } else if spec.is_enabled_in(SpecId::TangerineWhistle) {
+        BALANCE_TANGERINE
+    } else {
+        BALANCE_FRONTIER
+    }
+}
+
+/// EXTCODESIZE and EXTCODECOPY, the copy is charged on top.
+pub fn extcode_cost(spec: SpecId, is_cold: bool) -> u64 {
+    if spec.is_enabled_in(SpecId::Berlin) {
+        account_access_cost(is_cold)
+    } else if spec.is_enabled_in(SpecId::TangerineWhistle) {
+        EXTCODE_TANGERINE
+    } else {
+        EXTCODE_FRONTIER
+    }
+}
+
+pub fn extcodehash_cost(spec: SpecId, is_cold: bool) -> u64 {
+    if spec.is_enabled_in(SpecId::Berlin) {
+        account_access_cost(is_cold)
+    } else if spec.is_enabled_in(SpecId::Istanbul) {
+        EXTCODEHASH_ISTANBUL
+    } else {
+        EXTCODEHASH_CONSTANTINOPLE
+    }
+}
+
+/// The CALL family without the value transfer and new account costs.
+pub fn call_cost(spec: SpecId, is_cold: bool) -> u64 {
+    if spec.is_enabled_in(SpecId::Berlin) {
+        account_access_cost(is_cold)
+    } else if spec.is_enabled_in(SpecId::TangerineWhistle) {
+        CALL_TANGERINE
+    } else {
+        CALL_FRONTIER
+    }
+}
+
+pub fn sload_cost(spec: SpecId, is_cold: bool) -> u64 {
+    if spec.is_enabled_in(SpecId::Berlin) {
+        if is_cold {
+            COLD_SLOAD_COST
+        } else {
+            WARM_STORAGE_READ_COST
+        }
+    } else if spec.is_enabled_in(SpecId::Istanbul) {
+        SLOAD_ISTANBUL
+    } else if spec.is_enabled_in(SpecId::TangerineWhistle) {
+        SLOAD_TANGERINE
+    } else {
+        SLOAD_FRONTIER
+    }
+}
+
+/// EIP-1283 in Constantinople, which Petersburg took out again, and EIP-2200 from Istanbul on.
+pub fn is_sstore_net_metered(spec: SpecId) -> bool {
+    spec == SpecId::Constantinople || spec.is_enabled_in(SpecId::Istanbul)
+}
+
+/// What a net metered SSTORE costs when it leaves the slot as it is, a warm SLOAD.
+fn sstore_noop_cost(spec: SpecId) -> u64 {
+    if spec.is_enabled_in(SpecId::Berlin) {
+        WARM_STORAGE_READ_COST
+    } else {
+        sload_cost(spec, false)
+    }
+}
+
+fn sstore_reset_cost(spec: SpecId) -> u64 {
+    if spec.is_enabled_in(SpecId::Berlin) {
+        SSTORE_RESET - COLD_SLOAD_COST
+    } else {
+        SSTORE_RESET
+    }
+}
+
+/// The dynamic part of EXP, the static `EXP` is charged up front.
+pub fn exp_cost(spec: SpecId, exponent_bytes: u64) -> u64 {
+    let byte_cost = if spec.is_enabled_in(SpecId::SpuriousDragon) {
+        EXP_BYTE_SPURIOUS_DRAGON
+    } else {
+        EXP_BYTE_FRONTIER
+    };
+    byte_cost * exponent_bytes
+}
+
+pub fn sstore_cost(spec: SpecId, result: &SStoreResult, is_cold: bool) -> u64 {
+    if !is_sstore_net_metered(spec) {
+        return if result.present.is_zero() && !result.new.is_zero() {
+            SSTORE_SET
+        } else {
+            SSTORE_RESET
+        };
+    }
+
+    let cost = if result.present == result.new || result.original != result.present {
+        sstore_noop_cost(spec)
+    } else if result.original.is_zero() {
+        SSTORE_SET
+    } else {
+        sstore_reset_cost(spec)
+    };
+
+    if is_cold && spec.is_enabled_in(SpecId::Berlin) {
+        cost + COLD_SLOAD_COST
+    } else {
+        cost
+    }
+}
+
+pub fn sstore_refund(spec: SpecId, result: &SStoreResult) -> i64 {
+    let SStoreResult {
+        original,
+        present,
+        new,
+    } = result;
+    let clears = if spec.is_enabled_in(SpecId::London) {
+        SSTORE_CLEARS_SCHEDULE
+    } else {
+        SSTORE_CLEARS
+    };
+    if !is_sstore_net_metered(spec) {
+        return if !present.is_zero() && new.is_zero() {
+            clears
+        } else {
+            0
+        };
+    }
+    if present == new {
+        return 0;
+    }
+
+    if original == present {
+        if !original.is_zero() && new.is_zero() {
+            return clears;
+        }
+        return 0;
+    }
+
+    let mut refund = 0;
+    if !original.is_zero() {
+        if present.is_zero() {
+            refund -= clears;
+        } else if new.is_zero() {
+            refund += clears;
+        }
+    }
+    if original == new {
+        let noop = sstore_noop_cost(spec);
+        refund += if original.is_zero() {
+            (SSTORE_SET - noop) as i64
+        } else {
+            (sstore_reset_cost(spec) - noop) as i64
+        };
+    }
+    refund
+}
+
+#[cfg(test)]
+mod tests {
+    use ruint::aliases::U256;
+
+    use super::*;
+
+    fn sstore(original: u64, present: u64, new: u64) -> SStoreResult {
+        SStoreResult {
+            original: U256::from(original),
+            present: U256::from(present),
+            new: U256::from(new),
+        }
+    }
+
+    #[test]
+    fn record_cost() {
+        let mut gas = Gas::new(10);
+        assert!(gas.record_cost(7));
+        assert!(!gas.record_cost(4));
+        assert_eq!(gas.remaining(), 3);
+        assert_eq!(gas.spent(), 7);
+    }
+
+    #[test]
+    fn memory_expansion() {
+        assert_eq!(memory_gas(1), 3);
+        assert_eq!(memory_gas(32), 98);
+        assert_eq!(memory_gas(1024), 3 * 1024 + 2048);
+    }
+
+    #[test]
+    fn intrinsic() {
+        let data = [0, 1, 0, 2];
+        assert_eq!(
+            intrinsic_gas(SpecId::Cancun, &data, false, &[]),
+            21000 + 8 + 32
+        );
+        assert_eq!(
+            intrinsic_gas(SpecId::Byzantium, &data, false, &[]),
+            21000 + 8 + 136
+        );
+        assert_eq!(intrinsic_gas(SpecId::Frontier, &[], true, &[]), 21000);
+        assert_eq!(
+            intrinsic_gas(SpecId::Cancun, &data, true, &[]),
+            53000 + 40 + 2
+        );
+
+        let access_list = [AccessListItem {
+            address: Default::default(),
+            storage_keys: vec![U256::ZERO, U256::from(1)],
+        }];
+        assert_eq!(
+            intrinsic_gas(SpecId::Berlin, &[], false, &access_list),
+            21000 + 2400 + 2 * 1900
+        );
+    }
+
+    #[test]
+    fn account_access() {
+        assert_eq!(balance_cost(SpecId::Frontier, true), 20);
+        assert_eq!(balance_cost(SpecId::TangerineWhistle, true), 400);
+        assert_eq!(balance_cost(SpecId::Istanbul, true), 700);
+        assert_eq!(balance_cost(SpecId::Berlin, true), 2600);
+        assert_eq!(balance_cost(SpecId::Berlin, false), 100);
+        assert_eq!(extcode_cost(SpecId::Homestead, true), 20);
+        assert_eq!(extcode_cost(SpecId::Istanbul, true), 700);
+        assert_eq!(extcodehash_cost(SpecId::Constantinople, true), 400);
+        assert_eq!(extcodehash_cost(SpecId::Istanbul, true), 700);
+        assert_eq!(call_cost(SpecId::Frontier, true), 40);
+        assert_eq!(call_cost(SpecId::Byzantium, true), 700);
+        assert_eq!(call_cost(SpecId::Cancun, false), 100);
+        assert_eq!(sload_cost(SpecId::Frontier, true), 50);
+        assert_eq!(sload_cost(SpecId::Petersburg, true), 200);
+        assert_eq!(sload_cost(SpecId::Istanbul, true), 800);
+        assert_eq!(sload_cost(SpecId::London, true), 2100);
+    }
+
+    #[test]
+    fn exp_bytes() {
+        assert_eq!(exp_cost(SpecId::Frontier, 0), 0);
+        assert_eq!(exp_cost(SpecId::TangerineWhistle, 2), 20);
+        assert_eq!(exp_cost(SpecId::SpuriousDragon, 2), 100);
+        assert_eq!(exp_cost(SpecId::Cancun, 32), 1600);
+    }
+
+    #[test]
+    fn sstore_frontier() {
+        // Only the present and new value count, whatever was there before the transaction.
+        let spec = SpecId::Petersburg;
+        assert_eq!(sstore_cost(spec, &sstore(1, 0, 1), true), 20000);
+        assert_eq!(sstore_cost(spec, &sstore(0, 1, 2), true), 5000);
+        assert_eq!(sstore_cost(spec, &sstore(0, 0, 0), true), 5000);
+        assert_eq!(sstore_refund(spec, &sstore(0, 1, 0)), 15000);
+        assert_eq!(sstore_refund(spec, &sstore(1, 1, 1)), 0);
+    }
+
+    #[test]
+    fn sstore_eip2200() {
+        // The cases of the EIP-2200 test table.
+        let spec = SpecId::Istanbul;
+        assert_eq!(sstore_cost(spec, &sstore(0, 0, 0), true), 800);
+        assert_eq!(sstore_cost(spec, &sstore(0, 0, 1), true), 20000);
+        assert_eq!(sstore_cost(spec, &sstore(1, 1, 0), true), 5000);
+        assert_eq!(sstore_refund(spec, &sstore(1, 1, 0)), 15000);
+        assert_eq!(sstore_cost(spec, &sstore(1, 0, 1), false), 800);
+        assert_eq!(sstore_refund(spec, &sstore(1, 0, 1)), -15000 + 4200);
+        assert_eq!(sstore_refund(spec, &sstore(0, 1, 0)), 19200);
+        // EIP-1283 in Constantinople, with a SLOAD of 200.
+        assert_eq!(
+            sstore_refund(SpecId::Constantinople, &sstore(0, 1, 0)),
+            19800
+        );
+    }
+
+    #[test]
+    fn sstore_eip2929() {
+        // Berlin keeps the refund of 15000 for clearing a slot.
+        let spec = SpecId::Berlin;
+        assert_eq!(sstore_cost(spec, &sstore(1, 1, 0), true), 5000);
+        assert_eq!(sstore_cost(spec, &sstore(1, 1, 0), false), 2900);
+        assert_eq!(sstore_refund(spec, &sstore(1, 1, 0)), 15000);
+        assert_eq!(sstore_refund(spec, &sstore(1, 0, 1)), -15000 + 2800);
+    }
+
+    #[test]
+    fn sstore_eip3529() {
+        // The cases of the EIP-3529 test table, cold slots.
+        let spec = SpecId::London;
+        assert_eq!(sstore_cost(spec, &sstore(0, 0, 0), true), 2200);
+        assert_eq!(sstore_cost(spec, &sstore(0, 0, 1), true), 22100);
+        assert_eq!(sstore_cost(spec, &sstore(1, 1, 0), true), 5000);
+        assert_eq!(sstore_refund(spec, &sstore(1, 1, 0)), 4800);
+        assert_eq!(sstore_cost(spec, &sstore(1, 0, 1), false), 100);
+        assert_eq!(sstore_refund(spec, &sstore(1, 0, 1)), -4800 + 2800);
+        assert_eq!(sstore_refund(spec, &sstore(0, 1, 0)), 19900);
+        assert_eq!(sstore_refund(spec, &sstore(1, 2, 0)), 4800);
+    }
+}
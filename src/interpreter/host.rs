@@ -0,0 +1,136 @@
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use crate::domain::{
+    env::Env,
+    primitives::{Address, B256},
+    state::Log,
+};
+
+use super::{gas::Gas, InstructionResult};
+
+/// A value loaded from the state, along with whether the access was cold (EIP-2929).
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateLoad<T> {
+    pub data: T,
+    pub is_cold: bool,
+}
+
+/// The three values EIP-2200 prices a SSTORE by.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SStoreResult {
+    /// Value at the start of the transaction.
+    pub original: U256,
+    /// Value before this SSTORE.
+    pub present: U256,
+    pub new: U256,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SelfDestructResult {
+    pub had_value: bool,
+    /// The beneficiary is empty, or before EIP-161 does not exist, which costs a new account.
+    pub target_is_empty: bool,
+    pub previously_destroyed: bool,
+}
+
+/// The opcode that made a call, the transaction itself counts as a CALL.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum CallKind {
+    #[default]
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CallInputs {
+    pub kind: CallKind,
+    pub input: Bytes,
+    pub gas_limit: u64,
+    /// Account whose storage and balance the callee runs against.
+    pub target_address: Address,
+    /// Account whose code runs, differs from `target_address` for CALLCODE and DELEGATECALL.
+    pub code_address: Address,
+    pub caller: Address,
+    /// CALLVALUE of the callee.
+    pub value: U256,
+    /// Whether `value` moves from the caller to the target, false for DELEGATECALL.
+    pub transfers_value: bool,
+    pub is_static: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallOutcome {
+    pub result: InstructionResult,
+    /// The gas of the callee, what is left of it goes back to the caller.
+    pub gas: Gas,
+    pub output: Bytes,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum CreateScheme {
+    #[default]
+    Create,
+    Create2 {
+        salt: U256,
+    },
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CreateInputs {
+    pub caller: Address,
+    pub scheme: CreateScheme,
+    pub value: U256,
+    pub init_code: Bytes,
+    pub gas_limit: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreateOutcome {
+    pub result: InstructionResult,
+    /// Set if the contract was deployed.
+    pub address: Option<Address>,
+    pub gas: Gas,
+    /// The revert data, empty on success.
+    pub output: Bytes,
+}
+
+/// The interpreters view of the world outside of the current frame.
+pub trait Host {
+    fn env(&self) -> &Env;
+
+    /// Warms the account, `data` is true if it is empty (EIP-161).
+    fn load_account(&mut self, address: Address) -> StateLoad<bool>;
+
+    /// Hash of one of the 256 most recent blocks, the caller checks the range.
+    fn block_hash(&mut self, number: u64) -> B256;
+
+    fn balance(&mut self, address: Address) -> StateLoad<U256>;
+
+    fn code(&mut self, address: Address) -> StateLoad<Bytes>;
+
+    /// Zero for accounts that are empty or do not exist (EIP-1052).
+    fn code_hash(&mut self, address: Address) -> StateLoad<B256>;
+
+    fn sload(&mut self, address: Address, key: U256) -> StateLoad<U256>;
+
+    fn sstore(&mut self, address: Address, key: U256, value: U256) -> StateLoad<SStoreResult>;
+
+    fn tload(&mut self, address: Address, key: U256) -> U256;
+
+    fn tstore(&mut self, address: Address, key: U256, value: U256);
+
+    fn log(&mut self, log: Log);
+
+    /// Moves the balance of `address` to `target` and schedules `address` for removal, where the
+    /// fork allows it (EIP-6780).
+    fn selfdestruct(&mut self, address: Address, target: Address) -> StateLoad<SelfDestructResult>;
+
+    /// Runs a sub call to completion.
+    fn call(&mut self, inputs: CallInputs) -> CallOutcome;
+
+    /// Runs init code to completion and deploys what it returns.
+    fn create(&mut self, inputs: CreateInputs) -> CreateOutcome;
+}
@@ -0,0 +1,90 @@
+/// The byte addressed memory of a frame, always a multiple of 32 bytes long.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Memory {
+    data: Vec<u8>,
+}
+
+/// The number of 32 byte words needed to hold `len` bytes.
+pub const fn num_words(len: u64) -> u64 {
+    len.div_ceil(32)
+}
+
+impl Memory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Grows the memory to `new_len` rounded up to a whole word, new bytes are zero.
+    pub fn resize(&mut self, new_len: usize) {
+        let new_len = new_len.div_ceil(32) * 32;
+        if new_len > self.data.len() {
+            self.data.resize(new_len, 0);
+        }
+    }
+
+    /// Panics if the range is outside of the memory, it should be resized first.
+    pub fn slice(&self, offset: usize, len: usize) -> &[u8] {
+        &self.data[offset..offset + len]
+    }
+
+    pub fn set(&mut self, offset: usize, value: &[u8]) {
+        self.data[offset..offset + value.len()].copy_from_slice(value);
+    }
+
+    /// Copies `len` bytes of `data` starting at `data_offset` to `offset`, whatever lies past the
+    /// end of `data` is written as zero.
+    pub fn set_data(&mut self, offset: usize, data_offset: usize, len: usize, data: &[u8]) {
+        let target = &mut self.data[offset..offset + len];
+        if data_offset >= data.len() {
+            target.fill(0);
+            return;
+        }
+        let available = (data.len() - data_offset).min(len);
+        target[..available].copy_from_slice(&data[data_offset..data_offset + available]);
+        target[available..].fill(0);
+    }
+
+    /// Copies within memory, the ranges can overlap (MCOPY).
+    pub fn copy(&mut self, dst: usize, src: usize, len: usize) {
+        self.data.copy_within(src..src + len, dst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resize_to_words() {
+        let mut memory = Memory::new();
+        memory.resize(1);
+        assert_eq!(memory.len(), 32);
+        memory.resize(33);
+        assert_eq!(memory.len(), 64);
+        memory.resize(10);
+        assert_eq!(memory.len(), 64);
+    }
+
+    #[test]
+    fn set_data_zero_pads() {
+        let mut memory = Memory::new();
+        memory.resize(32);
+        memory.set(0, &[0xff; 32]);
+        memory.set_data(1, 2, 4, &[1, 2, 3, 4]);
+        assert_eq!(memory.slice(0, 6), &[0xff, 3, 4, 0, 0, 0xff]);
+
+        memory.set_data(0, 100, 2, &[1, 2, 3, 4]);
+        assert_eq!(memory.slice(0, 3), &[0, 0, 4]);
+
+        memory.copy(1, 4, 3);
+        assert_eq!(memory.slice(0, 5), &[0, 0, 0xff, 0xff, 0]);
+    }
+}
@@ -1,6 +1,11 @@
-use crate::instructions::{arithmetic, bitwise, comparison, stack};
+use crate::{
+    domain::spec::SpecId,
+    instructions::{
+        arithmetic, bitwise, comparison, contract, control, host, host_env, memory, stack, system,
+    },
+};
 
-use super::Interpreter;
+use super::{gas, host::Host, Interpreter};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C)]
@@ -17,18 +22,37 @@ impl Opcode {
     pub fn context(self) -> OpcodeContext {
         OPCODE_JUMPTABLE[self.0 as usize].unwrap()
     }
+
+    pub fn get(self) -> u8 {
+        self.0
+    }
+
+    /// The fork that added the opcode, before it the byte is not a instruction.
+    pub fn introduced_in(self) -> SpecId {
+        match self.0 {
+            DELEGATECALL => SpecId::Homestead,
+            RETURNDATASIZE | RETURNDATACOPY | STATICCALL | REVERT => SpecId::Byzantium,
+            SHL | SHR | SAR | CREATE2 | EXTCODEHASH => SpecId::Constantinople,
+            CHAINID | SELFBALANCE => SpecId::Istanbul,
+            BASEFEE => SpecId::London,
+            PUSH0 => SpecId::Shanghai,
+            BLOBHASH | BLOBBASEFEE | TLOAD | TSTORE | MCOPY => SpecId::Cancun,
+            _ => SpecId::Frontier,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
 pub struct OpcodeContext {
     inputs: u8,
     outputs: u8,
-    instruction: fn(&mut Interpreter),
+    static_gas: u64,
+    instruction: fn(&mut Interpreter, &mut dyn Host),
 }
 
 impl OpcodeContext {
-    pub fn instruction(&self, interpreter: &mut Interpreter) {
-        (self.instruction)(interpreter)
+    pub fn instruction(&self, interpreter: &mut Interpreter, host: &mut dyn Host) {
+        (self.instruction)(interpreter, host)
     }
 
     /// Returns the additional items placed on the stack (α)
@@ -40,16 +64,18 @@ impl OpcodeContext {
     pub fn outputs(&self) -> u8 {
         self.outputs
     }
-}
 
-fn todo_instr(_: &mut Interpreter) {
-    todo!()
+    /// Returns the gas charged before the instruction runs, dynamic costs are charged by the
+    /// instruction itself.
+    pub fn static_gas(&self) -> u64 {
+        self.static_gas
+    }
 }
 
 macro_rules! opcodes {
     (
         $(
-            $name:ident, $value:literal, $instr:expr, $inputs:expr, $outputs:expr;
+            $name:ident, $value:literal, $instr:expr, $inputs:expr, $outputs:expr, $gas:expr;
         )*
     ) => {
         $(
@@ -63,6 +89,7 @@ macro_rules! opcodes {
                 table[$value] = Some(OpcodeContext {
                     inputs: $inputs,
                     outputs: $outputs,
+                    static_gas: $gas,
                     instruction: $instr,
                 });
             )*
@@ -85,167 +112,173 @@ macro_rules! opcodes {
 
 opcodes! {
     // 10s: Comparison & Bitwise Logic Operations
-    STOP,           0x00, todo_instr, 0, 0;
-    ADD,            0x01, arithmetic::add, 2, 1;
-    MUL,            0x02, arithmetic::mul, 2, 1;
-    SUB,            0x03, arithmetic::sub, 2, 1;
-    DIV,            0x04, arithmetic::div, 2, 1;
-    SDIV,           0x05, todo_instr, 2, 1;
-    MOD,            0x06, arithmetic::rem, 2, 1;
-    SMOD,           0x07, todo_instr, 2, 1;
-    ADDMOD,         0x08, arithmetic::addmod, 3, 1;
-    MULMOD,         0x09, arithmetic::mulmod, 3, 1;
-    EXP,            0x0a, arithmetic::exp, 2, 1;
-    SIGNEXTEND,     0x0b, todo_instr, 2, 1;
-    LT,             0x10, comparison::lt, 2, 1;
-    GT,             0x11, comparison::gt, 2, 1;
-    SLT,            0x12, todo_instr, 2, 1;
-    SGT,            0x13, todo_instr, 2, 1;
-    EQ,             0x14, comparison::eq, 2, 1;
-    ISZERO,         0x15, comparison::iszero, 1, 1;
-    AND,            0x16, bitwise::and, 2, 1;
-    OR,             0x17, bitwise::or, 2, 1;
-    XOR,            0x18, bitwise::xor, 2, 1;
-    NOT,            0x19, bitwise::not, 1, 1;
-    BYTE,           0x1a, todo_instr, 2, 1;
-    SHL,            0x1b, bitwise::shl, 2, 1;
-    SHR,            0x1c, todo_instr, 2, 1;
-    SAR,            0x1d, todo_instr, 2, 1;
+    STOP,           0x00, control::stop, 0, 0, gas::ZERO;
+    ADD,            0x01, arithmetic::add, 2, 1, gas::VERYLOW;
+    MUL,            0x02, arithmetic::mul, 2, 1, gas::LOW;
+    SUB,            0x03, arithmetic::sub, 2, 1, gas::VERYLOW;
+    DIV,            0x04, arithmetic::div, 2, 1, gas::LOW;
+    SDIV,           0x05, arithmetic::sdiv, 2, 1, gas::LOW;
+    MOD,            0x06, arithmetic::rem, 2, 1, gas::LOW;
+    SMOD,           0x07, arithmetic::smod, 2, 1, gas::LOW;
+    ADDMOD,         0x08, arithmetic::addmod, 3, 1, gas::MID;
+    MULMOD,         0x09, arithmetic::mulmod, 3, 1, gas::MID;
+    EXP,            0x0a, arithmetic::exp, 2, 1, gas::EXP;
+    SIGNEXTEND,     0x0b, arithmetic::signextend, 2, 1, gas::LOW;
+    LT,             0x10, comparison::lt, 2, 1, gas::VERYLOW;
+    GT,             0x11, comparison::gt, 2, 1, gas::VERYLOW;
+    SLT,            0x12, comparison::slt, 2, 1, gas::VERYLOW;
+    SGT,            0x13, comparison::sgt, 2, 1, gas::VERYLOW;
+    EQ,             0x14, comparison::eq, 2, 1, gas::VERYLOW;
+    ISZERO,         0x15, comparison::iszero, 1, 1, gas::VERYLOW;
+    AND,            0x16, bitwise::and, 2, 1, gas::VERYLOW;
+    OR,             0x17, bitwise::or, 2, 1, gas::VERYLOW;
+    XOR,            0x18, bitwise::xor, 2, 1, gas::VERYLOW;
+    NOT,            0x19, bitwise::not, 1, 1, gas::VERYLOW;
+    BYTE,           0x1a, bitwise::byte, 2, 1, gas::VERYLOW;
+    SHL,            0x1b, bitwise::shl, 2, 1, gas::VERYLOW;
+    SHR,            0x1c, bitwise::shr, 2, 1, gas::VERYLOW;
+    SAR,            0x1d, bitwise::sar, 2, 1, gas::VERYLOW;
 
     // 20s: KECCAK256 (sometimes refered to as SHA3)
-    KECCAK256,      0x20, todo_instr, 2, 1;
+    KECCAK256,      0x20, system::keccak256, 2, 1, gas::KECCAK256;
 
     // 30s: Environmental Information
-    ADRESS,         0x30, todo_instr, 0, 1;
-    BALANCE,        0x31, todo_instr, 1, 1;
-    ORIGIN,         0x32, todo_instr, 0, 1;
-    CALLER,         0x33, todo_instr, 0, 1;
-    CALLVALUE,      0x34, todo_instr, 0, 1;
-    CALLDATALOAD,   0x35, todo_instr, 1, 1;
-    CALLDATASIZE,   0x36, todo_instr, 1, 1;
-    CALLDATACOPY,   0x37, todo_instr, 3, 0;
-    CODESIZE,       0x38, todo_instr, 0, 1;
-    CODECOPY,       0x39, todo_instr, 3, 0;
-    GASPRICE,       0x3a, todo_instr, 0, 1;
-    EXTCODESIZE,    0x3b, todo_instr, 1, 1;
-    EXTCODECOPY,    0x3c, todo_instr, 4, 0;
-    RETURNDATASIZE, 0x3d, todo_instr, 0, 1;
-    RETURNDATACOPY, 0x3e, todo_instr, 3, 0;
-    EXTCODEHASH,    0x3f, todo_instr, 1, 1;
+    ADDRESS,        0x30, system::address, 0, 1, gas::BASE;
+    BALANCE,        0x31, host::balance, 1, 1, gas::ZERO;
+    ORIGIN,         0x32, host_env::origin, 0, 1, gas::BASE;
+    CALLER,         0x33, system::caller, 0, 1, gas::BASE;
+    CALLVALUE,      0x34, system::callvalue, 0, 1, gas::BASE;
+    CALLDATALOAD,   0x35, system::calldataload, 1, 1, gas::VERYLOW;
+    CALLDATASIZE,   0x36, system::calldatasize, 0, 1, gas::BASE;
+    CALLDATACOPY,   0x37, system::calldatacopy, 3, 0, gas::VERYLOW;
+    CODESIZE,       0x38, system::codesize, 0, 1, gas::BASE;
+    CODECOPY,       0x39, system::codecopy, 3, 0, gas::VERYLOW;
+    GASPRICE,       0x3a, host_env::gasprice, 0, 1, gas::BASE;
+    EXTCODESIZE,    0x3b, host::extcodesize, 1, 1, gas::ZERO;
+    EXTCODECOPY,    0x3c, host::extcodecopy, 4, 0, gas::ZERO;
+    RETURNDATASIZE, 0x3d, system::returndatasize, 0, 1, gas::BASE;
+    RETURNDATACOPY, 0x3e, system::returndatacopy, 3, 0, gas::VERYLOW;
+    EXTCODEHASH,    0x3f, host::extcodehash, 1, 1, gas::ZERO;
 
     // 40s: Block Information
-    BLOCKHASH,      0x40, todo_instr, 1, 1;
-    COINBASE,       0x41, todo_instr, 0, 1;
-    TIMESTAMP,      0x42, todo_instr, 0, 1;
-    NUMBER,         0x43, todo_instr, 0, 1;
-    PREVRANDAO,     0x44, todo_instr, 0, 1;
-    GASLIMIT,       0x45, todo_instr, 0, 1;
-    CHAINID,        0x46, todo_instr, 0, 1;
-    SELFBALANCE,    0x47, todo_instr, 0, 1;
-    BASEFEE,        0x48, todo_instr, 0, 1;
+    BLOCKHASH,      0x40, host::blockhash, 1, 1, gas::BLOCKHASH;
+    COINBASE,       0x41, host_env::coinbase, 0, 1, gas::BASE;
+    TIMESTAMP,      0x42, host_env::timestamp, 0, 1, gas::BASE;
+    NUMBER,         0x43, host_env::number, 0, 1, gas::BASE;
+    PREVRANDAO,     0x44, host_env::prevrandao, 0, 1, gas::BASE;
+    GASLIMIT,       0x45, host_env::gaslimit, 0, 1, gas::BASE;
+    CHAINID,        0x46, host_env::chainid, 0, 1, gas::BASE;
+    SELFBALANCE,    0x47, host::selfbalance, 0, 1, gas::LOW;
+    BASEFEE,        0x48, host_env::basefee, 0, 1, gas::BASE;
+    BLOBHASH,       0x49, host_env::blobhash, 1, 1, gas::VERYLOW;
+    BLOBBASEFEE,    0x4a, host_env::blobbasefee, 0, 1, gas::BASE;
 
     // 50s: Stack, Memory, Storage and Flow Operations
-    POP,            0x50, stack::pop, 1, 0;
-    MLOAD,          0x51, todo_instr, 1, 1;
-    MSTORE,         0x52, todo_instr, 2, 0;
-    SLOAD,          0x54, todo_instr, 1, 1;
-    SSTORE,         0x55, todo_instr, 2, 0;
-    JUMP,           0x56, todo_instr, 1, 0;
-    JUMPI,          0x57, todo_instr, 2, 0;
-    PC,             0x58, todo_instr, 0, 1;
-    MSIZE,          0x59, todo_instr, 0, 1;
-    GAS,            0x5a, todo_instr, 0, 1;
-    JUMPDEST,       0x5b, todo_instr, 0, 0;
+    POP,            0x50, stack::pop, 1, 0, gas::BASE;
+    MLOAD,          0x51, memory::mload, 1, 1, gas::VERYLOW;
+    MSTORE,         0x52, memory::mstore, 2, 0, gas::VERYLOW;
+    MSTORE8,        0x53, memory::mstore8, 2, 0, gas::VERYLOW;
+    SLOAD,          0x54, host::sload, 1, 1, gas::ZERO;
+    SSTORE,         0x55, host::sstore, 2, 0, gas::ZERO;
+    JUMP,           0x56, control::jump, 1, 0, gas::MID;
+    JUMPI,          0x57, control::jumpi, 2, 0, gas::HIGH;
+    PC,             0x58, control::pc, 0, 1, gas::BASE;
+    MSIZE,          0x59, memory::msize, 0, 1, gas::BASE;
+    GAS,            0x5a, system::gas, 0, 1, gas::BASE;
+    JUMPDEST,       0x5b, control::jumpdest, 0, 0, gas::JUMPDEST;
+    TLOAD,          0x5c, host::tload, 1, 1, gas::TRANSIENT;
+    TSTORE,         0x5d, host::tstore, 2, 0, gas::TRANSIENT;
+    MCOPY,          0x5e, memory::mcopy, 3, 0, gas::VERYLOW;
 
     // 5f, 60s & 70s: Push Operations
-    PUSH0,          0x5f, stack::push0, 0, 1;
-    PUSH1,          0x60, stack::push::<1>, 0, 1;
-    PUSH2,          0x61, stack::push::<2>, 0, 1;
-    PUSH3,          0x62, stack::push::<3>, 0, 1;
-    PUSH4,          0x63, stack::push::<4>, 0, 1;
-    PUSH5,          0x64, stack::push::<5>, 0, 1;
-    PUSH6,          0x65, stack::push::<6>, 0, 1;
-    PUSH7,          0x66, stack::push::<7>, 0, 1;
-    PUSH8,          0x67, stack::push::<8>, 0, 1;
-    PUSH9,          0x68, stack::push::<9>, 0, 1;
-    PUSH10,         0x69, stack::push::<10>, 0, 1;
-    PUSH11,         0x6a, stack::push::<11>, 0, 1;
-    PUSH12,         0x6b, stack::push::<12>, 0, 1;
-    PUSH13,         0x6c, stack::push::<13>, 0, 1;
-    PUSH14,         0x6d, stack::push::<14>, 0, 1;
-    PUSH15,         0x6e, stack::push::<15>, 0, 1;
-    PUSH16,         0x6f, stack::push::<16>, 0, 1;
-    PUSH17,         0x70, stack::push::<17>, 0, 1;
-    PUSH18,         0x71, stack::push::<18>, 0, 1;
-    PUSH19,         0x72, stack::push::<19>, 0, 1;
-    PUSH20,         0x73, stack::push::<20>, 0, 1;
-    PUSH21,         0x74, stack::push::<21>, 0, 1;
-    PUSH22,         0x75, stack::push::<22>, 0, 1;
-    PUSH23,         0x76, stack::push::<23>, 0, 1;
-    PUSH24,         0x77, stack::push::<24>, 0, 1;
-    PUSH25,         0x78, stack::push::<25>, 0, 1;
-    PUSH26,         0x79, stack::push::<26>, 0, 1;
-    PUSH27,         0x7a, stack::push::<27>, 0, 1;
-    PUSH28,         0x7b, stack::push::<28>, 0, 1;
-    PUSH29,         0x7c, stack::push::<29>, 0, 1;
-    PUSH30,         0x7d, stack::push::<30>, 0, 1;
-    PUSH31,         0x7e, stack::push::<31>, 0, 1;
-    PUSH32,         0x7f, stack::push::<32>, 0, 1;
+    PUSH0,          0x5f, stack::push0, 0, 1, gas::BASE;
+    PUSH1,          0x60, stack::push::<1>, 0, 1, gas::VERYLOW;
+    PUSH2,          0x61, stack::push::<2>, 0, 1, gas::VERYLOW;
+    PUSH3,          0x62, stack::push::<3>, 0, 1, gas::VERYLOW;
+    PUSH4,          0x63, stack::push::<4>, 0, 1, gas::VERYLOW;
+    PUSH5,          0x64, stack::push::<5>, 0, 1, gas::VERYLOW;
+    PUSH6,          0x65, stack::push::<6>, 0, 1, gas::VERYLOW;
+    PUSH7,          0x66, stack::push::<7>, 0, 1, gas::VERYLOW;
+    PUSH8,          0x67, stack::push::<8>, 0, 1, gas::VERYLOW;
+    PUSH9,          0x68, stack::push::<9>, 0, 1, gas::VERYLOW;
+    PUSH10,         0x69, stack::push::<10>, 0, 1, gas::VERYLOW;
+    PUSH11,         0x6a, stack::push::<11>, 0, 1, gas::VERYLOW;
+    PUSH12,         0x6b, stack::push::<12>, 0, 1, gas::VERYLOW;
+    PUSH13,         0x6c, stack::push::<13>, 0, 1, gas::VERYLOW;
+    PUSH14,         0x6d, stack::push::<14>, 0, 1, gas::VERYLOW;
+    PUSH15,         0x6e, stack::push::<15>, 0, 1, gas::VERYLOW;
+    PUSH16,         0x6f, stack::push::<16>, 0, 1, gas::VERYLOW;
+    PUSH17,         0x70, stack::push::<17>, 0, 1, gas::VERYLOW;
+    PUSH18,         0x71, stack::push::<18>, 0, 1, gas::VERYLOW;
+    PUSH19,         0x72, stack::push::<19>, 0, 1, gas::VERYLOW;
+    PUSH20,         0x73, stack::push::<20>, 0, 1, gas::VERYLOW;
+    PUSH21,         0x74, stack::push::<21>, 0, 1, gas::VERYLOW;
+    PUSH22,         0x75, stack::push::<22>, 0, 1, gas::VERYLOW;
+    PUSH23,         0x76, stack::push::<23>, 0, 1, gas::VERYLOW;
+    PUSH24,         0x77, stack::push::<24>, 0, 1, gas::VERYLOW;
+    PUSH25,         0x78, stack::push::<25>, 0, 1, gas::VERYLOW;
+    PUSH26,         0x79, stack::push::<26>, 0, 1, gas::VERYLOW;
+    PUSH27,         0x7a, stack::push::<27>, 0, 1, gas::VERYLOW;
+    PUSH28,         0x7b, stack::push::<28>, 0, 1, gas::VERYLOW;
+    PUSH29,         0x7c, stack::push::<29>, 0, 1, gas::VERYLOW;
+    PUSH30,         0x7d, stack::push::<30>, 0, 1, gas::VERYLOW;
+    PUSH31,         0x7e, stack::push::<31>, 0, 1, gas::VERYLOW;
+    PUSH32,         0x7f, stack::push::<32>, 0, 1, gas::VERYLOW;
 
     // 80s: Duplication Operations
-    DUP1,           0x80, todo_instr, 1, 2;
-    DUP2,           0x81, todo_instr, 2, 3;
-    DUP3,           0x82, todo_instr, 3, 4;
-    DUP4,           0x83, todo_instr, 4, 5;
-    DUP5,           0x84, todo_instr, 5, 6;
-    DUP6,           0x85, todo_instr, 6, 7;
-    DUP7,           0x86, todo_instr, 7, 8;
-    DUP8,           0x87, todo_instr, 8, 9;
-    DUP9,           0x88, todo_instr, 9, 10;
-    DUP10,          0x89, todo_instr, 10, 11;
-    DUP11,          0x8a, todo_instr, 11, 12;
-    DUP12,          0x8b, todo_instr, 12, 13;
-    DUP13,          0x8c, todo_instr, 13, 14;
-    DUP14,          0x8d, todo_instr, 14, 15;
-    DUP15,          0x8e, todo_instr, 15, 16;
-    DUP16,          0x8f, todo_instr, 16, 17;
+    DUP1,           0x80, stack::dup::<1>, 1, 2, gas::VERYLOW;
+    DUP2,           0x81, stack::dup::<2>, 2, 3, gas::VERYLOW;
+    DUP3,           0x82, stack::dup::<3>, 3, 4, gas::VERYLOW;
+    DUP4,           0x83, stack::dup::<4>, 4, 5, gas::VERYLOW;
+    DUP5,           0x84, stack::dup::<5>, 5, 6, gas::VERYLOW;
+    DUP6,           0x85, stack::dup::<6>, 6, 7, gas::VERYLOW;
+    DUP7,           0x86, stack::dup::<7>, 7, 8, gas::VERYLOW;
+    DUP8,           0x87, stack::dup::<8>, 8, 9, gas::VERYLOW;
+    DUP9,           0x88, stack::dup::<9>, 9, 10, gas::VERYLOW;
+    DUP10,          0x89, stack::dup::<10>, 10, 11, gas::VERYLOW;
+    DUP11,          0x8a, stack::dup::<11>, 11, 12, gas::VERYLOW;
+    DUP12,          0x8b, stack::dup::<12>, 12, 13, gas::VERYLOW;
+    DUP13,          0x8c, stack::dup::<13>, 13, 14, gas::VERYLOW;
+    DUP14,          0x8d, stack::dup::<14>, 14, 15, gas::VERYLOW;
+    DUP15,          0x8e, stack::dup::<15>, 15, 16, gas::VERYLOW;
+    DUP16,          0x8f, stack::dup::<16>, 16, 17, gas::VERYLOW;
 
     // 90s: Exchange Operations
-    SWAP1,          0x90, todo_instr, 2, 2;
-    SWAP2,          0x91, todo_instr, 3, 3;
-    SWAP3,          0x92, todo_instr, 4, 4;
-    SWAP4,          0x93, todo_instr, 5, 5;
-    SWAP5,          0x94, todo_instr, 6, 6;
-    SWAP6,          0x95, todo_instr, 7, 7;
-    SWAP7,          0x96, todo_instr, 8, 8;
-    SWAP8,          0x97, todo_instr, 9, 9;
-    SWAP9,          0x98, todo_instr, 10, 10;
-    SWAP10,         0x99, todo_instr, 11, 11;
-    SWAP11,         0x9a, todo_instr, 12, 12;
-    SWAP12,         0x9b, todo_instr, 13, 13;
-    SWAP13,         0x9c, todo_instr, 14, 14;
-    SWAP14,         0x9d, todo_instr, 15, 15;
-    SWAP15,         0x9e, todo_instr, 16, 16;
-    SWAP16,         0x9f, todo_instr, 17, 17;
+    SWAP1,          0x90, stack::swap::<1>, 2, 2, gas::VERYLOW;
+    SWAP2,          0x91, stack::swap::<2>, 3, 3, gas::VERYLOW;
+    SWAP3,          0x92, stack::swap::<3>, 4, 4, gas::VERYLOW;
+    SWAP4,          0x93, stack::swap::<4>, 5, 5, gas::VERYLOW;
+    SWAP5,          0x94, stack::swap::<5>, 6, 6, gas::VERYLOW;
+    SWAP6,          0x95, stack::swap::<6>, 7, 7, gas::VERYLOW;
+    SWAP7,          0x96, stack::swap::<7>, 8, 8, gas::VERYLOW;
+    SWAP8,          0x97, stack::swap::<8>, 9, 9, gas::VERYLOW;
+    SWAP9,          0x98, stack::swap::<9>, 10, 10, gas::VERYLOW;
+    SWAP10,         0x99, stack::swap::<10>, 11, 11, gas::VERYLOW;
+    SWAP11,         0x9a, stack::swap::<11>, 12, 12, gas::VERYLOW;
+    SWAP12,         0x9b, stack::swap::<12>, 13, 13, gas::VERYLOW;
+    SWAP13,         0x9c, stack::swap::<13>, 14, 14, gas::VERYLOW;
+    SWAP14,         0x9d, stack::swap::<14>, 15, 15, gas::VERYLOW;
+    SWAP15,         0x9e, stack::swap::<15>, 16, 16, gas::VERYLOW;
+    SWAP16,         0x9f, stack::swap::<16>, 17, 17, gas::VERYLOW;
 
     // a0s: Logging Operations
-    LOG0,           0xa0, todo_instr, 2, 0;
-    LOG1,           0xa1, todo_instr, 3, 0;
-    LOG2,           0xa2, todo_instr, 4, 0;
-    LOG3,           0xa3, todo_instr, 5, 0;
-    LOG4,           0xa4, todo_instr, 6, 0;
+    LOG0,           0xa0, host::log::<0>, 2, 0, 375;
+    LOG1,           0xa1, host::log::<1>, 3, 0, 750;
+    LOG2,           0xa2, host::log::<2>, 4, 0, 1125;
+    LOG3,           0xa3, host::log::<3>, 5, 0, 1500;
+    LOG4,           0xa4, host::log::<4>, 6, 0, 1875;
 
     // f0s: System Operations
-    CREATE,         0xf0, todo_instr, 3, 1;
-    CALL,           0xf1, todo_instr, 7, 1;
-    CALLCODE,       0xf2, todo_instr, 7, 1;
-    RETURN,         0xf3, todo_instr, 2, 0;
-    DELEGATECALL,   0xf4, todo_instr, 6, 1;
-    CRATE32,        0xf5, todo_instr, 4, 1;
-    STATICCALL,     0xfa, todo_instr, 6, 1;
-    REVERT,         0xfd, todo_instr, 2, 0;
-    INVALID,        0xfe, todo_instr, 0, 0;
-    SELFDESTRUCT,   0xff, todo_instr, 1, 0;
+    CREATE,         0xf0, contract::create::<false>, 3, 1, gas::CREATE;
+    CALL,           0xf1, contract::call, 7, 1, gas::ZERO;
+    CALLCODE,       0xf2, contract::call_code, 7, 1, gas::ZERO;
+    RETURN,         0xf3, control::ret, 2, 0, gas::ZERO;
+    DELEGATECALL,   0xf4, contract::delegate_call, 6, 1, gas::ZERO;
+    CREATE2,        0xf5, contract::create::<true>, 4, 1, gas::CREATE;
+    STATICCALL,     0xfa, contract::static_call, 6, 1, gas::ZERO;
+    REVERT,         0xfd, control::revert, 2, 0, gas::ZERO;
+    INVALID,        0xfe, control::invalid, 0, 0, gas::ZERO;
+    SELFDESTRUCT,   0xff, host::selfdestruct, 1, 0, gas::ZERO;
 
 }
 
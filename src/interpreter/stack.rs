@@ -46,6 +46,11 @@ impl Stack {
         self.data.is_empty()
     }
 
+    /// The items from the bottom of the stack to the top.
+    pub fn data(&self) -> &[U256] {
+        &self.data
+    }
+
     pub fn push(&mut self, value: U256) -> Result<()> {
         if self.data.len() >= STACK_LIMIT {
             return Err(StackError::Overflow);
@@ -67,6 +72,41 @@ impl Stack {
         }
     }
 
+    pub fn pop2(&mut self) -> Result<(U256, U256)> {
+        Ok((self.pop()?, self.pop()?))
+    }
+
+    /// Pops `N` items, the top of the stack first.
+    pub fn popn<const N: usize>(&mut self) -> Result<[U256; N]> {
+        if self.data.len() < N {
+            return Err(StackError::Underflow);
+        }
+        let mut items = [U256::ZERO; N];
+        for item in items.iter_mut() {
+            *item = self.data.pop().unwrap();
+        }
+        Ok(items)
+    }
+
+    /// Pushes a copy of the `n`th item, counting from 1 at the top.
+    pub fn dup(&mut self, n: usize) -> Result<()> {
+        let len = self.data.len();
+        if len < n {
+            return Err(StackError::Underflow);
+        }
+        self.push(self.data[len - n])
+    }
+
+    /// Swaps the top with the item `n` below it.
+    pub fn swap(&mut self, n: usize) -> Result<()> {
+        let len = self.data.len();
+        if len <= n {
+            return Err(StackError::Underflow);
+        }
+        self.data.swap(len - 1, len - 1 - n);
+        Ok(())
+    }
+
     /// Pops a item from the stack then returns a reference to the top of the stack.
     /// This is equal to calling `pop()` -> `top()`.
     pub fn pop_top(&mut self) -> Result<(U256, &mut U256)> {
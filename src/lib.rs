@@ -1,3 +1,11 @@
+pub mod asm;
+pub mod cli;
 pub mod domain;
+pub mod evm;
+pub mod executor;
+pub mod fixtures;
+pub mod inspector;
 pub mod instructions;
 pub mod interpreter;
+pub mod precompiles;
+pub mod tracer;
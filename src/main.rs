@@ -1,8 +1,114 @@
+use std::{io, process::ExitCode};
+
 use bytes::Bytes;
-use evm_rs::interpreter::Interpreter;
+use clap::{Args, Parser, Subcommand};
+use evm_rs::{
+    asm::disassembler,
+    cli::{self, RunOptions},
+    domain::{
+        primitives::{self, Address},
+        spec::SpecId,
+    },
+};
+use ruint::aliases::U256;
+
+#[derive(Parser)]
+#[command(name = "evm-rs", about = "An Ethereum Virtual Machine")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Runs bytecode in a single frame against an empty state. Exits with a failure unless it
+    /// stops or returns.
+    Run(RunArgs),
+    /// Prints the instructions of bytecode with their offsets.
+    Disasm {
+        /// Hex bytecode, or `@path` to a file holding it.
+        code: String,
+    },
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Hex bytecode, or `@path` to a file holding it.
+    code: String,
+    /// Hex call data.
+    #[arg(long, value_parser = primitives::parse_hex, default_value = "")]
+    calldata: Bytes,
+    #[arg(long, default_value_t = 30_000_000)]
+    gas_limit: u64,
+    /// Wei sent with the call, decimal or 0x prefixed hex.
+    #[arg(long, value_parser = cli::parse_u256, default_value = "0")]
+    value: U256,
+    #[arg(long, default_value_t = Address::ZERO)]
+    caller: Address,
+    /// Fork name, e.g. Shanghai.
+    #[arg(long, default_value = "Cancun")]
+    fork: SpecId,
+    /// Prints the report as JSON.
+    #[arg(long)]
+    json: bool,
+    /// Writes an EIP-3155 trace to stderr.
+    #[arg(long)]
+    trace: bool,
+}
+
+fn run(args: RunArgs) -> ExitCode {
+    let code = match cli::read_code(&args.code) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let options = RunOptions {
+        calldata: args.calldata,
+        gas_limit: args.gas_limit,
+        value: args.value,
+        caller: args.caller,
+        spec: args.fork,
+    };
+    let report = if args.trace {
+        match cli::trace(code, &options, io::stderr().lock()) {
+            Ok(report) => report,
+            Err(error) => {
+                eprintln!("error: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        cli::run(code, &options)
+    };
+    if args.json {
+        println!("{}", report.to_json());
+    } else {
+        println!("{report}");
+    }
+    match report.result.is_ok() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+fn disasm(code: &str) -> ExitCode {
+    match cli::read_code(code) {
+        Ok(code) => {
+            print!("{}", disassembler::listing(code.bytes_slice()));
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
 
-fn main() {
-    let code = Bytes::from_static(&[0x5f, 0x50]);
-    let mut evm = Interpreter::new(code);
-    evm.run();
+fn main() -> ExitCode {
+    match Cli::parse().command {
+        Command::Run(args) => run(args),
+        Command::Disasm { code } => disasm(&code),
+    }
 }
@@ -0,0 +1,142 @@
+pub mod blake2;
+pub mod bn254;
+pub mod ecrecover;
+pub mod hash;
+pub mod identity;
+pub mod modexp;
+pub mod point_evaluation;
+
+use bytes::Bytes;
+use hashbrown::HashMap;
+
+use crate::domain::{primitives::Address, spec::SpecId};
+
+pub type PrecompileResult = Result<PrecompileOutput, PrecompileError>;
+
+/// A precompile gets the call input and the gas available to it.
+pub type PrecompileFn = fn(&[u8], u64) -> PrecompileResult;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PrecompileOutput {
+    pub gas_used: u64,
+    pub bytes: Bytes,
+}
+
+impl PrecompileOutput {
+    pub fn new(gas_used: u64, bytes: Bytes) -> Self {
+        Self { gas_used, bytes }
+    }
+}
+
+/// Any error consumes all the gas given to the precompile.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PrecompileError {
+    OutOfGas,
+    Bn254FieldPointNotAMember,
+    Bn254AffineGFailedToCreate,
+    Bn254PairLength,
+    Blake2WrongLength,
+    Blake2WrongFinalIndicatorFlag,
+    BlobInvalidInputLength,
+    BlobMismatchedVersion,
+    BlobVerifyKzgProofFailed,
+}
+
+/// The gas cost of precompiles that charge `base` plus `word` per 32 byte word of input.
+pub fn linear_cost(len: usize, base: u64, word: u64) -> u64 {
+    (len as u64).div_ceil(32) * word + base
+}
+
+/// Copies `input` into a zero padded buffer of `N` bytes, truncating if it is longer.
+pub fn right_pad<const N: usize>(input: &[u8]) -> [u8; N] {
+    let mut padded = [0u8; N];
+    let len = input.len().min(N);
+    padded[..len].copy_from_slice(&input[..len]);
+    padded
+}
+
+/// The precompiled contracts, looked up by the call machinery before executing any code.
+#[derive(Clone, Debug)]
+pub struct Precompiles {
+    inner: HashMap<Address, PrecompileFn>,
+}
+
+impl Default for Precompiles {
+    fn default() -> Self {
+        Self::new(SpecId::default())
+    }
+}
+
+impl Precompiles {
+    /// The precompiles available under `spec`, with that forks gas pricing.
+    pub fn new(spec: SpecId) -> Self {
+        let mut inner = HashMap::new();
+        inner.insert(ecrecover::ADDRESS, ecrecover::run as PrecompileFn);
+        inner.insert(hash::SHA256_ADDRESS, hash::sha256_run);
+        inner.insert(hash::RIPEMD160_ADDRESS, hash::ripemd160_run);
+        inner.insert(identity::ADDRESS, identity::run);
+        if spec.is_enabled_in(SpecId::Berlin) {
+            inner.insert(modexp::ADDRESS, modexp::berlin_run);
+        } else if spec.is_enabled_in(SpecId::Byzantium) {
+            inner.insert(modexp::ADDRESS, modexp::byzantium_run);
+        }
+        if spec.is_enabled_in(SpecId::Istanbul) {
+            inner.insert(bn254::ADD_ADDRESS, bn254::istanbul_add_run);
+            inner.insert(bn254::MUL_ADDRESS, bn254::istanbul_mul_run);
+            inner.insert(bn254::PAIRING_ADDRESS, bn254::istanbul_pairing_run);
+            inner.insert(blake2::ADDRESS, blake2::run);
+        } else if spec.is_enabled_in(SpecId::Byzantium) {
+            inner.insert(bn254::ADD_ADDRESS, bn254::byzantium_add_run);
+            inner.insert(bn254::MUL_ADDRESS, bn254::byzantium_mul_run);
+            inner.insert(bn254::PAIRING_ADDRESS, bn254::byzantium_pairing_run);
+        }
+        if spec.is_enabled_in(SpecId::Cancun) {
+            inner.insert(point_evaluation::ADDRESS, point_evaluation::run);
+        }
+        Self { inner }
+    }
+
+    pub fn get(&self, address: &Address) -> Option<PrecompileFn> {
+        self.inner.get(address).copied()
+    }
+
+    pub fn contains(&self, address: &Address) -> bool {
+        self.inner.contains_key(address)
+    }
+
+    /// Every precompile address, these are warm from the start of a transaction (EIP-2929).
+    pub fn addresses(&self) -> impl Iterator<Item = Address> + '_ {
+        self.inner.keys().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry() {
+        let precompiles = Precompiles::new(SpecId::Homestead);
+        for i in 1..=4 {
+            assert!(precompiles.contains(&Address::with_last_byte(i)));
+        }
+        assert!(precompiles.get(&Address::with_last_byte(0x42)).is_none());
+        assert_eq!(precompiles.addresses().count(), 4);
+        assert_eq!(Precompiles::new(SpecId::Byzantium).addresses().count(), 8);
+        assert_eq!(Precompiles::new(SpecId::Cancun).addresses().count(), 10);
+
+        let identity = precompiles.get(&Address::with_last_byte(4)).unwrap();
+        assert_eq!(
+            identity(&[1, 2, 3], 18).unwrap(),
+            PrecompileOutput::new(18, Bytes::from_static(&[1, 2, 3]))
+        );
+    }
+
+    #[test]
+    fn padding_and_cost() {
+        assert_eq!(right_pad::<4>(&[1, 2]), [1, 2, 0, 0]);
+        assert_eq!(right_pad::<2>(&[1, 2, 3]), [1, 2]);
+        assert_eq!(linear_cost(0, 15, 3), 15);
+        assert_eq!(linear_cost(33, 15, 3), 21);
+    }
+}
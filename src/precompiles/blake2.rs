@@ -0,0 +1,154 @@
+use bytes::Bytes;
+
+use crate::domain::primitives::Address;
+
+use super::{PrecompileError, PrecompileOutput, PrecompileResult};
+
+pub const ADDRESS: Address = Address::with_last_byte(9);
+
+/// `rounds || h || m || t || f`, see EIP-152.
+const INPUT_LEN: usize = 4 + 64 + 128 + 16 + 1;
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// Gas is one per round, the input has to be exactly 213 bytes with a final flag of 0 or 1.
+pub fn run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    if input.len() != INPUT_LEN {
+        return Err(PrecompileError::Blake2WrongLength);
+    }
+    let rounds = u32::from_be_bytes(input[..4].try_into().unwrap());
+    let gas_used = rounds as u64;
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    let last_block = match input[212] {
+        0 => false,
+        1 => true,
+        _ => return Err(PrecompileError::Blake2WrongFinalIndicatorFlag),
+    };
+
+    let word = |offset: usize| u64::from_le_bytes(input[offset..offset + 8].try_into().unwrap());
+    let mut h: [u64; 8] = std::array::from_fn(|i| word(4 + i * 8));
+    let m: [u64; 16] = std::array::from_fn(|i| word(68 + i * 8));
+    let t = [word(196), word(204)];
+
+    compress(rounds as usize, &mut h, m, t, last_block);
+
+    let mut output = [0u8; 64];
+    for (chunk, h) in output.chunks_exact_mut(8).zip(h) {
+        chunk.copy_from_slice(&h.to_le_bytes());
+    }
+    Ok(PrecompileOutput::new(
+        gas_used,
+        Bytes::copy_from_slice(&output),
+    ))
+}
+
+#[inline(always)]
+#[allow(clippy::many_single_char_names)]
+fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+/// The BLAKE2b compression function F, with the number of rounds as a parameter.
+pub fn compress(rounds: usize, h: &mut [u64; 8], m: [u64; 16], t: [u64; 2], last_block: bool) {
+    let mut v = [0u64; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV);
+    v[12] ^= t[0];
+    v[13] ^= t[1];
+    if last_block {
+        v[14] = !v[14];
+    }
+
+    for i in 0..rounds {
+        let s = &SIGMA[i % 10];
+        g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The test vectors of EIP-152, "abc" with 12 rounds is plain BLAKE2b-512.
+    const INPUT: &str = "0000000c48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b61626300000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000001";
+
+    #[test]
+    fn eip152_vectors() {
+        let mut input = hex::decode(INPUT).unwrap();
+        let output = run(&input, 12).unwrap();
+        assert_eq!(output.gas_used, 12);
+        assert_eq!(
+            hex::encode(output.bytes),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+            7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+        );
+        assert_eq!(run(&input, 11), Err(PrecompileError::OutOfGas));
+
+        input[3] = 0;
+        assert_eq!(
+            hex::encode(run(&input, 0).unwrap().bytes),
+            "08c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+            d282e6ad7f520e511f6c3e2b8c68059b9442be0454267ce079217e1319cde05b"
+        );
+    }
+
+    #[test]
+    fn invalid_input() {
+        let mut input = hex::decode(INPUT).unwrap();
+        assert_eq!(
+            run(&input[1..], 12),
+            Err(PrecompileError::Blake2WrongLength)
+        );
+        input.push(0);
+        assert_eq!(run(&input, 12), Err(PrecompileError::Blake2WrongLength));
+
+        input.pop();
+        input[212] = 2;
+        assert_eq!(
+            run(&input, 12),
+            Err(PrecompileError::Blake2WrongFinalIndicatorFlag)
+        );
+    }
+}
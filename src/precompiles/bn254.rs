@@ -0,0 +1,243 @@
+use bn::{AffineG1, AffineG2, Fq, Fq2, Fr, Group, Gt, G1, G2};
+use bytes::Bytes;
+
+use crate::domain::primitives::Address;
+
+use super::{right_pad, PrecompileError, PrecompileOutput, PrecompileResult};
+
+pub const ADD_ADDRESS: Address = Address::with_last_byte(6);
+pub const MUL_ADDRESS: Address = Address::with_last_byte(7);
+pub const PAIRING_ADDRESS: Address = Address::with_last_byte(8);
+
+/// A G1 point is two field elements, a G2 point two elements of the quadratic extension.
+const G1_LEN: usize = 64;
+const PAIR_LEN: usize = G1_LEN + 128;
+
+pub fn byzantium_add_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    run_add(input, gas_limit, 500)
+}
+
+/// EIP-1108 repricing.
+pub fn istanbul_add_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    run_add(input, gas_limit, 150)
+}
+
+pub fn byzantium_mul_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    run_mul(input, gas_limit, 40_000)
+}
+
+pub fn istanbul_mul_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    run_mul(input, gas_limit, 6_000)
+}
+
+pub fn byzantium_pairing_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    run_pairing(input, gas_limit, 100_000, 80_000)
+}
+
+pub fn istanbul_pairing_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    run_pairing(input, gas_limit, 45_000, 34_000)
+}
+
+fn read_fq(input: &[u8]) -> Result<Fq, PrecompileError> {
+    Fq::from_slice(input).map_err(|_| PrecompileError::Bn254FieldPointNotAMember)
+}
+
+/// (0, 0) is the point at infinity, anything else has to be on the curve.
+fn read_g1(input: &[u8]) -> Result<G1, PrecompileError> {
+    let x = read_fq(&input[..32])?;
+    let y = read_fq(&input[32..64])?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1::zero());
+    }
+    AffineG1::new(x, y)
+        .map(Into::into)
+        .map_err(|_| PrecompileError::Bn254AffineGFailedToCreate)
+}
+
+/// Coordinates are encoded imaginary part first, `x_im || x_re || y_im || y_re`.
+fn read_g2(input: &[u8]) -> Result<G2, PrecompileError> {
+    let x = Fq2::new(read_fq(&input[32..64])?, read_fq(&input[..32])?);
+    let y = Fq2::new(read_fq(&input[96..128])?, read_fq(&input[64..96])?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2::zero());
+    }
+    AffineG2::new(x, y)
+        .map(Into::into)
+        .map_err(|_| PrecompileError::Bn254AffineGFailedToCreate)
+}
+
+fn encode_g1(point: G1) -> Bytes {
+    let mut output = [0u8; G1_LEN];
+    // The point at infinity has no affine form and is encoded as (0, 0).
+    if let Some(point) = AffineG1::from_jacobian(point) {
+        point.x().to_big_endian(&mut output[..32]).unwrap();
+        point.y().to_big_endian(&mut output[32..]).unwrap();
+    }
+    Bytes::copy_from_slice(&output)
+}
+
+fn run_add(input: &[u8], gas_limit: u64, gas_used: u64) -> PrecompileResult {
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    let input = right_pad::<{ 2 * G1_LEN }>(input);
+    let p1 = read_g1(&input[..G1_LEN])?;
+    let p2 = read_g1(&input[G1_LEN..])?;
+    Ok(PrecompileOutput::new(gas_used, encode_g1(p1 + p2)))
+}
+
+fn run_mul(input: &[u8], gas_limit: u64, gas_used: u64) -> PrecompileResult {
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    let input = right_pad::<{ G1_LEN + 32 }>(input);
+    let point = read_g1(&input[..G1_LEN])?;
+    // Scalars are taken modulo the group order, so this can not fail.
+    let scalar = Fr::from_slice(&input[G1_LEN..]).unwrap();
+    Ok(PrecompileOutput::new(gas_used, encode_g1(point * scalar)))
+}
+
+/// Returns 1 if the product of the pairings of every (G1, G2) pair is one, 0 otherwise.
+fn run_pairing(input: &[u8], gas_limit: u64, base: u64, per_pair: u64) -> PrecompileResult {
+    let gas_used = (input.len() / PAIR_LEN) as u64 * per_pair + base;
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    if !input.len().is_multiple_of(PAIR_LEN) {
+        return Err(PrecompileError::Bn254PairLength);
+    }
+
+    let mut pairs = Vec::with_capacity(input.len() / PAIR_LEN);
+    for pair in input.chunks_exact(PAIR_LEN) {
+        pairs.push((read_g1(&pair[..G1_LEN])?, read_g2(&pair[G1_LEN..])?));
+    }
+    let success = bn::pairing_batch(&pairs) == Gt::one();
+
+    let mut output = [0u8; 32];
+    output[31] = success as u8;
+    Ok(PrecompileOutput::new(
+        gas_used,
+        Bytes::copy_from_slice(&output),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const G1_GEN: &str = "0000000000000000000000000000000000000000000000000000000000000001\
+        0000000000000000000000000000000000000000000000000000000000000002";
+    const G1_GEN_NEG: &str = "0000000000000000000000000000000000000000000000000000000000000001\
+        30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45";
+    const G1_GEN_DOUBLE: &str = "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3\
+        15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4";
+    const G2_GEN: &str = "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+        1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+        090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+        12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa";
+
+    fn input(parts: &[&str]) -> Vec<u8> {
+        hex::decode(parts.concat()).unwrap()
+    }
+
+    #[test]
+    fn add() {
+        // "chfast1" from the bn256Add tests.
+        let output = istanbul_add_run(
+            &input(&[
+                "18b18acfb4c2c30276db5411368e7185b311dd124691610c5d3b74034e093dc9",
+                "063c909c4720840cb5134cb9f59fa749755796819658d32efc0d288198f37266",
+                "07c2b7f58a84bd6145f00c9c2bc0bb1a187f20ff2c92963a88019e7c6a014eed",
+                "06614e20c147e940f2d70da3f74c9a17df361706a4485c742bd6788478fa17d7",
+            ]),
+            150,
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(output.bytes),
+            "2243525c5efd4b9c3d3c45ac0ca3fe4dd85e830a4ce6b65fa1eeaee202839703\
+            301d1d33be6da8e509df21cc35964723180eed7532537db9ae5e7d48f195c915"
+        );
+
+        let output = istanbul_add_run(&input(&[G1_GEN, G1_GEN]), 150).unwrap();
+        assert_eq!(hex::encode(output.bytes), G1_GEN_DOUBLE);
+
+        // P + -P is the point at infinity, as is empty input.
+        let output = byzantium_add_run(&input(&[G1_GEN, G1_GEN_NEG]), 500).unwrap();
+        assert_eq!(&output.bytes[..], &[0u8; 64]);
+        assert_eq!(&istanbul_add_run(&[], 150).unwrap().bytes[..], &[0u8; 64]);
+
+        assert_eq!(byzantium_add_run(&[], 499), Err(PrecompileError::OutOfGas));
+    }
+
+    #[test]
+    fn add_invalid_point() {
+        let mut not_on_curve = input(&[G1_GEN]);
+        not_on_curve[63] = 3;
+        assert_eq!(
+            istanbul_add_run(&not_on_curve, 150),
+            Err(PrecompileError::Bn254AffineGFailedToCreate)
+        );
+
+        // A coordinate that is not a field element.
+        let too_large = [0xff; 64];
+        assert_eq!(
+            istanbul_add_run(&too_large, 150),
+            Err(PrecompileError::Bn254FieldPointNotAMember)
+        );
+    }
+
+    #[test]
+    fn mul() {
+        let two = format!("{:064x}", 2);
+        let output = istanbul_mul_run(&input(&[G1_GEN, &two]), 6000).unwrap();
+        assert_eq!(hex::encode(output.bytes), G1_GEN_DOUBLE);
+
+        // Scalars past the group order wrap around, r + 2 is the same as 2.
+        let r_plus_two = "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000003";
+        let output = istanbul_mul_run(&input(&[G1_GEN, r_plus_two]), 6000).unwrap();
+        assert_eq!(hex::encode(output.bytes), G1_GEN_DOUBLE);
+
+        assert_eq!(
+            byzantium_mul_run(&input(&[G1_GEN, &two]), 6000),
+            Err(PrecompileError::OutOfGas)
+        );
+    }
+
+    #[test]
+    fn pairing() {
+        // e(G1, G2) * e(-G1, G2) == 1
+        let valid = input(&[G1_GEN, G2_GEN, G1_GEN_NEG, G2_GEN]);
+        let output = istanbul_pairing_run(&valid, 113_000).unwrap();
+        assert_eq!(output.gas_used, 45_000 + 2 * 34_000);
+        assert_eq!(output.bytes[31], 1);
+        assert_eq!(
+            byzantium_pairing_run(&valid, 259_999),
+            Err(PrecompileError::OutOfGas)
+        );
+
+        let invalid = input(&[G1_GEN, G2_GEN, G1_GEN, G2_GEN]);
+        assert_eq!(
+            istanbul_pairing_run(&invalid, 113_000).unwrap().bytes[31],
+            0
+        );
+
+        // The empty product is one.
+        assert_eq!(istanbul_pairing_run(&[], 45_000).unwrap().bytes[31], 1);
+
+        assert_eq!(
+            istanbul_pairing_run(&valid[..100], 100_000),
+            Err(PrecompileError::Bn254PairLength)
+        );
+    }
+
+    #[test]
+    fn pairing_invalid_g2() {
+        let mut bad_g2 = input(&[G1_GEN, G2_GEN]);
+        bad_g2[G1_LEN + 127] ^= 1;
+        assert_eq!(
+            istanbul_pairing_run(&bad_g2, 100_000),
+            Err(PrecompileError::Bn254AffineGFailedToCreate)
+        );
+    }
+}
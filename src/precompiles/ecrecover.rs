@@ -0,0 +1,84 @@
+use bytes::Bytes;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+use crate::domain::primitives::{keccak256, Address, B256};
+
+use super::{right_pad, PrecompileError, PrecompileOutput, PrecompileResult};
+
+pub const ADDRESS: Address = Address::with_last_byte(1);
+
+const GAS: u64 = 3000;
+
+/// Recovers the signer of `msg` from a `r || s` signature and its recovery id (0 or 1). High
+/// `s` values are accepted, it is up to the caller to enforce EIP-2.
+pub fn ecrecover(sig: &[u8; 64], recid: u8, msg: &B256) -> Option<Address> {
+    let mut signature = Signature::from_slice(sig).ok()?;
+    let mut recid = recid;
+    // k256 only verifies low `s` signatures, flipping `s` flips the parity of `R`.
+    if let Some(normalized) = signature.normalize_s() {
+        signature = normalized;
+        recid ^= 1;
+    }
+    let recid = RecoveryId::from_byte(recid)?;
+    let key = VerifyingKey::recover_from_prehash(msg, &signature, recid).ok()?;
+    let hash = keccak256(&key.to_encoded_point(false).as_bytes()[1..]);
+    Some(Address::from_slice(&hash[12..]))
+}
+
+/// Input is `hash || v || r || s`, each 32 bytes. Invalid signatures return empty output
+/// rather than failing.
+pub fn run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    if GAS > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    let input = right_pad::<128>(input);
+
+    let v = &input[32..64];
+    let recovered = if v[..31].iter().all(|byte| *byte == 0) && matches!(v[31], 27 | 28) {
+        let msg: B256 = input[..32].try_into().unwrap();
+        let sig: [u8; 64] = input[64..].try_into().unwrap();
+        ecrecover(&sig, v[31] - 27, &msg)
+    } else {
+        None
+    };
+
+    let bytes = match recovered {
+        Some(address) => {
+            let mut output = [0u8; 32];
+            output[12..].copy_from_slice(address.as_slice());
+            Bytes::copy_from_slice(&output)
+        }
+        None => Bytes::new(),
+    };
+    Ok(PrecompileOutput::new(GAS, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "18c547e4f7b0f325ad1e56f57e26c745b09a3e503d86e00e5255ff7f715d3d1c000000000000000000000000000000000000000000000000000000000000001c73b1693892219d736caba55bdb67216e485557ea6b6af75f37096c9aa6a5a75feeb940b1d03b21e36b0e47e79769f095fe2ab855bd91e3a38756b7d75a9c4549";
+
+    #[test]
+    fn recover() {
+        let input = hex::decode(INPUT).unwrap();
+        let output = run(&input, 3000).unwrap();
+        assert_eq!(output.gas_used, 3000);
+        assert_eq!(
+            hex::encode(output.bytes),
+            "000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b"
+        );
+        assert_eq!(run(&input, 2999), Err(PrecompileError::OutOfGas));
+    }
+
+    #[test]
+    fn invalid_input() {
+        let mut input = hex::decode(INPUT).unwrap();
+        input[63] = 29;
+        assert!(run(&input, 3000).unwrap().bytes.is_empty());
+
+        // Missing bytes are zero padded, which makes `r` and `s` zero.
+        assert!(run(&input[..64], 3000).unwrap().bytes.is_empty());
+        assert!(run(&[], 3000).unwrap().bytes.is_empty());
+    }
+}
@@ -0,0 +1,62 @@
+use bytes::Bytes;
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::domain::primitives::Address;
+
+use super::{linear_cost, PrecompileError, PrecompileOutput, PrecompileResult};
+
+pub const SHA256_ADDRESS: Address = Address::with_last_byte(2);
+pub const RIPEMD160_ADDRESS: Address = Address::with_last_byte(3);
+
+pub fn sha256_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    let gas_used = linear_cost(input.len(), 60, 12);
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    let output = Sha256::digest(input);
+    Ok(PrecompileOutput::new(
+        gas_used,
+        Bytes::copy_from_slice(&output),
+    ))
+}
+
+/// The 20 byte digest is returned left padded to 32 bytes.
+pub fn ripemd160_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    let gas_used = linear_cost(input.len(), 600, 120);
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    let mut output = [0u8; 32];
+    output[12..].copy_from_slice(&Ripemd160::digest(input));
+    Ok(PrecompileOutput::new(
+        gas_used,
+        Bytes::copy_from_slice(&output),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256() {
+        let output = sha256_run(b"", 60).unwrap();
+        assert_eq!(output.gas_used, 60);
+        assert_eq!(
+            hex::encode(output.bytes),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(sha256_run(b"a", 71), Err(PrecompileError::OutOfGas));
+    }
+
+    #[test]
+    fn ripemd160() {
+        let output = ripemd160_run(b"abc", 720).unwrap();
+        assert_eq!(output.gas_used, 720);
+        assert_eq!(
+            hex::encode(output.bytes),
+            "0000000000000000000000008eb208f7e05d987a9b044a8e98c6b087f15a0bfc"
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::domain::primitives::Address;
+
+use super::{linear_cost, PrecompileError, PrecompileOutput, PrecompileResult};
+
+pub const ADDRESS: Address = Address::with_last_byte(4);
+
+const BASE: u64 = 15;
+const PER_WORD: u64 = 3;
+
+/// Returns the input as is.
+pub fn run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    let gas_used = linear_cost(input.len(), BASE, PER_WORD);
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    Ok(PrecompileOutput::new(
+        gas_used,
+        Bytes::copy_from_slice(input),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity() {
+        let input = [0xab; 40];
+        let output = run(&input, 21).unwrap();
+        assert_eq!(output.gas_used, 21);
+        assert_eq!(&output.bytes[..], &input[..]);
+        assert_eq!(run(&input, 20), Err(PrecompileError::OutOfGas));
+    }
+}
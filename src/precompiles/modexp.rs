@@ -0,0 +1,196 @@
+use aurora_engine_modexp::modexp;
+use bytes::Bytes;
+use ruint::aliases::U256;
+
+use crate::domain::primitives::Address;
+
+use super::{right_pad, PrecompileError, PrecompileOutput, PrecompileResult};
+
+pub const ADDRESS: Address = Address::with_last_byte(5);
+
+/// EIP-198 pricing, Byzantium until Berlin.
+pub fn byzantium_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    run_inner(input, gas_limit, byzantium_gas)
+}
+
+/// EIP-2565 pricing, Berlin onwards.
+pub fn berlin_run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    run_inner(input, gas_limit, berlin_gas)
+}
+
+/// Input is `base_len || exp_len || mod_len || base || exp || mod` where the lengths are 32
+/// byte words. Anything missing from the input is read as zero.
+fn run_inner(
+    input: &[u8],
+    gas_limit: u64,
+    calc_gas: fn(U256, U256, U256, U256) -> U256,
+) -> PrecompileResult {
+    let header = right_pad::<96>(input);
+    let base_len = U256::from_be_slice(&header[..32]);
+    let exp_len = U256::from_be_slice(&header[32..64]);
+    let mod_len = U256::from_be_slice(&header[64..]);
+
+    // The declared lengths can be anything, so the gas is computed from them without reading
+    // more than the first 32 bytes of the exponent.
+    let exp_head_len = exp_len.min(U256::from(32)).to::<usize>();
+    let exp_head = match usize::try_from(base_len) {
+        Ok(base_len) => read_padded(input, 96usize.saturating_add(base_len), exp_head_len),
+        Err(_) => vec![0; exp_head_len],
+    };
+    let exp_head = U256::from_be_slice(&exp_head);
+
+    let gas = calc_gas(base_len, exp_len, mod_len, exp_head);
+    if gas > U256::from(gas_limit) {
+        return Err(PrecompileError::OutOfGas);
+    }
+    let gas_used = gas.to::<u64>();
+
+    if mod_len.is_zero() {
+        return Ok(PrecompileOutput::new(gas_used, Bytes::new()));
+    }
+    // Lengths that do not fit in memory are unpayable, this only guards the conversion.
+    let (Ok(base_len), Ok(exp_len), Ok(mod_len)) = (
+        usize::try_from(base_len),
+        usize::try_from(exp_len),
+        usize::try_from(mod_len),
+    ) else {
+        return Err(PrecompileError::OutOfGas);
+    };
+
+    let exp_offset = 96usize.saturating_add(base_len);
+    let mod_offset = exp_offset.saturating_add(exp_len);
+    let base = read_padded(input, 96, base_len);
+    let exp = read_padded(input, exp_offset, exp_len);
+    let modulus = read_padded(input, mod_offset, mod_len);
+
+    let result = modexp(&base, &exp, &modulus);
+    let mut output = vec![0u8; mod_len];
+    output[mod_len - result.len()..].copy_from_slice(&result);
+    Ok(PrecompileOutput::new(gas_used, Bytes::from(output)))
+}
+
+/// Reads `len` bytes from `offset`, zero padding whatever lies past the end of the input.
+fn read_padded(input: &[u8], offset: usize, len: usize) -> Vec<u8> {
+    let mut output = vec![0u8; len];
+    if offset < input.len() {
+        let end = offset.saturating_add(len).min(input.len());
+        output[..end - offset].copy_from_slice(&input[offset..end]);
+    }
+    output
+}
+
+/// The number of significant bits in the exponent, minus one, counting each byte past the
+/// first 32 as 8.
+fn adjusted_exp_len(exp_len: U256, exp_head: U256) -> U256 {
+    let head_log = U256::from(exp_head.bit_len().saturating_sub(1));
+    if exp_len <= U256::from(32) {
+        head_log
+    } else {
+        (exp_len - U256::from(32))
+            .saturating_mul(U256::from(8))
+            .saturating_add(head_log)
+    }
+}
+
+fn byzantium_gas(base_len: U256, exp_len: U256, mod_len: U256, exp_head: U256) -> U256 {
+    let x = base_len.max(mod_len);
+    let x_sq = x.saturating_mul(x);
+    let complexity = if x <= U256::from(64) {
+        x_sq
+    } else if x <= U256::from(1024) {
+        x_sq / U256::from(4) + U256::from(96) * x - U256::from(3072)
+    } else {
+        (x_sq / U256::from(16))
+            .saturating_add(x.saturating_mul(U256::from(480)))
+            .saturating_sub(U256::from(199680))
+    };
+    let iterations = adjusted_exp_len(exp_len, exp_head).max(U256::from(1));
+    complexity.saturating_mul(iterations) / U256::from(20)
+}
+
+fn berlin_gas(base_len: U256, exp_len: U256, mod_len: U256, exp_head: U256) -> U256 {
+    let words = base_len.max(mod_len).saturating_add(U256::from(7)) / U256::from(8);
+    let complexity = words.saturating_mul(words);
+    let iterations = adjusted_exp_len(exp_len, exp_head).max(U256::from(1));
+    (complexity.saturating_mul(iterations) / U256::from(3)).max(U256::from(200))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3 ^ (p - 1) % p for the secp256k1 field prime, the first example of EIP-198.
+    const FERMAT: &str = "0000000000000000000000000000000000000000000000000000000000000001\
+        0000000000000000000000000000000000000000000000000000000000000020\
+        0000000000000000000000000000000000000000000000000000000000000020\
+        03\
+        fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e\
+        fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+
+    #[test]
+    fn fermat() {
+        let input = hex::decode(FERMAT).unwrap();
+        let mut expected = [0u8; 32];
+        expected[31] = 1;
+
+        let output = byzantium_run(&input, 13056).unwrap();
+        assert_eq!(output.gas_used, 13056);
+        assert_eq!(&output.bytes[..], &expected);
+
+        let output = berlin_run(&input, 1360).unwrap();
+        assert_eq!(output.gas_used, 1360);
+        assert_eq!(&output.bytes[..], &expected);
+        assert_eq!(berlin_run(&input, 1359), Err(PrecompileError::OutOfGas));
+    }
+
+    #[test]
+    fn short_input_is_zero_padded() {
+        // The modulus is cut off entirely, so it reads as zero and so does the result.
+        let input = hex::decode(&FERMAT[..(96 + 1 + 32) * 2]).unwrap();
+        let output = berlin_run(&input, 100_000).unwrap();
+        assert_eq!(output.gas_used, 1360);
+        assert_eq!(&output.bytes[..], &[0u8; 32]);
+
+        assert_eq!(
+            berlin_run(&[], 200).unwrap(),
+            PrecompileOutput::new(200, Bytes::new())
+        );
+        assert_eq!(byzantium_run(&[], 0).unwrap().gas_used, 0);
+    }
+
+    #[test]
+    fn huge_declared_lengths() {
+        // A exponent length of 2^255 costs nothing as long as base and modulus are empty.
+        let mut input = [0u8; 96];
+        input[32] = 0x80;
+        assert_eq!(
+            berlin_run(&input, 200).unwrap(),
+            PrecompileOutput::new(200, Bytes::new())
+        );
+
+        // ...but with a modulus it is unpayable rather than a huge allocation.
+        input[95] = 1;
+        assert_eq!(berlin_run(&input, u64::MAX), Err(PrecompileError::OutOfGas));
+        assert_eq!(
+            byzantium_run(&input, u64::MAX),
+            Err(PrecompileError::OutOfGas)
+        );
+
+        // Same for a modulus length that does not fit in memory.
+        let mut input = [0u8; 96];
+        input[64] = 0xff;
+        assert_eq!(berlin_run(&input, u64::MAX), Err(PrecompileError::OutOfGas));
+    }
+
+    #[test]
+    fn zero_modulus() {
+        let mut input = [0u8; 96 + 3];
+        input[31] = 1;
+        input[63] = 1;
+        input[95] = 1;
+        input[96] = 2;
+        input[97] = 2;
+        let output = berlin_run(&input, 200).unwrap();
+        assert_eq!(&output.bytes[..], &[0]);
+    }
+}
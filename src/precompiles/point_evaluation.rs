@@ -0,0 +1,122 @@
+use bytes::Bytes;
+use c_kzg::{Bytes32, Bytes48, KzgProof};
+use sha2::{Digest, Sha256};
+
+use crate::domain::primitives::{Address, B256};
+
+use super::{PrecompileError, PrecompileOutput, PrecompileResult};
+
+pub const ADDRESS: Address = Address::with_last_byte(0x0a);
+
+const GAS: u64 = 50_000;
+
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// `FIELD_ELEMENTS_PER_BLOB || BLS_MODULUS`, each as a 32 byte word.
+const RETURN_VALUE: [u8; 64] = {
+    let mut output = [0u8; 64];
+    output[30] = 0x10;
+    let modulus = [
+        0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8,
+        0x05, 0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00,
+        0x00, 0x01,
+    ];
+    let mut i = 0;
+    while i < 32 {
+        output[32 + i] = modulus[i];
+        i += 1;
+    }
+    output
+};
+
+/// The versioned hash of a blob commitment (EIP-4844).
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> B256 {
+    let mut hash: B256 = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// Input is `versioned_hash || z || y || commitment || proof`, verifies that the blob behind
+/// the commitment evaluates to `y` at `z`.
+pub fn run(input: &[u8], gas_limit: u64) -> PrecompileResult {
+    if GAS > gas_limit {
+        return Err(PrecompileError::OutOfGas);
+    }
+    if input.len() != 192 {
+        return Err(PrecompileError::BlobInvalidInputLength);
+    }
+
+    let versioned_hash = &input[..32];
+    let commitment = &input[96..144];
+    if kzg_to_versioned_hash(commitment) != versioned_hash {
+        return Err(PrecompileError::BlobMismatchedVersion);
+    }
+
+    let z = Bytes32::from_bytes(&input[32..64]).unwrap();
+    let y = Bytes32::from_bytes(&input[64..96]).unwrap();
+    let commitment = Bytes48::from_bytes(commitment).unwrap();
+    let proof = Bytes48::from_bytes(&input[144..]).unwrap();
+    match KzgProof::verify_kzg_proof(&commitment, &z, &y, &proof, c_kzg::ethereum_kzg_settings()) {
+        Ok(true) => Ok(PrecompileOutput::new(
+            GAS,
+            Bytes::from_static(&RETURN_VALUE),
+        )),
+        _ => Err(PrecompileError::BlobVerifyKzgProofFailed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_input() -> Vec<u8> {
+        let commitment = hex::decode("8f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7").unwrap();
+        let mut input = kzg_to_versioned_hash(&commitment).to_vec();
+        input.extend(
+            hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000")
+                .unwrap(),
+        );
+        input.extend(
+            hex::decode("1522a4a7f34e1ea350ae07c29c96c7e79655aa926122e95fe69fcbd932ca49e9")
+                .unwrap(),
+        );
+        input.extend(commitment);
+        input.extend(hex::decode("a62ad71d14c5719385c0686f1871430475bf3a00f0aa3f7b8dd99a9abc2160744faf0070725e00b60ad9a026a15b1a8c").unwrap());
+        input
+    }
+
+    #[test]
+    fn valid_proof() {
+        let input = valid_input();
+        let output = run(&input, GAS).unwrap();
+        assert_eq!(output.gas_used, GAS);
+        assert_eq!(
+            hex::encode(output.bytes),
+            "0000000000000000000000000000000000000000000000000000000000001000\
+            73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+        );
+        assert_eq!(run(&input, GAS - 1), Err(PrecompileError::OutOfGas));
+    }
+
+    #[test]
+    fn invalid_input() {
+        let mut input = valid_input();
+        assert_eq!(
+            run(&input[..191], GAS),
+            Err(PrecompileError::BlobInvalidInputLength)
+        );
+
+        // y does not match the proof.
+        input[95] ^= 1;
+        assert_eq!(
+            run(&input, GAS),
+            Err(PrecompileError::BlobVerifyKzgProofFailed)
+        );
+
+        input[0] = 0x02;
+        assert_eq!(
+            run(&input, GAS),
+            Err(PrecompileError::BlobMismatchedVersion)
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Execution traces in the formats other clients produce, so they can be diffed against ours.
+
+pub mod call;
+pub mod eip3155;
+pub mod prestate;
+
+use bytes::Bytes;
+use serde::Serializer;
+
+use crate::{
+    domain::primitives::B256,
+    interpreter::{stack::StackError, InstructionResult},
+};
+
+/// The geth error message for the halt reasons it shares with us.
+pub fn error_name(result: InstructionResult) -> String {
+    match result {
+        InstructionResult::Revert => "execution reverted".to_string(),
+        InstructionResult::OutOfGas => "out of gas".to_string(),
+        InstructionResult::StackError(StackError::Underflow) => "stack underflow".to_string(),
+        InstructionResult::StackError(StackError::Overflow) => "stack limit reached".to_string(),
+        InstructionResult::InvalidFEOpcode | InstructionResult::OpcodeNotFound => {
+            "invalid opcode".to_string()
+        }
+        InstructionResult::InvalidJump => "invalid jump destination".to_string(),
+        InstructionResult::StateChangeDuringStaticCall => "write protection".to_string(),
+        InstructionResult::ReturnDataOutOfBounds => "return data out of bounds".to_string(),
+        InstructionResult::CallTooDeep => "max call depth exceeded".to_string(),
+        InstructionResult::OutOfFunds => "insufficient balance for transfer".to_string(),
+        InstructionResult::NonceOverflow => "nonce uint64 overflow".to_string(),
+        InstructionResult::CreateCollision => "contract address collision".to_string(),
+        InstructionResult::CreateContractSizeLimit => "max code size exceeded".to_string(),
+        InstructionResult::CreateContractStartingWithEF => {
+            "invalid code: must not begin with 0xef".to_string()
+        }
+        InstructionResult::CreateInitCodeSizeLimit => "max initcode size exceeded".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+fn serialize_hex_u64<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{value:#x}"))
+}
+
+fn serialize_bytes<S: Serializer>(value: &Bytes, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{}", hex::encode(value)))
+}
+
+fn serialize_b256<S: Serializer>(value: &B256, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{}", hex::encode(value)))
+}
+
+fn serialize_opt_b256<S: Serializer>(
+    value: &Option<B256>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serialize_b256(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
@@ -0,0 +1,517 @@
+//! The `callTracer` of geth: the tree of calls and creates a transaction made.
+
+use bytes::Bytes;
+use ruint::aliases::U256;
+use serde::{Serialize, Serializer};
+
+use crate::{
+    domain::{env::TxEnv, journal::JournaledState, primitives::Address, state::Log},
+    executor::ExecutionResult,
+    inspector::Inspector,
+    interpreter::host::{
+        CallInputs, CallKind, CallOutcome, CreateInputs, CreateOutcome, CreateScheme,
+    },
+};
+
+use super::{error_name, serialize_bytes, serialize_hex_u64};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CallType {
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+    Create,
+    Create2,
+    SelfDestruct,
+}
+
+impl From<CallKind> for CallType {
+    fn from(kind: CallKind) -> Self {
+        match kind {
+            CallKind::Call => Self::Call,
+            CallKind::CallCode => Self::CallCode,
+            CallKind::DelegateCall => Self::DelegateCall,
+            CallKind::StaticCall => Self::StaticCall,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    #[serde(rename = "type")]
+    pub kind: CallType,
+    pub from: Address,
+    /// The code that ran, the created contract for creates that succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    /// Not set for DELEGATECALL and STATICCALL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    #[serde(serialize_with = "serialize_hex_u64")]
+    pub gas: u64,
+    #[serde(serialize_with = "serialize_hex_u64")]
+    pub gas_used: u64,
+    #[serde(serialize_with = "serialize_bytes")]
+    pub input: Bytes,
+    /// The return data, the deployed code for creates.
+    #[serde(
+        skip_serializing_if = "Bytes::is_empty",
+        serialize_with = "serialize_bytes"
+    )]
+    pub output: Bytes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The message of a `Error(string)` revert.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrame>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<CallLog>,
+}
+
+impl CallFrame {
+    fn new(kind: CallType, from: Address, gas: u64, input: Bytes) -> Self {
+        Self {
+            kind,
+            from,
+            to: None,
+            value: None,
+            gas,
+            gas_used: 0,
+            input,
+            output: Bytes::new(),
+            error: None,
+            revert_reason: None,
+            calls: Vec::new(),
+            logs: Vec::new(),
+        }
+    }
+
+    /// The logs of a failed frame are not kept, nor are those of its sub calls.
+    fn clear_logs(&mut self) {
+        self.logs.clear();
+        for call in &mut self.calls {
+            call.clear_logs();
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct CallLog {
+    pub address: Address,
+    #[serde(serialize_with = "serialize_topics")]
+    pub topics: Vec<U256>,
+    #[serde(serialize_with = "serialize_bytes")]
+    pub data: Bytes,
+    /// The number of sub calls made before the log.
+    #[serde(serialize_with = "serialize_hex_u64")]
+    pub position: u64,
+}
+
+fn serialize_topics<S: Serializer>(topics: &[U256], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(topics.iter().map(|topic| format!("{topic:#066x}")))
+}
+
+/// The message of a revert with the output of `revert("...")` in solidity, ABI encoded as
+/// `Error(string)`.
+pub fn revert_reason(output: &[u8]) -> Option<String> {
+    const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+    let data = output.strip_prefix(&ERROR_SELECTOR)?;
+    let len = U256::from_be_slice(data.get(32..64)?);
+    let len = usize::try_from(len).ok()?;
+    let message = data.get(64..64usize.checked_add(len)?)?;
+    String::from_utf8(message.to_vec()).ok()
+}
+
+/// Builds the call tree as frames are entered and left.
+#[derive(Clone, Debug, Default)]
+pub struct CallTracer {
+    /// Whether to record the logs of each frame.
+    pub with_log: bool,
+    /// The frames that have not ended yet.
+    stack: Vec<CallFrame>,
+    root: Option<CallFrame>,
+}
+
+impl CallTracer {
+    pub fn new(with_log: bool) -> Self {
+        Self {
+            with_log,
+            ..Default::default()
+        }
+    }
+
+    /// The outermost frame, once it ended.
+    pub fn frame(&self) -> Option<&CallFrame> {
+        self.root.as_ref()
+    }
+
+    pub fn into_frame(self) -> Option<CallFrame> {
+        self.root
+    }
+
+    fn end_frame(&mut self, frame: CallFrame) {
+        match self.stack.last_mut() {
+            Some(parent) => parent.calls.push(frame),
+            None => self.root = Some(frame),
+        }
+    }
+}
+
+impl Inspector for CallTracer {
+    fn call(
+        &mut self,
+        inputs: &mut CallInputs,
+        _state: &mut JournaledState,
+    ) -> Option<CallOutcome> {
+        // CALLCODE and DELEGATECALL run in the frame of the caller.
+        let (from, value) = match inputs.kind {
+            CallKind::Call => (inputs.caller, Some(inputs.value)),
+            CallKind::CallCode => (inputs.target_address, Some(inputs.value)),
+            CallKind::DelegateCall => (inputs.target_address, None),
+            CallKind::StaticCall => (inputs.caller, None),
+        };
+        let mut frame = CallFrame::new(
+            inputs.kind.into(),
+            from,
+            inputs.gas_limit,
+            inputs.input.clone(),
+        );
+        frame.to = Some(inputs.code_address);
+        frame.value = value;
+        self.stack.push(frame);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _inputs: &CallInputs,
+        outcome: &mut CallOutcome,
+        _state: &mut JournaledState,
+    ) {
+        let Some(mut frame) = self.stack.pop() else {
+            return;
+        };
+        frame.gas_used = outcome.gas.spent();
+        frame.output = outcome.output.clone();
+        if !outcome.result.is_ok() {
+            frame.error = Some(error_name(outcome.result));
+            frame.revert_reason = revert_reason(&outcome.output);
+            frame.clear_logs();
+        }
+        self.end_frame(frame);
+    }
+
+    fn create(
+        &mut self,
+        inputs: &mut CreateInputs,
+        _state: &mut JournaledState,
+    ) -> Option<CreateOutcome> {
+        let kind = match inputs.scheme {
+            CreateScheme::Create => CallType::Create,
+            CreateScheme::Create2 { .. } => CallType::Create2,
+        };
+        let mut frame = CallFrame::new(
+            kind,
+            inputs.caller,
+            inputs.gas_limit,
+            inputs.init_code.clone(),
+        );
+        frame.value = Some(inputs.value);
+        self.stack.push(frame);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+        state: &mut JournaledState,
+    ) {
+        let Some(mut frame) = self.stack.pop() else {
+            return;
+        };
+        frame.gas_used = outcome.gas.spent();
+        frame.to = outcome.address;
+        match outcome.address {
+            Some(address) if outcome.result.is_ok() => frame.output = state.code(&address),
+            _ => {
+                frame.output = outcome.output.clone();
+                frame.error = Some(error_name(outcome.result));
+                frame.revert_reason = revert_reason(&outcome.output);
+                frame.clear_logs();
+            }
+        }
+        self.end_frame(frame);
+    }
+
+    fn log(&mut self, log: &Log) {
+        if !self.with_log {
+            return;
+        }
+        if let Some(frame) = self.stack.last_mut() {
+            frame.logs.push(CallLog {
+                address: log.address,
+                topics: log.topics.clone(),
+                data: log.data.clone(),
+                position: frame.calls.len() as u64,
+            });
+        }
+    }
+
+    fn selfdestruct(&mut self, address: Address, target: Address, value: U256) {
+        if let Some(frame) = self.stack.last_mut() {
+            let mut call = CallFrame::new(CallType::SelfDestruct, address, 0, Bytes::new());
+            call.to = Some(target);
+            call.value = Some(value);
+            frame.calls.push(call);
+        }
+    }
+
+    /// geth reports the gas limit of the transaction on the outermost frame, and the gas used
+    /// of the receipt, intrinsic gas and refunds included.
+    fn transaction_end(&mut self, tx: &TxEnv, result: &ExecutionResult) {
+        if let Some(root) = &mut self.root {
+            root.gas = tx.gas_limit;
+            root.gas_used = result.gas_used();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        bytecode,
+        domain::{
+            env::{BlockEnv, CfgEnv, Env, TransactTo},
+            state::{Account, State},
+        },
+        evm::Evm,
+        executor::Executor,
+        interpreter::{host::Host, opcodes},
+    };
+
+    use super::*;
+
+    const CALLER: Address = Address([0xca; 20]);
+    const A: Address = Address([0xaa; 20]);
+    const B: Address = Address([
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xbb,
+    ]);
+
+    #[test]
+    fn revert_reason() {
+        // revert("no")
+        let output = hex::decode(concat!(
+            "08c379a0",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "6e6f000000000000000000000000000000000000000000000000000000000000",
+        ))
+        .unwrap();
+        assert_eq!(super::revert_reason(&output), Some("no".to_string()));
+        assert_eq!(super::revert_reason(&output[..60]), None);
+        assert_eq!(super::revert_reason(&[]), None);
+    }
+
+    #[test]
+    fn call_tree() {
+        // LOG1 with topic 7, STATICCALL B, then DELEGATECALL B.
+        let a = Bytes::from_static(&[
+            opcodes::PUSH1,
+            0x07,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::LOG1,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH1,
+            0xbb,
+            opcodes::PUSH2,
+            0xff,
+            0xff,
+            opcodes::STATICCALL,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH1,
+            0xbb,
+            opcodes::PUSH2,
+            0xff,
+            0xff,
+            opcodes::DELEGATECALL,
+            opcodes::STOP,
+        ]);
+        // Logs and reverts with a single byte.
+        let b = Bytes::from_static(&[
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::LOG0,
+            opcodes::PUSH1,
+            0x01,
+            opcodes::PUSH0,
+            opcodes::REVERT,
+        ]);
+        let mut state = State::new();
+        state.insert(A, Account::with_code(a));
+        state.insert(B, Account::with_code(b));
+        let mut evm = Evm::with_inspector(Env::default(), state, CallTracer::new(true));
+        evm.call(CallInputs {
+            kind: CallKind::Call,
+            input: Bytes::from_static(&[0x12]),
+            gas_limit: 100_000,
+            target_address: A,
+            code_address: A,
+            caller: CALLER,
+            value: U256::ZERO,
+            transfers_value: true,
+            is_static: false,
+        });
+
+        let frame = evm.inspector.into_frame().unwrap();
+        assert_eq!(frame.calls.len(), 2);
+        // LOG0 is not allowed in a static frame.
+        let static_call = json!({
+            "type": "STATICCALL",
+            "from": A.to_string(),
+            "to": B.to_string(),
+            "gas": "0xffff",
+            "gasUsed": "0xffff",
+            "input": "0x",
+            "error": "write protection",
+        });
+        let delegate_call = json!({
+            "type": "DELEGATECALL",
+            "from": A.to_string(),
+            "to": B.to_string(),
+            // All but a 64th of what the static call left.
+            "gas": "0x7720",
+            "gasUsed": format!("{:#x}", 2 + 2 + 375 + 3 + 2 + 3),
+            "input": "0x",
+            "output": "0x00",
+            "error": "execution reverted",
+        });
+        assert_eq!(
+            serde_json::to_value(&frame).unwrap(),
+            json!({
+                "type": "CALL",
+                "from": CALLER.to_string(),
+                "to": A.to_string(),
+                "value": "0x0",
+                "gas": "0x186a0",
+                "gasUsed": format!("{:#x}", frame.gas_used),
+                "input": "0x12",
+                "calls": [static_call, delegate_call],
+                "logs": [{
+                    "address": A.to_string(),
+                    "topics": [format!("{:#066x}", 7)],
+                    "data": "0x",
+                    "position": "0x0",
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn creates_and_selfdestructs() {
+        // Deploys a contract returning the single byte 0x2a, then selfdestructs to B.
+        let init_code = [
+            opcodes::PUSH1,
+            0x2a,
+            opcodes::PUSH0,
+            opcodes::MSTORE8,
+            opcodes::PUSH1,
+            0x01,
+            opcodes::PUSH0,
+            opcodes::RETURN,
+        ];
+        let mut a = vec![opcodes::PUSH8];
+        a.extend(init_code);
+        a.extend([
+            opcodes::PUSH0,
+            opcodes::MSTORE,
+            opcodes::PUSH1,
+            0x08,
+            opcodes::PUSH1,
+            0x18,
+            opcodes::PUSH0,
+            opcodes::CREATE,
+            opcodes::POP,
+            opcodes::PUSH1,
+            0xbb,
+            opcodes::SELFDESTRUCT,
+        ]);
+        let mut state = State::new();
+        let mut account = Account::with_code(Bytes::from(a));
+        account.balance = U256::from(5);
+        state.insert(A, account);
+        let mut evm = Evm::with_inspector(Env::default(), state, CallTracer::default());
+        evm.call(CallInputs {
+            gas_limit: 100_000,
+            target_address: A,
+            code_address: A,
+            caller: CALLER,
+            transfers_value: true,
+            ..Default::default()
+        });
+
+        let frame = evm.inspector.frame().unwrap();
+        assert_eq!(frame.error, None);
+        let [create, selfdestruct] = &frame.calls[..] else {
+            panic!("{:?}", frame.calls);
+        };
+        assert_eq!(create.kind, CallType::Create);
+        assert_eq!(create.from, A);
+        assert_eq!(create.to, Some(A.create(0)));
+        assert_eq!(create.input[..], init_code);
+        assert_eq!(create.output[..], [0x2a]);
+        assert_eq!(create.gas_used, 3 + 2 + 3 + 3 + 3 + 2 + 200);
+        assert_eq!(selfdestruct.kind, CallType::SelfDestruct);
+        assert_eq!(selfdestruct.from, A);
+        assert_eq!(selfdestruct.to, Some(B));
+        assert_eq!(selfdestruct.value, Some(U256::from(5)));
+    }
+
+    #[test]
+    fn transaction_gas() {
+        // Clears a slot, for a refund.
+        let mut account = Account::with_code(bytecode!(PUSH0 PUSH1 1 SSTORE));
+        account.storage.insert(U256::from(1), U256::from(1));
+        let mut state = State::new();
+        state.insert(
+            CALLER,
+            Account::with_balance(U256::from(10).pow(U256::from(18))),
+        );
+        state.insert(A, account);
+        let tx = TxEnv {
+            caller: CALLER,
+            gas_limit: 100_000,
+            gas_price: U256::from(10),
+            transact_to: TransactTo::Call(A),
+            ..Default::default()
+        };
+        let block = BlockEnv {
+            gas_limit: 30_000_000,
+            ..Default::default()
+        };
+        let mut tracer = CallTracer::default();
+        let result = Executor::new(CfgEnv::default())
+            .transact_with_inspector(&tx, &block, &mut state, &mut tracer)
+            .unwrap();
+
+        let frame = tracer.into_frame().unwrap();
+        assert_eq!(frame.gas, 100_000);
+        // The intrinsic gas, a cold SSTORE reset, less the refund for clearing the slot.
+        assert_eq!(frame.gas_used, 21_000 + 2 + 3 + 5000 - 4800);
+        assert_eq!(frame.gas_used, result.gas_used());
+    }
+}
@@ -0,0 +1,347 @@
+//! EIP-3155 JSON lines, as `evm --json` of geth prints them: one object per executed
+//! instruction, then a summary.
+
+use std::io::{self, Write};
+
+use bytes::Bytes;
+use ruint::aliases::U256;
+use serde::{Serialize, Serializer};
+
+use crate::{
+    domain::{journal::JournaledState, primitives::B256},
+    inspector::Inspector,
+    interpreter::{
+        host::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
+        opcodes::Opcode,
+        InstructionResult, Interpreter,
+    },
+};
+
+use super::{error_name, serialize_hex_u64};
+
+/// The state before an instruction, and what it cost.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Step {
+    pub pc: usize,
+    pub op: u8,
+    #[serde(serialize_with = "serialize_hex_u64")]
+    pub gas: u64,
+    /// Static and dynamic cost together.
+    #[serde(serialize_with = "serialize_hex_u64")]
+    pub gas_cost: u64,
+    pub mem_size: usize,
+    /// From the bottom of the stack to the top.
+    pub stack: Vec<U256>,
+    /// 1 for the outermost frame.
+    pub depth: usize,
+    pub refund: i64,
+    pub op_name: String,
+    /// Set if the instruction halted the frame exceptionally.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The last line of a trace.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "super::serialize_opt_b256"
+    )]
+    pub state_root: Option<B256>,
+    #[serde(serialize_with = "serialize_output")]
+    pub output: Bytes,
+    #[serde(serialize_with = "serialize_hex_u64")]
+    pub gas_used: u64,
+    pub pass: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Summary {
+    pub fn new(result: InstructionResult, output: Bytes, gas_used: u64) -> Self {
+        Self {
+            state_root: None,
+            output,
+            gas_used,
+            pass: result.is_ok(),
+            error: (!result.is_ok()).then(|| error_name(result)),
+        }
+    }
+}
+
+/// geth prints the output without the `0x` prefix.
+fn serialize_output<S: Serializer>(value: &Bytes, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(value))
+}
+
+/// The name geth gives to the opcode byte.
+pub fn op_name(op: u8) -> String {
+    match Opcode::new(op).and_then(|op| op.to_str()) {
+        Some(name) => name.to_string(),
+        None => format!("opcode {op:#04x} not defined"),
+    }
+}
+
+/// A step that has not ended yet.
+#[derive(Debug)]
+struct Pending {
+    step: Step,
+    /// What a frame the step opened gave back of the gas it was charged for.
+    returned: u64,
+    /// The lines of that frame, they go after the line of the step.
+    lines: Vec<Step>,
+}
+
+/// A inspector writing a line to `out` for every instruction, of nested frames as well. As with
+/// geth the line of a CALL or CREATE comes before the lines of the frame it opens, and its cost
+/// includes all the gas it passed on.
+#[derive(Debug)]
+pub struct Tracer<W> {
+    out: W,
+    /// One per frame.
+    steps: Vec<Pending>,
+    /// The first write that failed, writes stop after it.
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Tracer<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            steps: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    /// Writes the summary, or returns the error of a earlier write.
+    pub fn summary(&mut self, summary: &Summary) -> io::Result<()> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        write_line(&mut self.out, summary)
+    }
+}
+
+fn write_line(mut out: impl Write, line: &impl Serialize) -> io::Result<()> {
+    serde_json::to_writer(&mut out, line)?;
+    writeln!(out)
+}
+
+impl<W: Write> Inspector for Tracer<W> {
+    fn step(&mut self, interpreter: &mut Interpreter, state: &mut JournaledState) {
+        // Running off the end of the code is a STOP.
+        let op = interpreter
+            .bytecode
+            .bytes_slice()
+            .get(interpreter.pc)
+            .copied()
+            .unwrap_or(0);
+        self.steps.push(Pending {
+            step: Step {
+                pc: interpreter.pc,
+                op,
+                gas: interpreter.gas.remaining(),
+                gas_cost: 0,
+                mem_size: interpreter.memory.len(),
+                stack: interpreter.stack.data().to_vec(),
+                depth: state.depth,
+                refund: interpreter.gas.refunded(),
+                op_name: op_name(op),
+                error: None,
+            },
+            returned: 0,
+            lines: Vec::new(),
+        });
+    }
+
+    fn step_end(&mut self, interpreter: &mut Interpreter, _state: &mut JournaledState) {
+        let Some(Pending {
+            mut step,
+            returned,
+            lines,
+        }) = self.steps.pop()
+        else {
+            return;
+        };
+        step.gas_cost = step.gas - interpreter.gas.remaining() + returned;
+        if interpreter.instruction_result.is_error() {
+            step.error = Some(error_name(interpreter.instruction_result));
+        }
+        match self.steps.last_mut() {
+            Some(parent) => {
+                parent.lines.push(step);
+                parent.lines.extend(lines);
+            }
+            None => {
+                for line in std::iter::once(step).chain(lines) {
+                    if self.error.is_none() {
+                        self.error = write_line(&mut self.out, &line).err();
+                    }
+                }
+            }
+        }
+    }
+
+    fn call_end(
+        &mut self,
+        _inputs: &CallInputs,
+        outcome: &mut CallOutcome,
+        _state: &mut JournaledState,
+    ) {
+        self.frame_end(outcome.result, outcome.gas.remaining());
+    }
+
+    fn create_end(
+        &mut self,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+        _state: &mut JournaledState,
+    ) {
+        self.frame_end(outcome.result, outcome.gas.remaining());
+    }
+}
+
+impl<W> Tracer<W> {
+    /// Notes the gas a frame hands back to the step that opened it.
+    fn frame_end(&mut self, result: InstructionResult, remaining: u64) {
+        if !(result.is_ok() || result.is_revert() || result.is_aborted()) {
+            return;
+        }
+        if let Some(parent) = self.steps.last_mut() {
+            parent.returned += remaining;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        domain::{
+            env::Env,
+            primitives::Address,
+            state::{Account, State},
+        },
+        evm::Evm,
+        interpreter::{gas::Gas, opcodes},
+    };
+
+    use super::*;
+
+    fn trace(code: &'static [u8], gas: u64) -> Vec<serde_json::Value> {
+        let mut interpreter = Interpreter::new(Bytes::from_static(code));
+        interpreter.gas = Gas::new(gas);
+        let mut evm = Evm::with_inspector(Env::default(), State::new(), Tracer::new(Vec::new()));
+        let result = evm.run_frame(&mut interpreter);
+        let mut tracer = evm.inspector;
+        tracer
+            .summary(&Summary::new(
+                result,
+                interpreter.output.clone(),
+                interpreter.gas.spent(),
+            ))
+            .unwrap();
+        String::from_utf8(tracer.into_inner())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn steps() {
+        // PUSH1 2, PUSH1 0, MSTORE, then off the end of the code.
+        let lines = trace(&[0x60, 0x02, 0x60, 0x00, 0x52], 100);
+        assert_eq!(lines.len(), 5);
+        assert_eq!(
+            lines[0],
+            serde_json::json!({
+                "pc": 0, "op": 0x60, "gas": "0x64", "gasCost": "0x3", "memSize": 0,
+                "stack": [], "depth": 1, "refund": 0, "opName": "PUSH1"
+            })
+        );
+        assert_eq!(lines[2]["stack"], serde_json::json!(["0x2", "0x0"]));
+        assert_eq!(lines[2]["opName"], "MSTORE");
+        assert_eq!(lines[2]["gasCost"], "0x6");
+        assert_eq!(lines[3]["pc"], 5);
+        assert_eq!(lines[3]["opName"], "STOP");
+        assert_eq!(lines[3]["memSize"], 32);
+        assert_eq!(lines[3]["gas"], "0x58");
+        assert_eq!(
+            lines[4],
+            serde_json::json!({"output": "", "gasUsed": "0xc", "pass": true})
+        );
+    }
+
+    #[test]
+    fn nested_call() {
+        let mut state = State::new();
+        state.insert(
+            Address::with_last_byte(0xbb),
+            Account::with_code(Bytes::from_static(&[opcodes::PUSH0, opcodes::STOP])),
+        );
+        let mut interpreter = Interpreter::new(Bytes::from_static(&[
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH0,
+            opcodes::PUSH1,
+            0xbb,
+            opcodes::PUSH2,
+            0x10,
+            0x00,
+            opcodes::CALL,
+        ]));
+        interpreter.gas = Gas::new(100_000);
+        let mut evm = Evm::with_inspector(Env::default(), state, Tracer::new(Vec::new()));
+        assert_eq!(evm.run_frame(&mut interpreter), InstructionResult::Stop);
+        let lines: Vec<serde_json::Value> = String::from_utf8(evm.inspector.into_inner())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        let ops: Vec<_> = lines
+            .iter()
+            .map(|line| {
+                (
+                    line["opName"].as_str().unwrap(),
+                    line["depth"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            ops[7..],
+            [("CALL", 1), ("PUSH0", 2), ("STOP", 2), ("STOP", 1)]
+        );
+        // A cold account and the gas passed on, what the callee leaves is not taken off.
+        assert_eq!(lines[7]["gasCost"], format!("{:#x}", 2600 + 0x1000));
+        assert_eq!(lines[8]["gas"], "0x1000");
+        assert_eq!(
+            lines[10]["gas"],
+            format!("{:#x}", 100_000 - 5 * 2 - 3 - 3 - 2600 - 2)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let lines = trace(&[0x60, 0x02, 0x01], 100);
+        assert_eq!(lines[1]["error"], "stack underflow");
+        assert_eq!(lines[2]["pass"], false);
+        assert_eq!(lines[2]["error"], "stack underflow");
+
+        let lines = trace(&[0x0c], 100);
+        assert_eq!(lines[0]["opName"], "opcode 0x0c not defined");
+        assert_eq!(lines[0]["error"], "invalid opcode");
+
+        let lines = trace(&[0x60, 0x02], 2);
+        assert_eq!(lines[0]["error"], "out of gas");
+    }
+}
@@ -0,0 +1,327 @@
+//! The `prestateTracer` of geth: the accounts a transaction touched as they were before it, or in
+//! diff mode what it changed about them.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use bytes::Bytes;
+use ruint::aliases::U256;
+use serde::{Serialize, Serializer};
+
+use crate::{
+    domain::{
+        journal::JournaledState,
+        primitives::Address,
+        state::{Account, State},
+    },
+    inspector::Inspector,
+    interpreter::{
+        host::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
+        opcodes, Interpreter,
+    },
+};
+
+use super::serialize_bytes;
+
+/// What the trace shows of a account, fields that are not set are left out.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct AccountState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_opt_bytes"
+    )]
+    pub code: Option<Bytes>,
+    #[serde(
+        skip_serializing_if = "BTreeMap::is_empty",
+        serialize_with = "serialize_storage"
+    )]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+fn serialize_opt_bytes<S: Serializer>(
+    value: &Option<Bytes>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serialize_bytes(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Keys and values as 32 byte hashes.
+fn serialize_storage<S: Serializer>(
+    storage: &BTreeMap<U256, U256>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(
+        storage
+            .iter()
+            .map(|(key, value)| (format!("{key:#066x}"), format!("{value:#066x}"))),
+    )
+}
+
+/// The output of the diff mode.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct StateDiff {
+    /// The changed accounts as they were, without those the transaction created.
+    pub pre: BTreeMap<Address, AccountState>,
+    /// Only what changed of the accounts, without those the transaction removed.
+    pub post: BTreeMap<Address, AccountState>,
+}
+
+fn storage_value(account: Option<&Account>, key: &U256) -> U256 {
+    account
+        .and_then(|account| account.storage.get(key))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// `account` with the `slots` of its storage, the balance is always shown.
+fn account_state<'a>(
+    account: Option<&Account>,
+    slots: impl IntoIterator<Item = &'a U256>,
+) -> AccountState {
+    let default = Account::default();
+    let value = account.unwrap_or(&default);
+    AccountState {
+        balance: Some(value.balance),
+        nonce: (value.nonce != 0).then_some(value.nonce),
+        code: (!value.code.is_empty()).then(|| value.code.clone()),
+        storage: slots
+            .into_iter()
+            .map(|key| (*key, storage_value(account, key)))
+            .collect(),
+    }
+}
+
+/// Records which accounts and storage slots are touched, the states before and after are then
+/// looked up in the ones given to [`PrestateTracer::prestate`] and [`PrestateTracer::diff`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PrestateTracer {
+    /// The touched accounts with the touched slots of each.
+    pub accounts: BTreeMap<Address, BTreeSet<U256>>,
+}
+
+impl PrestateTracer {
+    /// Adds a account the execution does not see, e.g. the coinbase that is paid for the gas.
+    pub fn touch(&mut self, address: Address) {
+        self.accounts.entry(address).or_default();
+    }
+
+    fn touch_slot(&mut self, address: Address, key: U256) {
+        self.accounts.entry(address).or_default().insert(key);
+    }
+
+    /// The touched accounts as they are in `pre`, including those that do not exist.
+    pub fn prestate(&self, pre: &State) -> BTreeMap<Address, AccountState> {
+        self.accounts
+            .iter()
+            .map(|(address, slots)| (*address, account_state(pre.get(address), slots)))
+            .collect()
+    }
+
+    /// The touched accounts that differ between `pre` and `post`.
+    pub fn diff(&self, pre: &State, post: &State) -> StateDiff {
+        let mut diff = StateDiff::default();
+        for (address, slots) in &self.accounts {
+            match (pre.get(address), post.get(address)) {
+                (None, None) => {}
+                (Some(account), None) => {
+                    diff.pre
+                        .insert(*address, account_state(Some(account), slots));
+                }
+                (None, Some(account)) => {
+                    let mut state = account_state(Some(account), slots);
+                    state.storage.retain(|_, value| !value.is_zero());
+                    diff.post.insert(*address, state);
+                }
+                (Some(before), Some(after)) => {
+                    let changed: Vec<_> = slots
+                        .iter()
+                        .filter(|key| {
+                            storage_value(Some(before), key) != storage_value(Some(after), key)
+                        })
+                        .collect();
+                    let post = AccountState {
+                        balance: (before.balance != after.balance).then_some(after.balance),
+                        nonce: (before.nonce != after.nonce).then_some(after.nonce),
+                        code: (before.code != after.code).then(|| after.code.clone()),
+                        storage: changed
+                            .iter()
+                            .map(|key| (**key, storage_value(Some(after), key)))
+                            .filter(|(_, value)| !value.is_zero())
+                            .collect(),
+                    };
+                    if post == AccountState::default() && changed.is_empty() {
+                        continue;
+                    }
+                    diff.pre
+                        .insert(*address, account_state(Some(before), changed));
+                    diff.post.insert(*address, post);
+                }
+            }
+        }
+        diff
+    }
+}
+
+impl Inspector for PrestateTracer {
+    fn step(&mut self, interpreter: &mut Interpreter, _state: &mut JournaledState) {
+        let Some(&op) = interpreter.bytecode.bytes_slice().get(interpreter.pc) else {
+            return;
+        };
+        let Some(&top) = interpreter.stack.data().last() else {
+            return;
+        };
+        match op {
+            opcodes::SLOAD | opcodes::SSTORE => self.touch_slot(interpreter.address, top),
+            opcodes::BALANCE
+            | opcodes::EXTCODESIZE
+            | opcodes::EXTCODECOPY
+            | opcodes::EXTCODEHASH => self.touch(Address::from_word(top)),
+            _ => {}
+        }
+    }
+
+    fn call(
+        &mut self,
+        inputs: &mut CallInputs,
+        _state: &mut JournaledState,
+    ) -> Option<CallOutcome> {
+        self.touch(inputs.caller);
+        self.touch(inputs.target_address);
+        self.touch(inputs.code_address);
+        None
+    }
+
+    fn create(
+        &mut self,
+        inputs: &mut CreateInputs,
+        _state: &mut JournaledState,
+    ) -> Option<CreateOutcome> {
+        self.touch(inputs.caller);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _inputs: &CreateInputs,
+        outcome: &mut CreateOutcome,
+        _state: &mut JournaledState,
+    ) {
+        if let Some(address) = outcome.address {
+            self.touch(address);
+        }
+    }
+
+    fn selfdestruct(&mut self, address: Address, target: Address, _value: U256) {
+        self.touch(address);
+        self.touch(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        domain::env::Env,
+        evm::Evm,
+        interpreter::{host::Host, InstructionResult},
+    };
+
+    use super::*;
+
+    const CALLER: Address = Address([0xca; 20]);
+    const A: Address = Address([0xaa; 20]);
+
+    fn run() -> (State, Evm<PrestateTracer>) {
+        // SLOAD 1, SSTORE 2 := 5, SSTORE 3 := 0 and BALANCE of 0xcc.
+        let code = Bytes::from_static(&[
+            opcodes::PUSH1,
+            0x01,
+            opcodes::SLOAD,
+            opcodes::POP,
+            opcodes::PUSH1,
+            0x05,
+            opcodes::PUSH1,
+            0x02,
+            opcodes::SSTORE,
+            opcodes::PUSH0,
+            opcodes::PUSH1,
+            0x03,
+            opcodes::SSTORE,
+            opcodes::PUSH1,
+            0xcc,
+            opcodes::BALANCE,
+            opcodes::STOP,
+        ]);
+        let mut a = Account::with_code(code);
+        a.storage.insert(U256::from(1), U256::from(7));
+        a.storage.insert(U256::from(3), U256::from(9));
+        let mut state = State::new();
+        state.insert(A, a);
+        state.insert(CALLER, Account::with_balance(U256::from(10)));
+
+        let mut evm = Evm::with_inspector(Env::default(), state.clone(), PrestateTracer::default());
+        let outcome = evm.call(CallInputs {
+            gas_limit: 100_000,
+            target_address: A,
+            code_address: A,
+            caller: CALLER,
+            value: U256::from(4),
+            transfers_value: true,
+            ..Default::default()
+        });
+        assert_eq!(outcome.result, InstructionResult::Stop);
+        (state, evm)
+    }
+
+    fn word(value: u64) -> String {
+        format!("{:#066x}", value)
+    }
+
+    #[test]
+    fn prestate() {
+        let (pre, evm) = run();
+        let prestate = evm.inspector.prestate(&pre);
+        assert_eq!(
+            serde_json::to_value(prestate).unwrap(),
+            json!({
+                Address::with_last_byte(0xcc).to_string(): {"balance": "0x0"},
+                A.to_string(): {
+                    "balance": "0x0",
+                    "code": format!("0x{}", hex::encode(&pre[&A].code)),
+                    "storage": {word(1): word(7), word(2): word(0), word(3): word(9)},
+                },
+                CALLER.to_string(): {"balance": "0xa"},
+            })
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let (pre, evm) = run();
+        let diff = evm.inspector.diff(&pre, &evm.journaled_state.state);
+        assert_eq!(
+            serde_json::to_value(diff).unwrap(),
+            json!({
+                "pre": {
+                    A.to_string(): {
+                        "balance": "0x0",
+                        "code": format!("0x{}", hex::encode(&pre[&A].code)),
+                        "storage": {word(2): word(0), word(3): word(9)},
+                    },
+                    CALLER.to_string(): {"balance": "0xa"},
+                },
+                "post": {
+                    A.to_string(): {"balance": "0x4", "storage": {word(2): word(5)}},
+                    CALLER.to_string(): {"balance": "0x6"},
+                },
+            })
+        );
+    }
+}